@@ -2,13 +2,13 @@ extern crate proc_macro;
 
 use heck::TitleCase;
 use proc_macro::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote};
 use syn::{
     self, DeriveInput, Fields, Variant, parse_macro_input,
     punctuated::Punctuated, token::Comma,
 };
 
-#[proc_macro_derive(RawUi)]
+#[proc_macro_derive(RawUi, attributes(range))]
 pub fn raw_ui_derive(input: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(input as DeriveInput);
 
@@ -27,15 +27,62 @@ fn impl_raw_ui_struct(ast: &syn::DeriveInput, fields: &Fields) -> proc_macro2::T
 
     let name = &ast.ident;
 
-    let draw_fields = fields.iter().filter_map(|f| {
-        if f.ident.as_ref().unwrap().to_string().starts_with('_') {
-            None
-        } else {
+    let fields: Vec<_> = fields
+        .iter()
+        .filter(|f| !f.ident.as_ref().unwrap().to_string().starts_with('_'))
+        .collect();
+
+    // `#[range(...)]` : bornes du jeu, le champ passe par `Ranged`
+    let ranged_fields = fields.iter().filter_map(|f| {
+        let attr = f.attrs.iter().find(|attr| attr.path.is_ident("range"))?;
+        let range: syn::Expr = attr.parse_args().expect("expected `#[range(<range>)]`");
+        let field_name = &f.ident;
+        let local = format_ident!("ranged_{}", field_name.as_ref().unwrap());
+        Some(quote! {
+            let mut #local = crate::save_data::Ranged(&mut self.#field_name, #range);
+        })
+    });
+
+    // Mêmes bornes pour le rapport, sans passer par l'UI
+    let range_checks: Vec<_> = fields
+        .iter()
+        .filter_map(|f| {
+            let attr = f.attrs.iter().find(|attr| attr.path.is_ident("range"))?;
+            let range: syn::Expr = attr.parse_args().expect("expected `#[range(<range>)]`");
             let field_name = &f.ident;
-            let field_string = field_name.as_ref().unwrap().to_string().to_title_case();
+            let field_string = field_name.as_ref().unwrap().to_string();
             Some(quote! {
-                (&mut self.#field_name as &mut dyn crate::save_data::RawUi, #field_string)
+                if let Some(error) = crate::save_data::shared::limits::range_error(&self.#field_name, &#range) {
+                    errors.push((#field_string, error));
+                }
             })
+        })
+        .collect();
+
+    let range_errors = if range_checks.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            fn range_errors(&self) -> Vec<(&'static str, String)> {
+                let mut errors = Vec::new();
+                #(#range_checks)*
+                errors
+            }
+        }
+    };
+
+    let draw_fields = fields.iter().map(|f| {
+        let field_name = &f.ident;
+        let field_string = field_name.as_ref().unwrap().to_string().to_title_case();
+        if f.attrs.iter().any(|attr| attr.path.is_ident("range")) {
+            let local = format_ident!("ranged_{}", field_name.as_ref().unwrap());
+            quote! {
+                (&mut #local as &mut dyn crate::save_data::RawUi, #field_string)
+            }
+        } else {
+            quote! {
+                (&mut self.#field_name as &mut dyn crate::save_data::RawUi, #field_string)
+            }
         }
     });
 
@@ -43,9 +90,12 @@ fn impl_raw_ui_struct(ast: &syn::DeriveInput, fields: &Fields) -> proc_macro2::T
         #[automatically_derived]
         impl crate::save_data::RawUi for #name {
             fn draw_raw_ui(&mut self, gui: &crate::gui::Gui, ident: &str) {
+                #(#ranged_fields)*
                 let mut fields = [#(#draw_fields),*];
                 gui.draw_struct(ident, &mut fields);
             }
+
+            #range_errors
         }
     }
 }
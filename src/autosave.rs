@@ -0,0 +1,58 @@
+// Minuteur de sauvegarde auto, l'horloge est fournie par l'appelant (`ui.time()`)
+#[derive(Default)]
+pub struct AutosaveTimer {
+    last_edit: Option<f64>,
+}
+
+impl AutosaveTimer {
+    // Chaque modification repousse la sauvegarde
+    pub fn edited(&mut self, now: f64) {
+        self.last_edit = Some(now);
+    }
+
+    pub fn cancel(&mut self) {
+        self.last_edit = None;
+    }
+
+    // `true` une seule fois par série de modifications, après `interval` secondes d'inactivité
+    pub fn poll(&mut self, now: f64, interval: Option<u32>) -> bool {
+        match (self.last_edit, interval) {
+            (Some(last_edit), Some(interval)) if now - last_edit >= interval as f64 => {
+                self.last_edit = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn timer() {
+        let mut timer = AutosaveTimer::default();
+
+        // Rien à sauvegarder
+        assert!(!timer.poll(100.0, Some(10)));
+
+        timer.edited(0.0);
+        assert!(!timer.poll(5.0, Some(10)));
+
+        // Une nouvelle modification repousse l'échéance
+        timer.edited(5.0);
+        assert!(!timer.poll(10.0, Some(10)));
+        assert!(timer.poll(15.0, Some(10)));
+        assert!(!timer.poll(30.0, Some(10)));
+
+        // Désactivé
+        timer.edited(30.0);
+        assert!(!timer.poll(100.0, None));
+        assert!(timer.poll(100.0, Some(10)));
+
+        timer.edited(100.0);
+        timer.cancel();
+        assert!(!timer.poll(200.0, Some(10)));
+    }
+}
@@ -0,0 +1,14 @@
+#![warn(clippy::all)]
+
+use std::{env, process};
+
+use trilogy_save_editor::cli;
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    if let Err(err) = cli::run(&args) {
+        eprintln!("{:#}", err);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,803 @@
+use anyhow::{bail, Context, Error, Result};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    edit_log::{self, Change},
+    event_handler::{self, backup_path, GameVersion, SaveGame},
+    patch::JsonPatch,
+    plot_db,
+    preset::Preset,
+    save_data::{shared::plot::PlotId, summary::CharacterSummary},
+    save_slot,
+};
+
+const USAGE: &str = "Usage:
+    tse batch-convert <dir> --to json
+    tse set <file> <plot-name> <value> [--dry-run]
+    tse json-patch <file> <patch> [--dry-run]
+    tse apply-preset <preset> <dir> [--dry-run]
+    tse validate <dir>
+    tse ranges <file>
+    tse schema <file>
+    tse summary <dir> --csv <output>";
+
+// Sous-commandes de `tse`, l'éditeur les accepte aussi sans ouvrir de fenêtre
+const COMMANDS: [&str; 8] = [
+    "batch-convert",
+    "set",
+    "json-patch",
+    "apply-preset",
+    "validate",
+    "ranges",
+    "schema",
+    "summary",
+];
+
+#[derive(PartialEq, Eq, Debug)]
+pub enum Launch {
+    // Sauvegarde à ouvrir, passée par une association de fichiers
+    Gui(Option<String>),
+    Cli,
+}
+
+pub fn parse_launch(args: &[String]) -> Launch {
+    match args.first() {
+        Some(command) if COMMANDS.contains(&command.as_str()) => Launch::Cli,
+        file => Launch::Gui(file.cloned()),
+    }
+}
+
+// Sous-commandes qui modifient des sauvegardes
+const EDIT_COMMANDS: [&str; 3] = ["set", "json-patch", "apply-preset"];
+
+pub fn run(args: &[String]) -> Result<()> {
+    // `--dry-run` : modifications et vérifications en mémoire, rien n'est écrit
+    let dry_run = args.iter().any(|arg| arg == "--dry-run");
+    let args: Vec<String> = args.iter().filter(|arg| *arg != "--dry-run").cloned().collect();
+    if dry_run && !args.first().map_or(false, |command| EDIT_COMMANDS.contains(&command.as_str())) {
+        bail!("`--dry-run` only applies to `set`, `json-patch` and `apply-preset`");
+    }
+
+    match args.first().map(String::as_str) {
+        Some("batch-convert") => {
+            let (dir, format) = match &args[1..] {
+                [dir, to, format] if to == "--to" => (dir, format),
+                _ => bail!(USAGE),
+            };
+            let format = ExportFormat::parse(format)?;
+
+            let report = batch_convert(Path::new(dir), format)?;
+            for (path, game) in &report.converted {
+                println!("OK     {} ({})", path.display(), game);
+            }
+            for (path, err) in &report.failed {
+                println!("FAILED {} : {:#}", path.display(), err);
+            }
+            println!("{} converted, {} failed", report.converted.len(), report.failed.len());
+
+            if !report.failed.is_empty() {
+                bail!("{} save(s) could not be converted", report.failed.len());
+            }
+            Ok(())
+        }
+        Some("set") => {
+            let (file, name, value) = match &args[1..] {
+                [file, name, value] => (file, name, value),
+                _ => bail!(USAGE),
+            };
+
+            let (plot_id, changes) = set_plot(Path::new(file), name, value, dry_run)?;
+            print!("{}", changes_text(&changes));
+            println!("{} ({:?}) = {}", name, plot_id, value);
+            if dry_run {
+                println!("Dry run, `{}` not written", file);
+            }
+            Ok(())
+        }
+        Some("json-patch") => {
+            let (file, patch) = match &args[1..] {
+                [file, patch] => (file, patch),
+                _ => bail!(USAGE),
+            };
+
+            let changes = json_patch(Path::new(file), Path::new(patch), dry_run)?;
+            print!("{}", changes_text(&changes));
+            println!("{} field(s) changed in {}", changes.len(), file);
+            if dry_run {
+                println!("Dry run, `{}` not written", file);
+            }
+            Ok(())
+        }
+        Some("apply-preset") => {
+            let (preset, dir) = match &args[1..] {
+                [preset, dir] => (preset, dir),
+                _ => bail!(USAGE),
+            };
+            let preset = Preset::load(Path::new(preset))?;
+
+            let report = apply_preset(Path::new(dir), &preset, dry_run)?;
+            for ((path, game), changes) in report.applied.iter().zip(&report.changes) {
+                println!("OK      {} ({:?})", path.display(), game);
+                if dry_run {
+                    print!("{}", changes_text(changes));
+                }
+            }
+            for (path, game) in &report.skipped {
+                println!("SKIPPED {} ({:?})", path.display(), game);
+            }
+            for (path, err) in &report.failed {
+                println!("FAILED  {} : {:#}", path.display(), err);
+            }
+            println!(
+                "{} applied, {} skipped, {} failed",
+                report.applied.len(),
+                report.skipped.len(),
+                report.failed.len()
+            );
+            if dry_run {
+                println!("Dry run, no save written");
+            }
+
+            if !report.failed.is_empty() {
+                bail!("The preset could not be applied to {} save(s)", report.failed.len());
+            }
+            Ok(())
+        }
+        Some("validate") => {
+            let dir = match &args[1..] {
+                [dir] => dir,
+                _ => bail!(USAGE),
+            };
+
+            let report = validate(Path::new(dir))?;
+            for (path, game) in &report.passed {
+                println!("OK     {} ({:?})", path.display(), game);
+            }
+            for (path, err) in &report.failed {
+                println!("FAILED {} : {:#}", path.display(), err);
+            }
+            println!("{} passed, {} failed", report.passed.len(), report.failed.len());
+
+            if !report.failed.is_empty() {
+                bail!("{} save(s) are corrupt", report.failed.len());
+            }
+            Ok(())
+        }
+        Some("ranges") => {
+            let file = match &args[1..] {
+                [file] => file,
+                _ => bail!(USAGE),
+            };
+
+            let report = ranges(Path::new(file))?;
+            for (path, error) in &report {
+                println!("{} : {}", path, error);
+            }
+            if !report.is_empty() {
+                bail!("{} field(s) out of range", report.len());
+            }
+            println!("All fields in range");
+            Ok(())
+        }
+        Some("schema") => {
+            let file = match &args[1..] {
+                [file] => file,
+                _ => bail!(USAGE),
+            };
+
+            let schema = schema(Path::new(file))?;
+            println!("{}", serde_json::to_string_pretty(&schema)?);
+            Ok(())
+        }
+        Some("summary") => {
+            let (dir, output) = match &args[1..] {
+                [dir, csv, output] if csv == "--csv" => (dir, output),
+                _ => bail!(USAGE),
+            };
+
+            let report = summary(Path::new(dir))?;
+            fs::write(output, report.to_csv())
+                .with_context(|| format!("Failed to write `{}`", output))?;
+            for (path, err) in &report.failed {
+                println!("FAILED {} : {:#}", path.display(), err);
+            }
+            println!("{} summarized, {} failed", report.rows.len(), report.failed.len());
+
+            if !report.failed.is_empty() {
+                bail!("{} save(s) could not be read", report.failed.len());
+            }
+            Ok(())
+        }
+        _ => bail!(USAGE),
+    }
+}
+
+fn load_save(path: &Path) -> Result<SaveGame> {
+    let input = fs::read(path).with_context(|| format!("Failed to open `{}`", path.display()))?;
+    SaveGame::load(path.to_string_lossy().into_owned(), &input)
+}
+
+// Modifie et vérifie la sauvegarde en mémoire, le fichier n'est écrit qu'en dehors
+// d'un `--dry-run`, l'original est alors sauvegardé en `.bak`
+fn edit_save(
+    path: &Path, mut save_game: SaveGame, dry_run: bool,
+    edit: impl FnOnce(&mut SaveGame) -> Result<()>,
+) -> Result<Vec<Change>> {
+    let before = save_game.to_json_value()?;
+    edit(&mut save_game)?;
+    let output = save_game.self_check()?;
+    let changes = edit_log::diff(&before, &save_game.to_json_value()?);
+
+    if !dry_run {
+        if let Some(backup) = backup_path(path) {
+            fs::copy(path, backup)?;
+        }
+        fs::write(path, output)?;
+    }
+    Ok(changes)
+}
+
+// Une ligne par champ modifié, comme le journal de l'éditeur
+pub fn changes_text(changes: &[Change]) -> String {
+    changes.iter().map(|change| format!("  {}\n", change)).collect()
+}
+
+// Modifie un plot par son nom
+pub fn set_plot(
+    path: &Path, name: &str, value: &str, dry_run: bool,
+) -> Result<(PlotId, Vec<Change>)> {
+    let save_game = load_save(path)?;
+    let plot_id = plot_db::find_plot(save_game.game_version(), name)?;
+    let changes =
+        edit_save(path, save_game, dry_run, |save_game| save_game.set_plot(plot_id, value))?;
+    Ok((plot_id, changes))
+}
+
+// Document RFC 6902 sur le JSON de `batch-convert`
+pub fn json_patch(path: &Path, patch: &Path, dry_run: bool) -> Result<Vec<Change>> {
+    let save_game = load_save(path)?;
+    let patch = fs::read_to_string(patch)
+        .with_context(|| format!("Failed to open `{}`", patch.display()))?;
+    let patch = JsonPatch::from_json(&patch)?;
+    edit_save(path, save_game, dry_run, |save_game| patch.apply(save_game))
+}
+
+#[derive(Default)]
+pub struct PresetReport {
+    pub applied: Vec<(PathBuf, GameVersion)>,
+    // Dans l'ordre de `applied`
+    pub changes: Vec<Vec<Change>>,
+    pub skipped: Vec<(PathBuf, GameVersion)>,
+    pub failed: Vec<(PathBuf, Error)>,
+}
+
+// Les sauvegardes d'un autre jeu sont ignorées, chaque fichier modifié est sauvegardé en `.bak`
+pub fn apply_preset(dir: &Path, preset: &Preset, dry_run: bool) -> Result<PresetReport> {
+    let mut files = Vec::new();
+    find_saves(dir, &mut files).with_context(|| format!("Failed to read `{}`", dir.display()))?;
+    files.sort();
+
+    let mut report = PresetReport::default();
+    for path in files {
+        let save_game = match fs::read(&path)
+            .map_err(Error::from)
+            .and_then(|input| SaveGame::load(path.to_string_lossy().into_owned(), &input))
+        {
+            Ok(save_game) => save_game,
+            Err(err) => {
+                report.failed.push((path, err));
+                continue;
+            }
+        };
+
+        let game = save_game.game_version();
+        if !preset.is_compatible(game) {
+            report.skipped.push((path, game));
+            continue;
+        }
+
+        match edit_save(&path, save_game, dry_run, |save_game| preset.apply(save_game)) {
+            Ok(changes) => {
+                report.applied.push((path, game));
+                report.changes.push(changes);
+            }
+            Err(err) => report.failed.push((path, err)),
+        }
+    }
+    Ok(report)
+}
+
+// Champs, types et positions de la sauvegarde, pas ses valeurs
+pub fn schema(path: &Path) -> Result<serde_json::Value> {
+    let input = fs::read(path).with_context(|| format!("Failed to open `{}`", path.display()))?;
+    SaveGame::load(path.to_string_lossy().into_owned(), &input)?.schema()
+}
+
+// Bornes des champs, celles que l'éditeur signale en rouge
+pub fn ranges(path: &Path) -> Result<Vec<(String, String)>> {
+    let input = fs::read(path).with_context(|| format!("Failed to open `{}`", path.display()))?;
+    Ok(SaveGame::load(path.to_string_lossy().into_owned(), &input)?.range_report())
+}
+
+#[derive(Default)]
+pub struct ValidateReport {
+    pub passed: Vec<(PathBuf, GameVersion)>,
+    pub failed: Vec<(PathBuf, Error)>,
+}
+
+// Mêmes vérifications que le navigateur de sauvegardes, puis lecture et réécriture complètes
+pub fn validate(dir: &Path) -> Result<ValidateReport> {
+    let mut files = Vec::new();
+    find_saves(dir, &mut files).with_context(|| format!("Failed to read `{}`", dir.display()))?;
+    files.sort();
+
+    let mut report = ValidateReport::default();
+    for path in files {
+        match validate_file(&path) {
+            Ok(game) => report.passed.push((path, game)),
+            Err(err) => report.failed.push((path, err)),
+        }
+    }
+    Ok(report)
+}
+
+fn validate_file(path: &Path) -> Result<GameVersion> {
+    let input = fs::read(path)?;
+    event_handler::quick_check(path, &input)?;
+    let save_game = SaveGame::load(path.to_string_lossy().into_owned(), &input)?;
+    save_game.self_check()?;
+    Ok(save_game.game_version())
+}
+
+const SUMMARY_COLUMNS: [&str; 10] = [
+    "file", "game", "name", "class", "level", "playtime", "paragon", "renegade", "credits",
+    "romances",
+];
+
+#[derive(Default)]
+pub struct SummaryReport {
+    pub rows: Vec<(PathBuf, GameVersion, Option<CharacterSummary>)>,
+    pub failed: Vec<(PathBuf, Error)>,
+}
+
+impl SummaryReport {
+    // Une ligne par sauvegarde lue, les colonnes du personnage restent vides pour ME1
+    pub fn to_csv(&self) -> String {
+        let mut csv = SUMMARY_COLUMNS.join(",");
+        csv.push('\n');
+
+        for (path, game, summary) in &self.rows {
+            let mut row = vec![path.to_string_lossy().into_owned(), format!("{:?}", game)];
+            match summary {
+                Some(summary) => row.extend(vec![
+                    summary.name.clone(),
+                    summary.class_name.clone().unwrap_or_default(),
+                    summary.level.to_string(),
+                    summary.playtime(),
+                    summary.paragon.to_string(),
+                    summary.renegade.to_string(),
+                    summary.credits.to_string(),
+                    summary.romances.join(" "),
+                ]),
+                None => row.resize(SUMMARY_COLUMNS.len(), String::new()),
+            }
+
+            let row: Vec<_> = row.iter().map(|field| csv_field(field)).collect();
+            csv.push_str(&row.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+// Entre guillemets seulement si nécessaire, les guillemets sont doublés
+fn csv_field(field: &str) -> String {
+    if field.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+// Personnage de chaque sauvegarde du dossier (et sous-dossiers)
+pub fn summary(dir: &Path) -> Result<SummaryReport> {
+    let mut files = Vec::new();
+    find_saves(dir, &mut files).with_context(|| format!("Failed to read `{}`", dir.display()))?;
+    files.sort();
+
+    let mut report = SummaryReport::default();
+    for path in files {
+        match fs::read(&path)
+            .map_err(Error::from)
+            .and_then(|input| SaveGame::load(path.to_string_lossy().into_owned(), &input))
+        {
+            Ok(save_game) => {
+                report.rows.push((path, save_game.game_version(), save_game.summary()))
+            }
+            Err(err) => report.failed.push((path, err)),
+        }
+    }
+    Ok(report)
+}
+
+#[derive(Clone, Copy)]
+pub enum ExportFormat {
+    Json,
+}
+
+impl ExportFormat {
+    fn parse(format: &str) -> Result<Self> {
+        match format {
+            "json" => Ok(ExportFormat::Json),
+            _ => bail!("Unknown format `{}`, expected `json`", format),
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct BatchReport {
+    pub converted: Vec<(PathBuf, String)>,
+    pub failed: Vec<(PathBuf, Error)>,
+}
+
+// Convertit toutes les sauvegardes du dossier (et sous-dossiers), l'export est écrit à côté
+pub fn batch_convert(dir: &Path, format: ExportFormat) -> Result<BatchReport> {
+    let mut files = Vec::new();
+    find_saves(dir, &mut files).with_context(|| format!("Failed to read `{}`", dir.display()))?;
+    files.sort();
+
+    let mut report = BatchReport::default();
+    for path in files {
+        match convert_file(&path, format) {
+            Ok(game) => report.converted.push((path, game)),
+            Err(err) => report.failed.push((path, err)),
+        }
+    }
+    Ok(report)
+}
+
+fn find_saves(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            find_saves(&path, files)?;
+        } else if save_slot::is_save(&path) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn convert_file(path: &Path, format: ExportFormat) -> Result<String> {
+    let input = fs::read(path)?;
+    let save_game = SaveGame::load(path.to_string_lossy().into_owned(), &input)?;
+
+    let output = match format {
+        ExportFormat::Json => save_game.to_json()?,
+    };
+
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".");
+    file_name.push(format.extension());
+    fs::write(path.with_file_name(file_name), output)?;
+
+    Ok(format!("{:?}", save_game.game_version()))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{event_handler::MainEvent, gui::UiEvent};
+
+    use super::*;
+
+    #[test]
+    fn batch_convert_to_json() -> Result<()> {
+        let dir = std::env::temp_dir().join("trilogy_save_editor_batch_convert");
+        if dir.exists() {
+            fs::remove_dir_all(&dir)?;
+        }
+        fs::create_dir_all(dir.join("ME3"))?;
+
+        fs::copy("test/ME2Save.pcsav", dir.join("ME2Save.pcsav"))?;
+        fs::copy("test/ME3Save.pcsav", dir.join("ME3").join("ME3Save.pcsav"))?;
+        fs::write(dir.join("Broken.pcsav"), b"not a save")?;
+        fs::write(dir.join("notes.txt"), b"ignored")?;
+
+        let report = batch_convert(&dir, ExportFormat::Json)?;
+
+        assert_eq!(report.converted.len(), 2);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].0, dir.join("Broken.pcsav"));
+
+        for json in &[dir.join("ME2Save.pcsav.json"), dir.join("ME3").join("ME3Save.pcsav.json")] {
+            let _: serde_json::Value = serde_json::from_str(&fs::read_to_string(json)?)?;
+        }
+        assert!(!dir.join("Broken.pcsav.json").exists());
+        assert!(!dir.join("notes.txt.json").exists());
+
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn launch_with_file() -> Result<()> {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        assert_eq!(parse_launch(&[]), Launch::Gui(None));
+        assert_eq!(parse_launch(&args(&["validate", "saves"])), Launch::Cli);
+
+        // Comme au lancement de l'éditeur
+        let open = |file: String| async move {
+            let (event_addr, event_rx) = flume::unbounded();
+            let (ui_addr, ui_rx) = flume::unbounded();
+            event_addr.send(MainEvent::OpenSave(file)).unwrap();
+            drop(event_addr);
+            event_handler::event_loop(event_rx, ui_addr).await;
+            ui_rx.drain().collect::<Vec<_>>()
+        };
+
+        let file = match parse_launch(&args(&["test/ME2Save.pcsav"])) {
+            Launch::Gui(Some(file)) => file,
+            launch => panic!("unexpected {:?}", launch),
+        };
+        let events = open(file).await;
+        assert!(events.iter().any(|event| matches!(event, UiEvent::OpenedSave(save_game)
+            if save_game.game_version() == GameVersion::MassEffect2)));
+
+        // Une erreur dans l'éditeur plutôt qu'un crash
+        let events = open("notes.txt".to_owned()).await;
+        assert!(events.iter().any(|event| matches!(event, UiEvent::Error(_))));
+        assert!(!events.iter().any(|event| matches!(event, UiEvent::OpenedSave(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn summary_csv() -> Result<()> {
+        let dir = std::env::temp_dir().join("trilogy_save_editor_summary");
+        if dir.exists() {
+            fs::remove_dir_all(&dir)?;
+        }
+        fs::create_dir_all(&dir)?;
+
+        let fixtures = [
+            "Clare00_AutoSave.MassEffectSave",
+            "ME1Leg00_QuickSave.pcsav",
+            "ME2Save.pcsav",
+            "ME2LegSave.pcsav",
+            "ME3Save.pcsav",
+        ];
+        for file in &fixtures {
+            fs::copy(Path::new("test").join(file), dir.join(file))?;
+        }
+
+        let report = summary(&dir)?;
+        assert!(report.failed.is_empty());
+        let csv = report.to_csv();
+        let lines: Vec<_> = csv.lines().collect();
+
+        assert_eq!(
+            lines[0],
+            "file,game,name,class,level,playtime,paragon,renegade,credits,romances"
+        );
+        assert_eq!(lines.len(), fixtures.len() + 1);
+        for line in &lines[1..] {
+            assert_eq!(line.split(',').count(), SUMMARY_COLUMNS.len(), "{}", line);
+        }
+        assert!(lines.iter().any(|line| line.contains(",MassEffect3,")));
+
+        assert_eq!(csv_field("Jane \"Commander\", N7"), "\"Jane \"\"Commander\"\", N7\"");
+
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn set() -> Result<()> {
+        let dir = std::env::temp_dir().join("trilogy_save_editor_set");
+        if dir.exists() {
+            fs::remove_dir_all(&dir)?;
+        }
+        fs::create_dir_all(&dir)?;
+        let path = dir.join("Save_0001.pcsav");
+        fs::copy("test/ME2Save.pcsav", &path)?;
+
+        // Booléen et entier
+        assert_eq!(set_plot(&path, "garrus is loyal", "true", false)?.0, PlotId::Boolean(183));
+        assert_eq!(set_plot(&path, "Paragon points", "1234", false)?.0, PlotId::Int(2));
+        assert!(dir.join("Save_0001.pcsav.bak").exists());
+
+        let save_game = SaveGame::load(String::new(), &fs::read(&path)?)?;
+        match save_game {
+            SaveGame::MassEffect2 { save_game, .. } => {
+                assert!(save_game.plot.bool_variables[183]);
+                assert_eq!(save_game.plot.int_variables[2], 1234);
+            }
+            _ => panic!("not a Mass Effect 2 save"),
+        }
+
+        // Erreurs
+        let before = fs::read(&path)?;
+        let err = set_plot(&path, "Nobody is loyal", "true", false).unwrap_err();
+        assert_eq!(err.to_string(), "Unknown plot `Nobody is loyal` for MassEffect2");
+        let err = set_plot(&path, "Garrus is loyal", "maybe", false).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid value `maybe`, expected `true` or `false`");
+        assert_eq!(fs::read(&path)?, before);
+
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn dry_run() -> Result<()> {
+        let dir = std::env::temp_dir().join("trilogy_save_editor_dry_run");
+        if dir.exists() {
+            fs::remove_dir_all(&dir)?;
+        }
+        fs::create_dir_all(&dir)?;
+        let path = dir.join("Save_0001.pcsav");
+        fs::copy("test/ME2Save.pcsav", &path)?;
+        let before = fs::read(&path)?;
+
+        // Le diff est celui d'une vraie modification
+        let (_, changes) = set_plot(&path, "Paragon points", "1234", true)?;
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].path(), "plot.int_variables[2]");
+        assert!(changes_text(&changes).ends_with(" → 1234\n"));
+        assert_eq!(fs::read(&path)?, before);
+        assert!(!dir.join("Save_0001.pcsav.bak").exists());
+
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        let file = path.to_string_lossy().into_owned();
+        run(&args(&["set", &file, "Garrus is loyal", "true", "--dry-run"]))?;
+        assert_eq!(fs::read(&path)?, before);
+
+        // Code de retour non nul si la modification échoue
+        assert!(run(&args(&["set", &file, "Garrus is loyal", "maybe", "--dry-run"])).is_err());
+        assert!(run(&args(&["validate", &file, "--dry-run"])).is_err());
+        assert_eq!(fs::read(&path)?, before);
+
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn apply_preset_to_dir() -> Result<()> {
+        let dir = std::env::temp_dir().join("trilogy_save_editor_apply_preset");
+        if dir.exists() {
+            fs::remove_dir_all(&dir)?;
+        }
+        fs::create_dir_all(&dir)?;
+
+        fs::copy("test/ME2Save.pcsav", dir.join("ME2Save.pcsav"))?;
+        fs::copy("test/ME2LegSave.pcsav", dir.join("ME2LegSave.pcsav"))?;
+        fs::copy("test/ME3Save.pcsav", dir.join("ME3Save.pcsav"))?;
+        fs::write(dir.join("Broken.pcsav"), b"not a save")?;
+
+        let mut plots = indexmap::IndexMap::new();
+        plots.insert("Garrus is loyal".to_owned(), "true".to_owned());
+        plots.insert("Paragon points".to_owned(), "1234".to_owned());
+        let preset = Preset { game: GameVersion::MassEffect2, plots };
+
+        let me3_before = fs::read(dir.join("ME3Save.pcsav"))?;
+        let report = apply_preset(&dir, &preset, false)?;
+
+        assert_eq!(
+            report.applied,
+            vec![
+                (dir.join("ME2LegSave.pcsav"), GameVersion::MassEffect2Leg),
+                (dir.join("ME2Save.pcsav"), GameVersion::MassEffect2),
+            ]
+        );
+        assert_eq!(report.skipped, vec![(dir.join("ME3Save.pcsav"), GameVersion::MassEffect3)]);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].0, dir.join("Broken.pcsav"));
+
+        // Backup et plots appliqués
+        for file in &["ME2Save.pcsav", "ME2LegSave.pcsav"] {
+            assert!(dir.join(format!("{}.bak", file)).exists());
+
+            let save_game = SaveGame::load(String::new(), &fs::read(dir.join(file))?)?;
+            let plot = match &save_game {
+                SaveGame::MassEffect2 { save_game, .. } => &save_game.plot,
+                SaveGame::MassEffect2Leg { save_game, .. } => &save_game.plot,
+                _ => panic!("not a Mass Effect 2 save"),
+            };
+            assert!(plot.bool_variables[183]);
+            assert_eq!(plot.int_variables[2], 1234);
+        }
+
+        // Jeu incompatible intact
+        assert_eq!(fs::read(dir.join("ME3Save.pcsav"))?, me3_before);
+        assert!(!dir.join("ME3Save.pcsav.bak").exists());
+
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn usage() {
+        assert!(run(&[]).is_err());
+        assert!(run(&["batch-convert".to_owned(), "dir".to_owned()]).is_err());
+
+        let args = ["batch-convert", "dir", "--to", "xml"].iter().map(|s| s.to_string());
+        let err = run(&args.collect::<Vec<_>>()).unwrap_err();
+        assert_eq!(err.to_string(), "Unknown format `xml`, expected `json`");
+    }
+
+    #[test]
+    fn validate_dir() -> Result<()> {
+        let dir = std::env::temp_dir().join("trilogy_save_editor_validate");
+        if dir.exists() {
+            fs::remove_dir_all(&dir)?;
+        }
+        fs::create_dir_all(&dir)?;
+
+        fs::copy("test/ME2Save.pcsav", dir.join("Good.pcsav"))?;
+        let mut corrupt = fs::read("test/ME2Save.pcsav")?;
+        let middle = corrupt.len() / 2;
+        corrupt[middle] ^= 0xFF;
+        fs::write(dir.join("Corrupt.pcsav"), corrupt)?;
+
+        let report = validate(&dir)?;
+        assert_eq!(report.passed.len(), 1);
+        assert_eq!(report.passed[0], (dir.join("Good.pcsav"), GameVersion::MassEffect2));
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].0, dir.join("Corrupt.pcsav"));
+        assert_eq!(report.failed[0].1.to_string(), "Invalid checksum");
+
+        // Code de retour non nul
+        let args = vec!["validate".to_owned(), dir.to_string_lossy().into_owned()];
+        assert!(run(&args).is_err());
+
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn schema_sections() -> Result<()> {
+        let schema = schema(Path::new("test/ME2Save.pcsav"))?;
+        assert_eq!(schema["type"], "struct");
+        assert_eq!(schema["name"], "Me2SaveGame");
+
+        let fields = schema["fields"].as_array().unwrap();
+        let field = |name: &str| {
+            fields.iter().find(|field| field["field"] == name).unwrap_or_else(|| panic!("{}", name))
+        };
+        for section in &["player", "squad", "plot", "me1_plot", "galaxy_map"] {
+            assert_eq!(field(*section)["type"], "struct", "{}", section);
+        }
+        assert_eq!(field("difficulty")["type"], "enum");
+        assert_eq!(field("levels")["type"], "array");
+        assert_eq!(field("streaming_records")["type"], "map");
+
+        // Les positions correspondent au fichier
+        let input = fs::read("test/ME2Save.pcsav")?;
+        assert_eq!(fields[0]["offset"], 0);
+        let offset = field("seconds_played")["offset"].as_u64().unwrap() as usize;
+        let seconds_played = f32::from_le_bytes([
+            input[offset],
+            input[offset + 1],
+            input[offset + 2],
+            input[offset + 3],
+        ]);
+        match SaveGame::load(String::new(), &input)? {
+            SaveGame::MassEffect2 { save_game, .. } => {
+                assert_eq!(seconds_played, save_game.seconds_played)
+            }
+            _ => panic!("not a Mass Effect 2 save"),
+        }
+
+        // Pas de position dans un format compressé
+        let schema = schema(Path::new("test/Clare00_AutoSave.MassEffectSave"))?;
+        assert!(schema.get("offset").is_none());
+        Ok(())
+    }
+}
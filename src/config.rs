@@ -0,0 +1,189 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    env, fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use crate::keybindings::KeyBindings;
+
+const CONFIG_FILE: &str = "config.ron";
+
+// Un `config.ron` à côté de l'exécutable garde la version portable, sinon le dossier de config de l'OS
+pub fn config_path() -> PathBuf {
+    let portable =
+        env::current_exe().ok().and_then(|exe| exe.parent().map(|dir| dir.join(CONFIG_FILE)));
+    match (portable, dirs::config_dir()) {
+        (Some(portable), _) if portable.is_file() => portable,
+        (_, Some(config_dir)) => config_dir.join("trilogy-save-editor").join(CONFIG_FILE),
+        (Some(portable), None) => portable,
+        (None, None) => PathBuf::from(CONFIG_FILE),
+    }
+}
+
+// Au-delà, les largeurs fixes de l'UI coupent trop de libellés
+pub const FONT_SCALE_RANGE: (f32, f32) = (0.75, 2.0);
+
+// Préférences de l'éditeur, un fichier absent donne la config par défaut
+#[derive(Deserialize, Serialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct Config {
+    // En secondes, désactivé si `None`
+    pub autosave_interval: Option<u32>,
+    pub keybindings: KeyBindings,
+    // Langue des noms de plots, anglais si `None`
+    pub plot_locale: Option<String>,
+    pub keyboard_navigation: bool,
+    pub number_base: NumberBase,
+    // Réenregistre chaque sauvegarde ouverte pour vérifier qu'elle ressort identique
+    pub verify_on_open: bool,
+    // Garde la date de modification du fichier, pour ceux qui trient leurs sauvegardes par date
+    pub preserve_mtime: bool,
+    // Refuse les opérations de `safe_mode::Guarded`, actif par défaut
+    pub safe_mode: bool,
+    // Remplace les couleurs du jeu par un thème noir et blanc
+    pub high_contrast: bool,
+    pub font_scale: f32,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            autosave_interval: None,
+            keybindings: KeyBindings::default(),
+            plot_locale: None,
+            keyboard_navigation: false,
+            number_base: NumberBase::default(),
+            verify_on_open: false,
+            preserve_mtime: false,
+            safe_mode: true,
+            high_contrast: false,
+            font_scale: 1.0,
+        }
+    }
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Self> {
+        let input = match fs::read_to_string(path) {
+            Ok(input) => input,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Config::default()),
+            Err(err) => return Err(err.into()),
+        };
+        ron::from_str(&input).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    // Un fichier édité à la main peut contenir n'importe quoi
+    pub fn font_scale(&self) -> f32 {
+        let (min, max) = FONT_SCALE_RANGE;
+        if self.font_scale.is_finite() {
+            self.font_scale.max(min).min(max)
+        } else {
+            1.0
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let output = ron::ser::to_string_pretty(self, Default::default())?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        fs::write(path, output).with_context(|| format!("Failed to save {}", path.display()))
+    }
+}
+
+// Affichage des entiers dans l'UI brute, la sauvegarde n'est pas concernée
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NumberBase {
+    Decimal,
+    Hexadecimal,
+}
+
+impl Default for NumberBase {
+    fn default() -> Self {
+        NumberBase::Decimal
+    }
+}
+
+impl NumberBase {
+    // Les négatifs en hexadécimal sont affichés en complément à deux, comme dans le fichier
+    pub fn format(self, value: i32) -> String {
+        match self {
+            NumberBase::Decimal => value.to_string(),
+            NumberBase::Hexadecimal => format!("{:X}", value as u32),
+        }
+    }
+
+    pub fn parse(self, input: &str) -> Option<i32> {
+        let input = input.trim();
+        match self {
+            NumberBase::Decimal => input.parse().ok(),
+            // Sans préfixe `0x`, le champ n'accepte que des chiffres hexadécimaux
+            NumberBase::Hexadecimal => {
+                u32::from_str_radix(input, 16).ok().map(|value| value as i32)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn load_save() -> Result<()> {
+        let path = std::env::temp_dir().join("trilogy_save_editor_config.ron");
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(Config::load(&path)?, Config::default());
+
+        let config = Config {
+            autosave_interval: Some(30),
+            keyboard_navigation: true,
+            number_base: NumberBase::Hexadecimal,
+            verify_on_open: true,
+            preserve_mtime: true,
+            safe_mode: false,
+            high_contrast: true,
+            font_scale: 1.5,
+            ..Default::default()
+        };
+        config.save(&path)?;
+        assert_eq!(Config::load(&path)?, config);
+
+        // Champs manquants
+        fs::write(&path, "()")?;
+        assert_eq!(Config::load(&path)?, Config::default());
+
+        // Taille de police hors limites
+        fs::write(&path, "(font_scale: 1.25)")?;
+        assert_eq!(Config::load(&path)?.font_scale(), 1.25);
+        fs::write(&path, "(font_scale: 10.0)")?;
+        assert_eq!(Config::load(&path)?.font_scale(), FONT_SCALE_RANGE.1);
+        assert_eq!(Config { font_scale: f32::NAN, ..Default::default() }.font_scale(), 1.0);
+
+        fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn hex_edit() {
+        let hex = NumberBase::Hexadecimal;
+        assert_eq!(hex.format(255), "FF");
+        assert_eq!(hex.format(-1), "FFFFFFFF");
+
+        // Saisie en mode hexadécimal
+        assert_eq!(hex.parse("ff"), Some(255));
+        assert_eq!(hex.parse("1F4"), Some(500));
+        assert_eq!(hex.parse("0x1F4"), None);
+        assert_eq!(hex.parse("FFFFFFFF"), Some(-1));
+        assert_eq!(hex.parse(&hex.format(i32::MIN)), Some(i32::MIN));
+        assert_eq!(hex.parse("100000000"), None);
+        assert_eq!(hex.parse(""), None);
+
+        assert_eq!(NumberBase::Decimal.parse("-12"), Some(-12));
+        assert_eq!(NumberBase::Decimal.parse("FF"), None);
+    }
+}
@@ -0,0 +1,355 @@
+use anyhow::{bail, Context, Result};
+use serde_json::Value;
+use std::fmt;
+
+use crate::event_handler::SaveGame;
+
+// Journal des modifications de la session, chaque entrée est le diff depuis la précédente
+#[derive(Default)]
+pub struct EditLog {
+    // État au chargement, pour revenir sur un seul champ
+    original: Option<Value>,
+    // État du dernier enregistrement
+    saved: Option<Value>,
+    snapshot: Option<Value>,
+    entries: Vec<Change>,
+}
+
+impl EditLog {
+    pub fn start(&mut self, save_game: &SaveGame) -> Result<()> {
+        let value = save_game.to_json_value()?;
+        self.original = Some(value.clone());
+        self.saved = Some(value.clone());
+        self.snapshot = Some(value);
+        self.entries.clear();
+        Ok(())
+    }
+
+    // Renvoie le nombre de changements ajoutés
+    pub fn record(&mut self, save_game: &SaveGame) -> Result<usize> {
+        let current = save_game.to_json_value()?;
+        let changes = match &self.snapshot {
+            Some(snapshot) => diff(snapshot, &current),
+            None => Vec::new(),
+        };
+        let count = changes.len();
+        self.entries.extend(changes);
+        self.snapshot = Some(current);
+        Ok(count)
+    }
+
+    pub fn mark_saved(&mut self, save_game: &SaveGame) -> Result<()> {
+        self.saved = Some(save_game.to_json_value()?);
+        Ok(())
+    }
+
+    // Des modifications seraient perdues en fermant la sauvegarde
+    pub fn is_dirty(&self, save_game: &SaveGame) -> Result<bool> {
+        let current = save_game.to_json_value()?;
+        Ok(self.saved.as_ref().map_or(false, |saved| *saved != current))
+    }
+
+    // Différence avec le chargement, indépendante de l'ordre des éditions
+    pub fn changes_since_load(&self, save_game: &SaveGame) -> Result<Vec<Change>> {
+        let current = save_game.to_json_value()?;
+        Ok(self.original.as_ref().map(|original| diff(original, &current)).unwrap_or_default())
+    }
+
+    // Remet le champ `path` (voir `diff`) à sa valeur au chargement
+    pub fn revert(&self, save_game: &mut SaveGame, path: &str) -> Result<()> {
+        let original = self.original.as_ref().context("No save loaded")?;
+        let mut current = save_game.to_json_value()?;
+
+        match bool_variable(path) {
+            Some((array_path, id)) => {
+                let pointer = to_pointer(array_path);
+                let (idx, mask) = (id / 32, 1 << (id % 32));
+                let bits = original.pointer(&pointer).and_then(|bits| bits.get(idx));
+                let was_set = bits.and_then(Value::as_u64).unwrap_or(0) & mask != 0;
+
+                let bits = current
+                    .pointer_mut(&pointer)
+                    .and_then(|bits| bits.get_mut(idx))
+                    .with_context(|| format!("`{}` doesn't exist anymore", path))?;
+                let value = bits.as_u64().unwrap_or(0);
+                *bits = Value::from(if was_set { value | mask } else { value & !mask });
+            }
+            None => {
+                let pointer = to_pointer(path);
+                match original.pointer(&pointer) {
+                    Some(value) => {
+                        let field = current
+                            .pointer_mut(&pointer)
+                            .with_context(|| format!("`{}` doesn't exist anymore", path))?;
+                        *field = value.clone();
+                    }
+                    // Ajouté depuis le chargement
+                    None => {
+                        let (parent, key) = pointer.rsplit_once('/').unwrap_or_default();
+                        let removed = current
+                            .pointer_mut(parent)
+                            .and_then(Value::as_object_mut)
+                            .and_then(|map| map.remove(&unescape(key)));
+                        if removed.is_none() {
+                            bail!("`{}` can't be reverted", path);
+                        }
+                    }
+                }
+            }
+        }
+        save_game.set_json_value(current)
+    }
+
+    pub fn entries(&self) -> &[Change] {
+        &self.entries
+    }
+
+    pub fn to_text(&self) -> String {
+        self.entries.iter().map(|change| format!("{}\n", change)).collect()
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub enum Change {
+    Set { path: String, before: Value, after: Value },
+    Toggle { path: String },
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Change::Set { path, before, after } => write!(f, "set {} {} → {}", path, before, after),
+            Change::Toggle { path } => write!(f, "toggle {}", path),
+        }
+    }
+}
+
+impl Change {
+    pub fn path(&self) -> &str {
+        match self {
+            Change::Set { path, .. } | Change::Toggle { path } => path,
+        }
+    }
+}
+
+// `plot.bool_variables[183]` -> (`plot.bool_variables`, 183)
+pub(crate) fn bool_variable(path: &str) -> Option<(&str, usize)> {
+    let (array_path, id) = path.strip_suffix(']')?.rsplit_once('[')?;
+    if !array_path.ends_with("bool_variables") {
+        return None;
+    }
+    id.parse().ok().map(|id| (array_path, id))
+}
+
+// `player.powers[2].rank` -> `/player/powers/2/rank`
+pub(crate) fn to_pointer(path: &str) -> String {
+    let mut pointer = String::new();
+    for part in path.split('.') {
+        let (key, indices) = part.split_at(part.find('[').unwrap_or_else(|| part.len()));
+        pointer.push('/');
+        pointer.push_str(&key.replace('~', "~0").replace('/', "~1"));
+        for idx in indices.split(|c| c == '[' || c == ']').filter(|idx| !idx.is_empty()) {
+            pointer.push('/');
+            pointer.push_str(idx);
+        }
+    }
+    pointer
+}
+
+pub(crate) fn unescape(key: &str) -> String {
+    key.replace("~1", "/").replace("~0", "~")
+}
+
+// Chemins `player.level`, `plot.bool_variables[183]`
+pub fn diff(before: &Value, after: &Value) -> Vec<Change> {
+    let mut changes = Vec::new();
+    diff_value("", before, after, &mut changes);
+    changes
+}
+
+fn diff_value(path: &str, before: &Value, after: &Value, changes: &mut Vec<Change>) {
+    match (before, after) {
+        (Value::Object(before), Value::Object(after)) => {
+            for (key, value) in after {
+                let path = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                match before.get(key) {
+                    Some(before) => diff_value(&path, before, value, changes),
+                    None => changes.push(Change::Set {
+                        path,
+                        before: Value::Null,
+                        after: value.clone(),
+                    }),
+                }
+            }
+        }
+        // Les booléens de plot sont sérialisés en bitfields de 32
+        (Value::Array(before), Value::Array(after)) if path.ends_with("bool_variables") => {
+            let len = before.len().max(after.len());
+            for i in 0..len {
+                let bits = |values: &[Value]| values.get(i).and_then(Value::as_u64).unwrap_or(0);
+                let toggled = bits(before) ^ bits(after);
+                for bit in (0..32).filter(|bit| toggled & (1 << bit) != 0) {
+                    changes.push(Change::Toggle { path: format!("{}[{}]", path, i * 32 + bit) });
+                }
+            }
+        }
+        (Value::Array(before_values), Value::Array(after_values))
+            if before_values.len() == after_values.len() =>
+        {
+            for (i, (before, after)) in before_values.iter().zip(after_values).enumerate() {
+                diff_value(&format!("{}[{}]", path, i), before, after, changes);
+            }
+        }
+        (Value::Bool(_), Value::Bool(_)) if before != after => {
+            changes.push(Change::Toggle { path: path.to_owned() })
+        }
+        _ if before != after => changes.push(Change::Set {
+            path: path.to_owned(),
+            before: before.clone(),
+            after: after.clone(),
+        }),
+        _ => (),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn captures_edits() -> Result<()> {
+        let input = fs::read("test/ME2Save.pcsav")?;
+        let mut save_game = SaveGame::load(String::new(), &input)?;
+
+        let mut edit_log = EditLog::default();
+        edit_log.start(&save_game)?;
+
+        // Niveau puis plot
+        let old_level = match &mut save_game {
+            SaveGame::MassEffect2 { save_game, .. } => {
+                let old_level = save_game.player.level;
+                save_game.player.level = 30;
+                old_level
+            }
+            _ => panic!("not a Mass Effect 2 save"),
+        };
+        assert_eq!(edit_log.record(&save_game)?, 1);
+
+        if let SaveGame::MassEffect2 { save_game, .. } = &mut save_game {
+            let value = save_game.plot.bool_variables[183];
+            save_game.plot.bool_variables.set(183, !value);
+        }
+        assert_eq!(edit_log.record(&save_game)?, 1);
+
+        // Rien de nouveau
+        assert_eq!(edit_log.record(&save_game)?, 0);
+
+        assert_eq!(
+            edit_log.to_text(),
+            format!("set player.level {} → 30\ntoggle plot.bool_variables[183]\n", old_level)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn revert_field() -> Result<()> {
+        let input = fs::read("test/ME2Save.pcsav")?;
+        let mut save_game = SaveGame::load(String::new(), &input)?;
+
+        let mut edit_log = EditLog::default();
+        edit_log.start(&save_game)?;
+
+        let old_level = match &mut save_game {
+            SaveGame::MassEffect2 { save_game, .. } => {
+                let old_level = save_game.player.level;
+                save_game.player.level = old_level + 1;
+                save_game.player.credits += 1000;
+                let value = save_game.plot.bool_variables[183];
+                save_game.plot.bool_variables.set(183, !value);
+                old_level
+            }
+            _ => panic!("not a Mass Effect 2 save"),
+        };
+        assert_eq!(edit_log.changes_since_load(&save_game)?.len(), 3);
+
+        // Seul le niveau revient, les autres éditions restent
+        edit_log.revert(&mut save_game, "player.level")?;
+        edit_log.revert(&mut save_game, "plot.bool_variables[183]")?;
+        let changes = edit_log.changes_since_load(&save_game)?;
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].path(), "player.credits");
+        match &save_game {
+            SaveGame::MassEffect2 { save_game, .. } => {
+                assert_eq!(save_game.player.level, old_level)
+            }
+            _ => unreachable!(),
+        }
+
+        // Même octets qu'une sauvegarde où seuls les crédits ont changé
+        let mut expected = SaveGame::load(String::new(), &input)?;
+        if let SaveGame::MassEffect2 { save_game, .. } = &mut expected {
+            save_game.player.credits += 1000;
+        }
+        assert_eq!(save_game.to_bytes()?, expected.to_bytes()?);
+        Ok(())
+    }
+
+    #[test]
+    fn json_value_round_trip() -> Result<()> {
+        let files = [
+            "test/ME1Leg00_QuickSave.pcsav",
+            "test/ME2Save.pcsav",
+            "test/ME2LegSave.pcsav",
+            "test/ME3Save.pcsav",
+        ];
+        for file in &files {
+            let input = fs::read(file)?;
+            let mut save_game = SaveGame::load(String::new(), &input)?;
+            let expected = save_game.to_bytes()?;
+
+            // Le retour en arrière par champ repasse par là, rien ne doit bouger
+            let value = save_game.to_json_value()?;
+            save_game.set_json_value(value)?;
+            assert_eq!(save_game.to_bytes()?, expected, "{}", file);
+        }
+
+        // ME1 ne se restaure pas, sans rien casser
+        let input = fs::read("test/Clare00_AutoSave.MassEffectSave")?;
+        let mut save_game = SaveGame::load(String::new(), &input)?;
+        let value = save_game.to_json_value()?;
+        assert!(save_game.set_json_value(value).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn dirty_until_saved() -> Result<()> {
+        let input = fs::read("test/ME2Save.pcsav")?;
+        let mut save_game = SaveGame::load(String::new(), &input)?;
+
+        let mut edit_log = EditLog::default();
+        edit_log.start(&save_game)?;
+        assert!(!edit_log.is_dirty(&save_game)?);
+
+        // Changer de sauvegarde demanderait confirmation
+        if let SaveGame::MassEffect2 { save_game, .. } = &mut save_game {
+            save_game.player.credits += 1000;
+        }
+        assert!(edit_log.is_dirty(&save_game)?);
+
+        edit_log.mark_saved(&save_game)?;
+        assert!(!edit_log.is_dirty(&save_game)?);
+
+        // Revenir à l'état enregistré n'est pas une modification
+        if let SaveGame::MassEffect2 { save_game, .. } = &mut save_game {
+            save_game.player.credits -= 1000;
+        }
+        assert!(edit_log.is_dirty(&save_game)?);
+        if let SaveGame::MassEffect2 { save_game, .. } = &mut save_game {
+            save_game.player.credits += 1000;
+        }
+        assert!(!edit_log.is_dirty(&save_game)?);
+        Ok(())
+    }
+}
@@ -1,33 +1,87 @@
-use anyhow::{Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use crc::{Crc, CRC_32_BZIP2};
 use flume::{Receiver, Sender};
-use ron::ser::PrettyConfig;
-use std::path::{Path, PathBuf};
+use imgui::ImString;
+use serde::{Deserialize, Serialize};
+use std::{
+    future::Future,
+    io::{self, Cursor, ErrorKind},
+    path::{Path, PathBuf},
+    process::Command,
+    time::Duration,
+};
 use tokio::{
     fs::{self, File},
     io::{AsyncReadExt, AsyncWriteExt},
+    task, time,
 };
 
 use crate::{
+    config::{self, Config},
+    edit_log::{self, Change},
     gui::UiEvent,
+    overview::{Overview, OverviewFormat},
+    patch::SavePatch,
+    plot_db::{self, PlotLocale},
+    save_archive::{self, TruncatedMember},
     save_data::{
-        mass_effect_1::{known_plot::Me1KnownPlot, Me1SaveGame},
+        build::Build,
+        carry_forward::{decision_conflicts, Decision, ME2_DECISIONS, ME3_DECISIONS},
+        mass_effect_1::{player::Player, state::State, Me1SaveGame},
         mass_effect_1_leg::Me1LegSaveGame,
-        mass_effect_2::{
-            known_plot::Me2KnownPlot, Me2LegSaveGame, Me2LegVersion, Me2SaveGame, Me2Version,
+        mass_effect_2::{Me2LegSaveGame, Me2LegVersion, Me2SaveGame, Me2Version},
+        mass_effect_3::{Me3SaveGame, Me3Version},
+        max_character,
+        shared::{
+            appearance::{HasHeadMorph, HeadMorph},
+            byte_diff::byte_diff,
+            limits::{self, SaveTooLarge},
+            outfits::{self, SquadOutfits, ME2_SQUAD_OUTFITS, ME3_SQUAD_OUTFITS},
+            plot::{replace_ints, BoolVec, PlotId, PlotNames},
         },
-        mass_effect_3::{known_plot::Me3KnownPlot, Me3SaveGame},
-        shared::appearance::HeadMorph,
+        summary::CharacterSummary,
+        RawUi,
     },
-    unreal,
+    save_slot,
+    unreal::{self, Coverage, OpaqueRegion},
 };
 
 pub enum MainEvent {
     OpenSave(String),
     SaveSave(String, SaveGame),
-    LoadKnownPlots,
+    // Différences avec le fichier déjà confirmées
+    SaveConfirmedSave(String, SaveGame),
+    // Sans l'avertissement de taille, déjà confirmé
+    SaveLargeSave(String, SaveGame),
+    LoadKnownPlots(GameVersion, Option<String>),
     ImportHeadMorph(String),
     ExportHeadMorph(String, Box<HeadMorph>),
+    RenameSave(String, String),
+    DuplicateSave(String, Option<u32>),
+    // Copie manuelle <-> automatique
+    ConvertSave(String),
+    DeleteSave(String),
+    OpenUrl(String),
+    ImportMe2Decisions(String),
+    ExportZipMember(String, Vec<u8>),
+    ImportZipMember(String, String),
+    // (membre, contenu actuel, sauvegarde à comparer)
+    CompareZipMember(String, Vec<u8>, String),
+    LoadConfig,
+    SaveConfig(Config),
+    AutoSave(SaveGame),
+    ImportPalette(String),
+    ExportPalette(String, String),
+    ImportInventory(String),
+    ExportInventory(String, String),
+    BrowseSaves(String),
+    ExportEditLog(String, String),
+    ExportOverview(String, SaveGame),
+    // (patch, sauvegarde d'arrivée, sauvegarde de départ)
+    ExportPatch(String, String, SaveGame),
+    ImportPatch(String),
+    // (sauvegarde à fusionner, sauvegarde actuelle)
+    MergePlots(String, SaveGame),
 }
 
 #[derive(Clone)]
@@ -39,213 +93,2465 @@ pub enum SaveGame {
     MassEffect3 { file_path: String, save_game: Box<Me3SaveGame> },
 }
 
-pub async fn event_loop(rx: Receiver<MainEvent>, ui_addr: Sender<UiEvent>) {
-    while let Ok(event) = rx.recv_async().await {
-        let result = async {
-            let ui_addr = Sender::clone(&ui_addr);
-            match event {
-                MainEvent::OpenSave(path) => tokio::spawn(open_save(path, ui_addr)).await?,
-                MainEvent::SaveSave(path, save_game) => {
-                    tokio::spawn(save_save(path, save_game, ui_addr)).await?
-                }
-                MainEvent::LoadKnownPlots => {
-                    let me1_handle = tokio::spawn(load_me1_known_plot(Sender::clone(&ui_addr)));
-                    let me2_handle = tokio::spawn(load_me2_known_plot(Sender::clone(&ui_addr)));
-                    let me3_handle = tokio::spawn(load_me3_known_plot(ui_addr));
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GameVersion {
+    MassEffect1,
+    MassEffect1Leg,
+    MassEffect2,
+    MassEffect2Leg,
+    MassEffect3,
+}
 
-                    let (me1_result, me2_result, me3_result) =
-                        tokio::join!(me1_handle, me2_handle, me3_handle);
+// Le contenu passe avant l'extension, une sauvegarde LE renommée en `.MassEffectSave`
+// ne doit pas être lue avec le format d'origine
+pub fn detect_game(file_path: &Path, input: &[u8]) -> GameVersion {
+    let is_me1 = file_path
+        .extension()
+        .map(|ext| unicase::eq(ext.to_string_lossy().to_string().as_str(), "MassEffectSave"))
+        .unwrap_or(false);
 
-                    me1_result?.context("Failed to parse plot/me1_known_plot.ron")?;
-                    me2_result?.context("Failed to parse plot/me2_known_plot.ron")?;
-                    me3_result?.context("Failed to parse plot/me3_known_plot.ron")
-                }
-                MainEvent::ImportHeadMorph(path) => {
-                    tokio::spawn(import_head_morph(path, ui_addr)).await?
-                }
-                MainEvent::ExportHeadMorph(path, head_morph) => {
-                    tokio::spawn(export_head_morph(path, head_morph, ui_addr)).await?
-                }
-            }
-        };
+    if is_me1 && !input.starts_with(&ME1_LEG_MAGIC) {
+        GameVersion::MassEffect1
+    } else {
+        detect_content(input).unwrap_or(GameVersion::MassEffect3)
+    }
+}
 
-        if let Err(err) = result.await {
-            let _ = ui_addr.send_async(UiEvent::Error(err)).await;
+// Par l'en-tête seul, `None` si ce n'est pas une sauvegarde
+pub fn detect_content(input: &[u8]) -> Option<GameVersion> {
+    if input.starts_with(&ME1_LEG_MAGIC) {
+        Some(GameVersion::MassEffect1Leg)
+    } else if has_me1_zip(input) {
+        Some(GameVersion::MassEffect1)
+    } else if unreal::Deserializer::from_bytes::<Me2Version>(input).is_ok() {
+        Some(GameVersion::MassEffect2)
+    } else if unreal::Deserializer::from_bytes::<Me2LegVersion>(input).is_ok() {
+        Some(GameVersion::MassEffect2Leg)
+    } else if unreal::Deserializer::from_bytes::<Me3Version>(input).is_ok() {
+        Some(GameVersion::MassEffect3)
+    } else {
+        None
+    }
+}
+
+const ME1_LEG_MAGIC: [u8; 4] = [0xC1, 0x83, 0x2A, 0x9E];
+
+// ME1 d'origine : le zip commence à `zip_offset`
+fn has_me1_zip(input: &[u8]) -> bool {
+    let zip_offset = match input.get(8..12) {
+        Some(bytes) => u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize,
+        None => return false,
+    };
+    input.get(zip_offset..).map(|zip| zip.starts_with(b"PK\x03\x04")).unwrap_or(false)
+}
+
+// Vérification rapide pour le navigateur de sauvegardes : zip lisible pour ME1, CRC pour les autres
+pub fn quick_check(file_path: &Path, input: &[u8]) -> Result<GameVersion> {
+    let game = detect_game(file_path, input);
+    match checksums(game, input)? {
+        Some((stored, computed)) if stored != computed => bail!("Invalid checksum"),
+        Some(_) => (),
+        None => Me1SaveGame::check_zip(Cursor::new(input))?,
+    }
+    Ok(game)
+}
+
+// (stocké, calculé), un seul CRC en fin de fichier, pas de checksum par section
+// ME1 n'en a pas, chaque fichier du zip a le sien
+pub fn checksums(game: GameVersion, input: &[u8]) -> Result<Option<(u32, u32)>> {
+    // Le checksum de ME1 LE est suivi de 8 octets
+    let checksum_offset = match game {
+        GameVersion::MassEffect1 => return Ok(None),
+        GameVersion::MassEffect1Leg => input.len().checked_sub(12),
+        GameVersion::MassEffect2 | GameVersion::MassEffect2Leg | GameVersion::MassEffect3 => {
+            input.len().checked_sub(4)
         }
     }
+    .ok_or_else(|| anyhow!("The save is too short"))?;
+
+    let crc = Crc::<u32>::new(&CRC_32_BZIP2);
+    let checksum = &input[checksum_offset..checksum_offset + 4];
+    let stored = u32::from_le_bytes([checksum[0], checksum[1], checksum[2], checksum[3]]);
+    Ok(Some((stored, crc.checksum(&input[..checksum_offset]))))
 }
 
-async fn open_save(file_path: String, ui_addr: Sender<UiEvent>) -> Result<()> {
-    let mut input = Vec::new();
-    {
-        let mut file = File::open(&file_path).await?;
-        file.read_to_end(&mut input).await?;
+#[derive(Serialize)]
+struct Me1Json<'a> {
+    player: &'a Player,
+    state: &'a State,
+}
+
+pub struct SaveEntry {
+    pub file_path: String,
+    pub integrity: Result<GameVersion, String>,
+}
+
+pub struct SaveGroup {
+    pub game: Option<GameVersion>,
+    // (dossier du personnage, sauvegardes), vide pour celles hors d'un dossier de personnage
+    pub characters: Vec<(String, Vec<SaveEntry>)>,
+}
+
+// Par jeu puis par personnage, le jeu lu dans la sauvegarde passe avant le dossier
+pub fn group_saves(root: &Path, entries: Vec<SaveEntry>) -> Vec<SaveGroup> {
+    let mut groups: Vec<SaveGroup> = Vec::new();
+    for entry in entries {
+        let path = Path::new(&entry.file_path);
+        let game =
+            entry.integrity.as_ref().ok().copied().or_else(|| save_slot::game_from_path(path));
+        let character = save_slot::character_folder(root, path).unwrap_or_default();
+
+        let idx = match groups.iter().position(|group| group.game == game) {
+            Some(idx) => idx,
+            None => {
+                groups.push(SaveGroup { game, characters: Vec::new() });
+                groups.len() - 1
+            }
+        };
+        let characters = &mut groups[idx].characters;
+        match characters.iter().position(|(name, _)| *name == character) {
+            Some(idx) => characters[idx].1.push(entry),
+            None => characters.push((character, vec![entry])),
+        }
     }
 
-    if let Some(ext) = Path::new(&file_path).extension() {
-        let save_game = if unicase::eq(ext.to_string_lossy().to_string().as_str(), "MassEffectSave")
-        {
-            // ME1
-            SaveGame::MassEffect1 {
-                file_path,
-                save_game: Box::new(unreal::Deserializer::from_bytes(&input)?),
+    // Jeux dans l'ordre de la trilogie, les inconnus à la fin
+    groups.sort_by_key(|group| group.game.map_or(usize::MAX, |game| game as usize));
+    for group in &mut groups {
+        group.characters.sort_by(|(a, _), (b, _)| a.cmp(b));
+    }
+    groups
+}
+
+// En-tête de ME1 + zip vide, la plus petite taille possible pour les 3 jeux
+const MIN_SAVE_LEN: usize = 34;
+
+impl SaveGame {
+    pub fn load(file_path: String, input: &[u8]) -> Result<Self> {
+        if input.len() < MIN_SAVE_LEN {
+            bail!("File too small to be a valid save ({} bytes)", input.len());
+        }
+
+        let save_game = match detect_game(Path::new(&file_path), input) {
+            GameVersion::MassEffect1 => {
+                SaveGame::MassEffect1 { file_path, save_game: Box::new(Me1SaveGame::load(input)?) }
             }
-        } else if input[0..4] == [0xC1, 0x83, 0x2A, 0x9E] {
-            // ME1 Legendary
-            SaveGame::MassEffect1Leg {
+            GameVersion::MassEffect1Leg => SaveGame::MassEffect1Leg {
                 file_path,
-                save_game: Box::new(unreal::Deserializer::from_bytes(&input)?),
-            }
-        } else if unreal::Deserializer::from_bytes::<Me2Version>(&input).is_ok() {
-            // ME2
-            SaveGame::MassEffect2 {
+                save_game: Box::new(unreal::Deserializer::from_bytes(input)?),
+            },
+            GameVersion::MassEffect2 => SaveGame::MassEffect2 {
                 file_path,
-                save_game: Box::new(unreal::Deserializer::from_bytes(&input)?),
-            }
-        } else if unreal::Deserializer::from_bytes::<Me2LegVersion>(&input).is_ok() {
-            // ME2 Legendary
-            SaveGame::MassEffect2Leg {
+                save_game: Box::new(unreal::Deserializer::from_bytes(input)?),
+            },
+            GameVersion::MassEffect2Leg => SaveGame::MassEffect2Leg {
                 file_path,
-                save_game: Box::new(unreal::Deserializer::from_bytes(&input)?),
-            }
-        } else {
-            // ME3
-            SaveGame::MassEffect3 {
+                save_game: Box::new(unreal::Deserializer::from_bytes(input)?),
+            },
+            GameVersion::MassEffect3 => SaveGame::MassEffect3 {
                 file_path,
-                save_game: Box::new(unreal::Deserializer::from_bytes(&input)?),
+                save_game: Box::new(unreal::Deserializer::from_bytes(input)?),
+            },
+        };
+        Ok(save_game)
+    }
+
+    // Avec checksum
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let crc = Crc::<u32>::new(&CRC_32_BZIP2);
+
+        let output = match self {
+            SaveGame::MassEffect1 { save_game, .. } => unreal::Serializer::to_byte_buf(save_game)?,
+            SaveGame::MassEffect1Leg { save_game, .. } => {
+                let mut output = unreal::Serializer::to_byte_buf(save_game)?;
+
+                // Checksum
+                let checksum_offset = output.len() - 12;
+                let checksum = crc.checksum(&output[..checksum_offset]);
+
+                // Update checksum
+                let end = checksum_offset + 4;
+                output[checksum_offset..end].swap_with_slice(&mut u32::to_le_bytes(checksum));
+                output
+            }
+            SaveGame::MassEffect2 { save_game, .. } => {
+                let mut output = unreal::Serializer::to_byte_buf(save_game)?;
+                let checksum = crc.checksum(&output);
+                output.extend(&u32::to_le_bytes(checksum));
+                output
+            }
+            SaveGame::MassEffect2Leg { save_game, .. } => {
+                let mut output = unreal::Serializer::to_byte_buf(save_game)?;
+                let checksum = crc.checksum(&output);
+                output.extend(&u32::to_le_bytes(checksum));
+                output
+            }
+            SaveGame::MassEffect3 { save_game, .. } => {
+                let mut output = unreal::Serializer::to_byte_buf(save_game)?;
+                let checksum = crc.checksum(&output);
+                output.extend(&u32::to_le_bytes(checksum));
+                output
             }
         };
+        Ok(output)
+    }
 
-        let _ = ui_addr.send_async(UiEvent::OpenedSave(save_game)).await;
-        let _ = ui_addr.send_async(UiEvent::Notification("Opened")).await;
+    // Le chargement reste permissif, seul l'enregistrement est refusé
+    fn check_name(&self) -> Result<()> {
+        let first_name = match self {
+            // Pas de joueur structuré dans ME1
+            SaveGame::MassEffect1 { .. } => return Ok(()),
+            SaveGame::MassEffect1Leg { save_game, .. } => &save_game.save_data.player.first_name,
+            SaveGame::MassEffect2 { save_game, .. } => &save_game.player.first_name,
+            SaveGame::MassEffect2Leg { save_game, .. } => &save_game.player.first_name,
+            SaveGame::MassEffect3 { save_game, .. } => &save_game.player.first_name,
+        };
+        limits::check_name(first_name.to_str()).context("Invalid character name")
     }
 
-    Ok(())
-}
+    pub fn to_json(&self) -> Result<String> {
+        let json = match self {
+            // Le zip n'est pas lisible, on exporte son contenu
+            SaveGame::MassEffect1 { save_game, .. } => {
+                let Me1SaveGame { player, state, .. } = save_game.as_ref();
+                serde_json::to_string_pretty(&Me1Json { player, state })?
+            }
+            SaveGame::MassEffect1Leg { save_game, .. } => {
+                serde_json::to_string_pretty(&save_game.save_data)?
+            }
+            SaveGame::MassEffect2 { save_game, .. } => serde_json::to_string_pretty(save_game)?,
+            SaveGame::MassEffect2Leg { save_game, .. } => serde_json::to_string_pretty(save_game)?,
+            // Ids triés pour des exports comparables, la sauvegarde garde l'ordre du fichier
+            SaveGame::MassEffect3 { save_game, .. } => {
+                let mut save_game = save_game.clone();
+                save_game.plot.int_variables.sort_keys();
+                save_game.plot.float_variables.sort_keys();
+                serde_json::to_string_pretty(&save_game)?
+            }
+        };
+        Ok(json)
+    }
 
-async fn save_save(path: String, save_game: SaveGame, ui_addr: Sender<UiEvent>) -> Result<()> {
-    let output = match save_game {
-        SaveGame::MassEffect1 { save_game, .. } => unreal::Serializer::to_byte_buf(&save_game)?,
-        SaveGame::MassEffect1Leg { save_game, .. } => {
-            let mut output = unreal::Serializer::to_byte_buf(&save_game)?;
+    // Même contenu que `to_json`, pour comparer deux états
+    pub fn to_json_value(&self) -> Result<serde_json::Value> {
+        let value = match self {
+            SaveGame::MassEffect1 { save_game, .. } => {
+                let Me1SaveGame { player, state, .. } = save_game.as_ref();
+                serde_json::to_value(&Me1Json { player, state })?
+            }
+            SaveGame::MassEffect1Leg { save_game, .. } => {
+                serde_json::to_value(&save_game.save_data)?
+            }
+            SaveGame::MassEffect2 { save_game, .. } => serde_json::to_value(save_game)?,
+            SaveGame::MassEffect2Leg { save_game, .. } => serde_json::to_value(save_game)?,
+            SaveGame::MassEffect3 { save_game, .. } => serde_json::to_value(save_game)?,
+        };
+        Ok(value)
+    }
 
-            // Checksum
-            let checksum_offset = output.len() - 12;
-            let crc = Crc::<u32>::new(&CRC_32_BZIP2);
-            let checksum = crc.checksum(&output[..checksum_offset]);
+    // Inverse de `to_json_value`, remplace le contenu éditable de la sauvegarde
+    pub fn set_json_value(&mut self, value: serde_json::Value) -> Result<()> {
+        match self {
+            SaveGame::MassEffect1 { .. } => {
+                bail!("Mass Effect 1 fields can't be restored, its player is a list of properties")
+            }
+            SaveGame::MassEffect1Leg { save_game, .. } => {
+                save_game.save_data = serde_json::from_value(value)?
+            }
+            SaveGame::MassEffect2 { save_game, .. } => **save_game = serde_json::from_value(value)?,
+            SaveGame::MassEffect2Leg { save_game, .. } => {
+                **save_game = serde_json::from_value(value)?
+            }
+            SaveGame::MassEffect3 { save_game, .. } => **save_game = serde_json::from_value(value)?,
+        }
+        Ok(())
+    }
 
-            // Update checksum
-            let end = checksum_offset + 4;
-            output[checksum_offset..end].swap_with_slice(&mut u32::to_le_bytes(checksum));
-            output
+    // Map chargée au chargement de la sauvegarde, voir `location::location_name`
+    pub fn location(&self) -> &str {
+        match self {
+            SaveGame::MassEffect1 { save_game, .. } => save_game.state.base_level_name.to_str(),
+            SaveGame::MassEffect1Leg { save_game, .. } => save_game.save_data.map_name.to_str(),
+            SaveGame::MassEffect2 { save_game, .. } => save_game.base_level_name.to_str(),
+            SaveGame::MassEffect2Leg { save_game, .. } => save_game.base_level_name.to_str(),
+            SaveGame::MassEffect3 { save_game, .. } => save_game.base_level_name.to_str(),
         }
-        SaveGame::MassEffect2 { save_game, .. } => {
-            let mut output = unreal::Serializer::to_byte_buf(&save_game)?;
+    }
+
+    // La position et l'état des quêtes ne suivent pas, le jeu peut charger le joueur
+    // hors de la map
+    pub fn set_location(&mut self, map: &str) {
+        let base_level_name = match self {
+            SaveGame::MassEffect1 { save_game, .. } => &mut save_game.state.base_level_name,
+            SaveGame::MassEffect1Leg { save_game, .. } => &mut save_game.save_data.map_name,
+            SaveGame::MassEffect2 { save_game, .. } => &mut save_game.base_level_name,
+            SaveGame::MassEffect2Leg { save_game, .. } => &mut save_game.base_level_name,
+            SaveGame::MassEffect3 { save_game, .. } => &mut save_game.base_level_name,
+        };
+        *base_level_name = ImString::new(map).into();
+    }
 
-            let crc = Crc::<u32>::new(&CRC_32_BZIP2);
-            let checksum = crc.checksum(&output);
-            output.extend(&u32::to_le_bytes(checksum));
-            output
+    // Pas de résumé pour ME1, le joueur n'y est qu'une liste de propriétés
+    pub fn summary(&self) -> Option<CharacterSummary> {
+        match self {
+            SaveGame::MassEffect1 { .. } => None,
+            SaveGame::MassEffect1Leg { save_game, .. } => {
+                Some(CharacterSummary::me1_leg(&save_game.save_data))
+            }
+            SaveGame::MassEffect2 { save_game, .. } => Some(CharacterSummary::me2(save_game)),
+            SaveGame::MassEffect2Leg { save_game, .. } => {
+                Some(CharacterSummary::me2_leg(save_game))
+            }
+            SaveGame::MassEffect3 { save_game, .. } => Some(CharacterSummary::me3(save_game)),
         }
-        SaveGame::MassEffect2Leg { save_game, .. } => {
-            let mut output = unreal::Serializer::to_byte_buf(&save_game)?;
+    }
 
-            let crc = Crc::<u32>::new(&CRC_32_BZIP2);
-            let checksum = crc.checksum(&output);
-            output.extend(&u32::to_le_bytes(checksum));
-            output
+    // Champs hors des bornes du jeu, (chemin, message), pour auditer une sauvegarde importée
+    pub fn range_report(&self) -> Vec<(String, String)> {
+        let errors = match self {
+            SaveGame::MassEffect1 { save_game, .. } => save_game.player.range_errors(),
+            SaveGame::MassEffect1Leg { save_game, .. } => save_game.save_data.player.range_errors(),
+            SaveGame::MassEffect2 { save_game, .. } => save_game.player.range_errors(),
+            SaveGame::MassEffect2Leg { save_game, .. } => save_game.player.range_errors(),
+            SaveGame::MassEffect3 { save_game, .. } => save_game.player.range_errors(),
+        };
+        // Mêmes chemins que le journal des modifications
+        let mut report: Vec<_> =
+            errors.into_iter().map(|(field, error)| (format!("player.{}", field), error)).collect();
+        if let Err(err) = self.check_name() {
+            report.push(("player.first_name".to_owned(), format!("{:#}", err)));
         }
-        SaveGame::MassEffect3 { save_game, .. } => {
-            let mut output = unreal::Serializer::to_byte_buf(&save_game)?;
+        report
+    }
 
-            let crc = Crc::<u32>::new(&CRC_32_BZIP2);
-            let checksum = crc.checksum(&output);
-            output.extend(&u32::to_le_bytes(checksum));
-            output
+    // ME1 et ME1 LE n'ont pas de pouvoirs nommés à partager
+    pub fn build(&self) -> Option<Build> {
+        match self {
+            SaveGame::MassEffect1 { .. } | SaveGame::MassEffect1Leg { .. } => None,
+            SaveGame::MassEffect2 { save_game, .. } => Some(Build::me2(&save_game.player)),
+            SaveGame::MassEffect2Leg { save_game, .. } => Some(Build::me2(&save_game.player)),
+            SaveGame::MassEffect3 { save_game, .. } => Some(Build::me3(&save_game.player)),
         }
-    };
+    }
 
-    // Backup si fichier existe
-    let path = PathBuf::from(path);
-    if fs::metadata(&path).await.is_ok() {
-        if let Some(ext) = path.extension() {
-            let to = Path::with_extension(&path, ext.to_string_lossy().into_owned() + ".bak");
-            fs::copy(&path, to).await?;
+    pub fn apply_build(&mut self, build: &Build) -> Result<()> {
+        match self {
+            SaveGame::MassEffect1 { .. } | SaveGame::MassEffect1Leg { .. } => {
+                bail!("Build codes are only supported by Mass Effect 2 and 3")
+            }
+            SaveGame::MassEffect2 { save_game, .. } => build.apply_me2(&mut save_game.player),
+            SaveGame::MassEffect2Leg { save_game, .. } => build.apply_me2(&mut save_game.player),
+            SaveGame::MassEffect3 { save_game, .. } => build.apply_me3(&mut save_game.player),
         }
     }
 
-    let mut file = File::create(&path).await?;
-    file.write_all(&output).await?;
+    // La forme de la sauvegarde plutôt que ses valeurs, positions comprises sauf pour ME1
+    // et ME1 LE qui sont compressés
+    pub fn schema(&self) -> Result<serde_json::Value> {
+        let schema = match self {
+            SaveGame::MassEffect1 { save_game, .. } => {
+                let Me1SaveGame { player, state, .. } = save_game.as_ref();
+                unreal::to_schema(&Me1Json { player, state }, false)?
+            }
+            SaveGame::MassEffect1Leg { save_game, .. } => {
+                unreal::to_schema(&save_game.save_data, false)?
+            }
+            SaveGame::MassEffect2 { save_game, .. } => unreal::to_schema(save_game, true)?,
+            SaveGame::MassEffect2Leg { save_game, .. } => unreal::to_schema(save_game, true)?,
+            SaveGame::MassEffect3 { save_game, .. } => unreal::to_schema(save_game, true)?,
+        };
+        Ok(schema)
+    }
 
-    let _ = ui_addr.send_async(UiEvent::Notification("Saved")).await;
-    Ok(())
-}
+    // Part des octets compris par l'éditeur, ME1 et ME1 LE sont mesurés décompressés
+    pub fn coverage(&self) -> Result<Coverage> {
+        let coverage = match self {
+            SaveGame::MassEffect1 { save_game, .. } => save_game.coverage()?,
+            SaveGame::MassEffect1Leg { save_game, .. } => {
+                unreal::to_coverage(&save_game.save_data)?
+            }
+            SaveGame::MassEffect2 { save_game, .. } => unreal::to_coverage(save_game)?,
+            SaveGame::MassEffect2Leg { save_game, .. } => unreal::to_coverage(save_game)?,
+            SaveGame::MassEffect3 { save_game, .. } => unreal::to_coverage(save_game)?,
+        };
+        Ok(coverage)
+    }
 
-async fn load_me1_known_plot(ui_addr: Sender<UiEvent>) -> Result<()> {
-    let mut input = String::new();
-    {
-        let mut file = File::open("plot/me1_known_plot.ron").await?;
-        file.read_to_string(&mut input).await?;
+    pub fn opaque_regions(&self) -> Result<Vec<OpaqueRegion>> {
+        let regions = match self {
+            SaveGame::MassEffect1 { save_game, .. } => save_game.opaque_regions()?,
+            SaveGame::MassEffect1Leg { save_game, .. } => {
+                unreal::to_opaque_regions(&save_game.save_data)?
+            }
+            SaveGame::MassEffect2 { save_game, .. } => unreal::to_opaque_regions(save_game)?,
+            SaveGame::MassEffect2Leg { save_game, .. } => unreal::to_opaque_regions(save_game)?,
+            SaveGame::MassEffect3 { save_game, .. } => unreal::to_opaque_regions(save_game)?,
+        };
+        Ok(regions)
+    }
+
+    // Seul ME1 garde la version du moteur, les autres jeux n'écrivent que celle du format
+    // qui est vérifiée au chargement
+    pub fn format_version(&self) -> String {
+        match self {
+            SaveGame::MassEffect1 { save_game, .. } => {
+                let (version, licensee) = save_game.player.package_version();
+                format!("Package {}, licensee {}", version, licensee)
+            }
+            SaveGame::MassEffect1Leg { save_game, .. } => {
+                format!("Save format {}", save_game.save_data.version())
+            }
+            SaveGame::MassEffect2 { save_game, .. } => {
+                format!("Save format {}", save_game.version())
+            }
+            SaveGame::MassEffect2Leg { save_game, .. } => {
+                format!("Save format {}", save_game.version())
+            }
+            SaveGame::MassEffect3 { save_game, .. } => {
+                format!("Save format {}", save_game.version())
+            }
+        }
     }
 
-    let me1_known_plot: Me1KnownPlot = ron::from_str(&input)?;
+    // Relit la sauvegarde sérialisée pour s'assurer que le jeu pourra la lire
+    pub fn self_check(&self) -> Result<Vec<u8>> {
+        let output = self.self_check_any_len()?;
+        limits::check_save_len(output.len())?;
+        Ok(output)
+    }
 
-    let _ = ui_addr.send_async(UiEvent::LoadedMe1KnownPlot(me1_known_plot)).await;
-    Ok(())
-}
+    // Une fois la taille acceptée par l'utilisateur
+    pub fn self_check_any_len(&self) -> Result<Vec<u8>> {
+        let output = self.to_bytes()?;
+        let reloaded = SaveGame::load(self.file_path().to_owned(), &output)
+            .context("Self check failed, the edited save can't be read back")?;
+        if reloaded.to_bytes()? != output {
+            bail!("Self check failed, the edited save isn't stable");
+        }
+        Ok(output)
+    }
 
-async fn load_me2_known_plot(ui_addr: Sender<UiEvent>) -> Result<()> {
-    let mut input = String::new();
-    {
-        let mut file = File::open("plot/me2_known_plot.ron").await?;
-        file.read_to_string(&mut input).await?;
+    // Compare la sauvegarde réécrite à l'originale. ME1 et ME1 LE ne sont pas compressés
+    // comme le fait le jeu, on vérifie seulement qu'ils se relisent à l'identique
+    pub fn verify_round_trip(&self, input: &[u8]) -> Result<()> {
+        match self {
+            SaveGame::MassEffect1 { .. } | SaveGame::MassEffect1Leg { .. } => {
+                self.self_check_any_len()?;
+            }
+            _ => {
+                let output = self.to_bytes()?;
+                let offset = input.iter().zip(&output).position(|(a, b)| a != b).or_else(|| {
+                    (input.len() != output.len()).then(|| input.len().min(output.len()))
+                });
+                if let Some(offset) = offset {
+                    bail!(
+                        "This save isn't fully supported yet, the editor writes it back differently from offset 0x{:X}. Keep a backup before saving.",
+                        offset
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn set_plot(&mut self, plot_id: PlotId, value: &str) -> Result<()> {
+        match plot_id {
+            PlotId::Boolean(id) => {
+                let value = match value {
+                    "true" | "1" => true,
+                    "false" | "0" => false,
+                    _ => bail!("Invalid value `{}`, expected `true` or `false`", value),
+                };
+                self.set_bool(id, value);
+            }
+            PlotId::Int(id) => {
+                let value: i32 = value
+                    .parse()
+                    .map_err(|_| anyhow!("Invalid value `{}`, expected an integer", value))?;
+                self.set_int(id, value);
+            }
+        }
+        Ok(())
     }
 
-    let me2_known_plot: Me2KnownPlot = ron::from_str(&input)?;
+    // Accès par nom pour les outils externes, résolu dans la base de plots du jeu
+    pub fn get_plot_bool(&self, name: &str) -> Result<bool> {
+        let id = self.find_bool(name)?;
+        Ok(self.plot_booleans().get(id).map_or(false, |value| *value))
+    }
 
-    let _ = ui_addr.send_async(UiEvent::LoadedMe2KnownPlot(me2_known_plot)).await;
-    Ok(())
-}
+    pub fn set_plot_bool(&mut self, name: &str, value: bool) -> Result<()> {
+        let id = self.find_bool(name)?;
+        self.set_bool(id, value);
+        Ok(())
+    }
 
-async fn load_me3_known_plot(ui_addr: Sender<UiEvent>) -> Result<()> {
-    let mut input = String::new();
-    {
-        let mut file = File::open("plot/me3_known_plot.ron").await?;
-        file.read_to_string(&mut input).await?;
+    pub fn get_plot_int(&self, name: &str) -> Result<i32> {
+        let id = self.find_int(name)?;
+        let value = match self {
+            SaveGame::MassEffect1 { save_game, .. } => {
+                save_game.state.plot.int_variables.get(id).copied()
+            }
+            SaveGame::MassEffect1Leg { save_game, .. } => {
+                save_game.save_data.plot.int_variables.get(id).copied()
+            }
+            SaveGame::MassEffect2 { save_game, .. } => {
+                save_game.plot.int_variables.get(id).copied()
+            }
+            SaveGame::MassEffect2Leg { save_game, .. } => {
+                save_game.plot.int_variables.get(id).copied()
+            }
+            SaveGame::MassEffect3 { save_game, .. } => {
+                save_game.plot.int_variables.get(&(id as i32)).copied()
+            }
+        };
+        // Absent de la sauvegarde, le jeu le lit comme 0
+        Ok(value.unwrap_or(0))
     }
 
-    let me3_known_plot: Me3KnownPlot = ron::from_str(&input)?;
+    pub fn set_plot_int(&mut self, name: &str, value: i32) -> Result<()> {
+        let id = self.find_int(name)?;
+        self.set_int(id, value);
+        Ok(())
+    }
 
-    let _ = ui_addr.send_async(UiEvent::LoadedMe3KnownPlot(me3_known_plot)).await;
-    Ok(())
-}
+    // Tous les plots de la sauvegarde dans l'ordre des ids, avec leur description si elle est
+    // connue. `names` vient de `PlotDb::names`
+    pub fn iter_plot_bools<'a>(
+        &'a self, names: &'a PlotNames,
+    ) -> impl Iterator<Item = (usize, Option<&'a str>, bool)> + 'a {
+        self.plot_booleans()
+            .iter()
+            .enumerate()
+            .map(move |(id, value)| (id, names.booleans.get(&id).map(String::as_str), *value))
+    }
 
-async fn import_head_morph(path: String, ui_addr: Sender<UiEvent>) -> Result<()> {
-    let mut import = String::new();
-    {
-        let mut file = File::open(&path).await?;
-        file.read_to_string(&mut import).await?;
+    pub fn iter_plot_ints<'a>(
+        &'a self, names: &'a PlotNames,
+    ) -> impl Iterator<Item = (usize, Option<&'a str>, i32)> + 'a {
+        let ints: Vec<(usize, i32)> = match self {
+            SaveGame::MassEffect1 { save_game, .. } => {
+                save_game.state.plot.int_variables.iter().copied().enumerate().collect()
+            }
+            SaveGame::MassEffect1Leg { save_game, .. } => {
+                save_game.save_data.plot.int_variables.iter().copied().enumerate().collect()
+            }
+            SaveGame::MassEffect2 { save_game, .. } => {
+                save_game.plot.int_variables.iter().copied().enumerate().collect()
+            }
+            SaveGame::MassEffect2Leg { save_game, .. } => {
+                save_game.plot.int_variables.iter().copied().enumerate().collect()
+            }
+            SaveGame::MassEffect3 { save_game, .. } => save_game
+                .plot
+                .int_variables
+                .iter()
+                .map(|(id, value)| (*id as usize, *value))
+                .collect(),
+        };
+        ints.into_iter()
+            .map(move |(id, value)| (id, names.ints.get(&id).map(String::as_str), value))
     }
 
-    let head_morph: HeadMorph = ron::from_str(&import)?;
+    // Accès direct par id, `None` au-delà des plots de la sauvegarde
+    pub fn plot_bool_at<'a>(
+        &self, id: usize, names: &'a PlotNames,
+    ) -> Option<(Option<&'a str>, bool)> {
+        let value = *self.plot_booleans().get(id)?;
+        Some((names.booleans.get(&id).map(String::as_str), value))
+    }
 
-    let _ = ui_addr.send_async(UiEvent::ImportedHeadMorph(head_morph)).await;
-    let _ = ui_addr.send_async(UiEvent::Notification("Imported")).await;
-    Ok(())
-}
+    // Les entiers de ME3 absents de la map valent 0 pour le jeu
+    pub fn plot_int_at<'a>(
+        &self, id: usize, names: &'a PlotNames,
+    ) -> Option<(Option<&'a str>, i32)> {
+        let value = match self {
+            SaveGame::MassEffect1 { save_game, .. } => {
+                *save_game.state.plot.int_variables.get(id)?
+            }
+            SaveGame::MassEffect1Leg { save_game, .. } => {
+                *save_game.save_data.plot.int_variables.get(id)?
+            }
+            SaveGame::MassEffect2 { save_game, .. } => *save_game.plot.int_variables.get(id)?,
+            SaveGame::MassEffect2Leg { save_game, .. } => *save_game.plot.int_variables.get(id)?,
+            SaveGame::MassEffect3 { save_game, .. } => {
+                save_game.plot.int_variables.get(&(id as i32)).copied().unwrap_or(0)
+            }
+        };
+        Some((names.ints.get(&id).map(String::as_str), value))
+    }
 
-async fn export_head_morph(
-    path: String, head_morph: Box<HeadMorph>, ui_addr: Sender<UiEvent>,
-) -> Result<()> {
-    let pretty_config =
-        PrettyConfig::new().with_enumerate_arrays(true).with_new_line(String::from('\n'));
+    // La base de plots ne décrit aucun flottant, la description est toujours `None`
+    pub fn iter_plot_floats<'a>(
+        &'a self,
+    ) -> impl Iterator<Item = (usize, Option<&'a str>, f32)> + 'a {
+        let floats: Vec<(usize, f32)> = match self {
+            SaveGame::MassEffect1 { save_game, .. } => {
+                save_game.state.plot.float_variables.iter().copied().enumerate().collect()
+            }
+            SaveGame::MassEffect1Leg { save_game, .. } => {
+                save_game.save_data.plot.float_variables.iter().copied().enumerate().collect()
+            }
+            SaveGame::MassEffect2 { save_game, .. } => {
+                save_game.plot.float_variables.iter().copied().enumerate().collect()
+            }
+            SaveGame::MassEffect2Leg { save_game, .. } => {
+                save_game.plot.float_variables.iter().copied().enumerate().collect()
+            }
+            SaveGame::MassEffect3 { save_game, .. } => save_game
+                .plot
+                .float_variables
+                .iter()
+                .map(|(id, value)| (*id as usize, *value))
+                .collect(),
+        };
+        floats.into_iter().map(|(id, value)| (id, None, value))
+    }
 
-    let export = ron::ser::to_string_pretty(&head_morph, pretty_config)?;
-    {
-        let mut file = File::create(&path).await?;
-        file.write_all(export.as_bytes()).await?;
+    fn find_bool(&self, name: &str) -> Result<usize> {
+        match plot_db::find_plot(self.game_version(), name)? {
+            PlotId::Boolean(id) => Ok(id),
+            PlotId::Int(_) => bail!("`{}` is an integer plot, not a boolean", name),
+        }
     }
 
-    let _ = ui_addr.send_async(UiEvent::Notification("Exported")).await;
-    Ok(())
+    fn find_int(&self, name: &str) -> Result<usize> {
+        match plot_db::find_plot(self.game_version(), name)? {
+            PlotId::Int(id) => Ok(id),
+            PlotId::Boolean(_) => bail!("`{}` is a boolean plot, not an integer", name),
+        }
+    }
+
+    fn plot_booleans(&self) -> &BoolVec {
+        match self {
+            SaveGame::MassEffect1 { save_game, .. } => &save_game.state.plot.bool_variables,
+            SaveGame::MassEffect1Leg { save_game, .. } => &save_game.save_data.plot.bool_variables,
+            SaveGame::MassEffect2 { save_game, .. } => &save_game.plot.bool_variables,
+            SaveGame::MassEffect2Leg { save_game, .. } => &save_game.plot.bool_variables,
+            SaveGame::MassEffect3 { save_game, .. } => &save_game.plot.bool_variables,
+        }
+    }
+
+    fn set_bool(&mut self, id: usize, value: bool) {
+        let booleans = match self {
+            SaveGame::MassEffect1 { save_game, .. } => &mut save_game.state.plot.bool_variables,
+            SaveGame::MassEffect1Leg { save_game, .. } => {
+                &mut save_game.save_data.plot.bool_variables
+            }
+            SaveGame::MassEffect2 { save_game, .. } => &mut save_game.plot.bool_variables,
+            SaveGame::MassEffect2Leg { save_game, .. } => &mut save_game.plot.bool_variables,
+            SaveGame::MassEffect3 { save_game, .. } => &mut save_game.plot.bool_variables,
+        };
+        if id >= booleans.len() {
+            booleans.resize(id + 1, false);
+        }
+        booleans.set(id, value);
+
+        // ME1 écrit ses booléens par u32 entiers
+        if let SaveGame::MassEffect1 { save_game, .. } = self {
+            save_game.state.plot.normalize();
+        }
+    }
+
+    fn set_int(&mut self, id: usize, value: i32) {
+        let ints = match self {
+            SaveGame::MassEffect1 { save_game, .. } => &mut save_game.state.plot.int_variables,
+            SaveGame::MassEffect1Leg { save_game, .. } => {
+                &mut save_game.save_data.plot.int_variables
+            }
+            SaveGame::MassEffect2 { save_game, .. } => &mut save_game.plot.int_variables,
+            SaveGame::MassEffect2Leg { save_game, .. } => &mut save_game.plot.int_variables,
+            SaveGame::MassEffect3 { save_game, .. } => {
+                save_game.plot.int_variables.insert(id as i32, value);
+                return;
+            }
+        };
+        if id >= ints.len() {
+            ints.resize(id + 1, 0);
+        }
+        ints[id] = value;
+    }
+
+    // Garde la progression la plus avancée de chaque plot : vrai plutôt que faux et le plus
+    // grand entier. Les flottants et les tenues, des index, ne sont pas fusionnés
+    pub fn merge_plots(&mut self, other: &SaveGame) -> Result<()> {
+        if other.game_version() != self.game_version() {
+            bail!(
+                "Can't merge a {:?} save into a {:?} save",
+                other.game_version(),
+                self.game_version()
+            );
+        }
+
+        let names = PlotNames::default();
+        let booleans: Vec<_> =
+            other.iter_plot_bools(&names).filter(|(.., value)| *value).map(|(id, ..)| id).collect();
+        let ints: Vec<_> = other.iter_plot_ints(&names).map(|(id, _, value)| (id, value)).collect();
+        for id in booleans {
+            self.set_bool(id, true);
+        }
+        let squad_outfits: &'static [SquadOutfits] = match self {
+            SaveGame::MassEffect2 { .. } | SaveGame::MassEffect2Leg { .. } => &ME2_SQUAD_OUTFITS,
+            SaveGame::MassEffect3 { .. } => &ME3_SQUAD_OUTFITS,
+            _ => &[],
+        };
+        for (id, value) in ints {
+            if outfits::find(squad_outfits, id).is_some() {
+                continue;
+            }
+            let current = self.plot_int_at(id, &names).map_or(0, |(_, current)| current);
+            if value > current {
+                self.set_int(id, value);
+            }
+        }
+        Ok(())
+    }
+
+    // Choix exclusifs faits ensemble par la fusion, l'aperçu les signale
+    pub fn merge_conflicts(&self, merged: &SaveGame) -> Vec<String> {
+        let decisions: &[Decision] = match self {
+            SaveGame::MassEffect2 { .. } | SaveGame::MassEffect2Leg { .. } => &ME2_DECISIONS,
+            SaveGame::MassEffect3 { .. } => &ME3_DECISIONS,
+            _ => &[],
+        };
+        let current = decision_conflicts(self.plot_booleans(), decisions);
+        decision_conflicts(merged.plot_booleans(), decisions)
+            .into_iter()
+            .filter(|conflict| !current.contains(conflict))
+            .collect()
+    }
+
+    // Uniquement les entiers du plot, les booléens et flottants ne sont jamais touchés
+    pub fn replace_plot_ints(&mut self, from: i32, to: Option<i32>) -> Vec<usize> {
+        let ints = match self {
+            SaveGame::MassEffect1 { save_game, .. } => &mut save_game.state.plot.int_variables,
+            SaveGame::MassEffect1Leg { save_game, .. } => {
+                &mut save_game.save_data.plot.int_variables
+            }
+            SaveGame::MassEffect2 { save_game, .. } => &mut save_game.plot.int_variables,
+            SaveGame::MassEffect2Leg { save_game, .. } => &mut save_game.plot.int_variables,
+            SaveGame::MassEffect3 { save_game, .. } => {
+                let ints = save_game.plot.int_variables.iter_mut();
+                return replace_ints(ints.map(|(idx, value)| (*idx as usize, value)), from, to);
+            }
+        };
+        replace_ints(ints.iter_mut().enumerate(), from, to)
+    }
+
+    pub fn game_version(&self) -> GameVersion {
+        match self {
+            SaveGame::MassEffect1 { .. } => GameVersion::MassEffect1,
+            SaveGame::MassEffect1Leg { .. } => GameVersion::MassEffect1Leg,
+            SaveGame::MassEffect2 { .. } => GameVersion::MassEffect2,
+            SaveGame::MassEffect2Leg { .. } => GameVersion::MassEffect2Leg,
+            SaveGame::MassEffect3 { .. } => GameVersion::MassEffect3,
+        }
+    }
+
+    pub fn file_path(&self) -> &str {
+        match self {
+            SaveGame::MassEffect1 { file_path, .. }
+            | SaveGame::MassEffect1Leg { file_path, .. }
+            | SaveGame::MassEffect2 { file_path, .. }
+            | SaveGame::MassEffect2Leg { file_path, .. }
+            | SaveGame::MassEffect3 { file_path, .. } => file_path,
+        }
+    }
+
+    pub fn set_file_path(&mut self, path: String) {
+        match self {
+            SaveGame::MassEffect1 { file_path, .. }
+            | SaveGame::MassEffect1Leg { file_path, .. }
+            | SaveGame::MassEffect2 { file_path, .. }
+            | SaveGame::MassEffect2Leg { file_path, .. }
+            | SaveGame::MassEffect3 { file_path, .. } => *file_path = path,
+        }
+    }
+
+    pub fn max_character(&mut self) -> Result<()> {
+        match self {
+            SaveGame::MassEffect1 { .. } => {
+                bail!("Max character is only available for the Legendary Edition of Mass Effect 1")
+            }
+            SaveGame::MassEffect1Leg { save_game, .. } => {
+                max_character::me1_leg(&mut save_game.save_data.player)
+            }
+            SaveGame::MassEffect2 { save_game, .. } => max_character::me2(&mut save_game.player),
+            SaveGame::MassEffect2Leg { save_game, .. } => max_character::me2(&mut save_game.player),
+            SaveGame::MassEffect3 { save_game, .. } => max_character::me3(&mut save_game.player),
+        }
+        Ok(())
+    }
+
+    // Ne remplace que le head morph, le reste du joueur n'est pas touché. Renvoie les
+    // avertissements, un autre modèle de tête reste importable
+    pub fn import_head_morph(&mut self, head_morph: HeadMorph) -> Result<Vec<String>> {
+        let has_head_morph = self.has_head_morph_mut()?;
+        head_morph.validate().context("The head morph doesn't fit this save")?;
+        let warnings = has_head_morph
+            .head_morph
+            .as_ref()
+            .map(|current| head_morph.lod_warnings(current))
+            .unwrap_or_default();
+        *has_head_morph = HasHeadMorph { has_head_morph: true, head_morph: Some(head_morph) };
+        Ok(warnings)
+    }
+
+    pub fn import_palette(&mut self, input: &str) -> Result<()> {
+        let head_morph = self.has_head_morph_mut()?.head_morph.as_mut();
+        head_morph.context("Import a head morph before applying a palette")?.import_palette(input)
+    }
+
+    pub fn import_inventory(&mut self, input: &str) -> Result<()> {
+        match self {
+            SaveGame::MassEffect1Leg { save_game, .. } => {
+                save_game.save_data.player.inventory.import_json(input)
+            }
+            _ => bail!("Only Mass Effect 1 Legendary saves have an item inventory"),
+        }
+    }
+
+    fn has_head_morph_mut(&mut self) -> Result<&mut HasHeadMorph> {
+        let has_head_morph = match self {
+            SaveGame::MassEffect1 { .. } => bail!("Mass Effect 1 saves don't have a head morph"),
+            SaveGame::MassEffect1Leg { save_game, .. } => {
+                &mut save_game.save_data.player.head_morph
+            }
+            SaveGame::MassEffect2 { save_game, .. } => &mut save_game.player.appearance.head_morph,
+            SaveGame::MassEffect2Leg { save_game, .. } => {
+                &mut save_game.player.appearance.head_morph
+            }
+            SaveGame::MassEffect3 { save_game, .. } => &mut save_game.player.appearance.head_morph,
+        };
+        Ok(has_head_morph)
+    }
+}
+
+pub fn backup_path(path: &Path) -> Option<PathBuf> {
+    let ext = path.extension()?;
+    Some(Path::with_extension(path, ext.to_string_lossy().into_owned() + ".bak"))
+}
+
+// Suggestion affichée avec l'erreur
+pub fn error_hint(error: &anyhow::Error) -> Option<&'static str> {
+    error.chain().find_map(|cause| {
+        if let Some(err) = cause.downcast_ref::<std::io::Error>() {
+            match err.kind() {
+                ErrorKind::NotFound => {
+                    Some("The file doesn't exist anymore, it may have been moved or deleted.")
+                }
+                ErrorKind::PermissionDenied => Some(
+                    "Check that the file isn't read-only or opened by another program (the game ?).",
+                ),
+                _ => None,
+            }
+        } else if let Some(err) = cause.downcast_ref::<unreal::Error>() {
+            match err {
+                unreal::Error::Eof => Some(
+                    "The save seems truncated, try to restore the `.bak` backup made by the editor.",
+                ),
+                unreal::Error::Message(msg) if msg.contains("zip archive") => Some(
+                    "The save archive is damaged, try to restore the `.bak` backup made by the editor.",
+                ),
+                _ => None,
+            }
+        } else if cause.is::<zip::result::ZipError>() {
+            Some(
+                "The save archive is damaged, try to restore the `.bak` backup made by the editor.",
+            )
+        } else if cause.is::<TruncatedMember>() {
+            Some(
+                "The compressed data is damaged and can't be rebuilt by the editor, restore the `.bak` backup or a copy of the save.",
+            )
+        } else {
+            None
+        }
+    })
+}
+
+pub async fn event_loop(rx: Receiver<MainEvent>, ui_addr: Sender<UiEvent>) {
+    // Lu dès le départ, une sauvegarde passée en ligne de commande arrive avant la config de l'UI
+    let config = Config::load(&config::config_path()).unwrap_or_default();
+    let (mut verify_on_open, mut preserve_mtime) = (config.verify_on_open, config.preserve_mtime);
+    // Une sauvegarde automatique ignorée n'est signalée qu'une fois, pas à chaque délai
+    let mut auto_save_skipped: Option<(String, &'static str)> = None;
+    while let Ok(event) = rx.recv_async().await {
+        let result = async {
+            let ui_addr = Sender::clone(&ui_addr);
+            match event {
+                MainEvent::OpenSave(path) => {
+                    let context = format!("Failed to open `{}`", path);
+                    let _ = ui_addr.send_async(UiEvent::Busy(Some("Opening"))).await;
+                    let result =
+                        tokio::spawn(open_save(path, verify_on_open, Sender::clone(&ui_addr)))
+                            .await;
+                    let _ = ui_addr.send_async(UiEvent::Busy(None)).await;
+                    result?.context(context)
+                }
+                MainEvent::SaveSave(path, save_game) => {
+                    save_save_busy(path, save_game, SaveChecks::All, preserve_mtime, ui_addr).await
+                }
+                MainEvent::SaveConfirmedSave(path, save_game) => {
+                    save_save_busy(path, save_game, SaveChecks::Size, preserve_mtime, ui_addr).await
+                }
+                MainEvent::SaveLargeSave(path, save_game) => {
+                    save_save_busy(path, save_game, SaveChecks::None, preserve_mtime, ui_addr).await
+                }
+                MainEvent::LoadKnownPlots(game, locale) => {
+                    load_known_plots(game, locale, ui_addr).await
+                }
+                MainEvent::ImportHeadMorph(path) => {
+                    tokio::spawn(import_head_morph(path, ui_addr)).await?
+                }
+                MainEvent::ExportHeadMorph(path, head_morph) => {
+                    tokio::spawn(export_head_morph(path, head_morph, ui_addr)).await?
+                }
+                MainEvent::RenameSave(path, new_name) => {
+                    tokio::spawn(rename_save(path, new_name, ui_addr)).await?
+                }
+                MainEvent::DuplicateSave(path, slot) => {
+                    tokio::spawn(duplicate_save(path, slot, ui_addr)).await?
+                }
+                MainEvent::ConvertSave(path) => tokio::spawn(convert_save(path, ui_addr)).await?,
+                MainEvent::DeleteSave(path) => tokio::spawn(delete_save(path, ui_addr)).await?,
+                MainEvent::OpenUrl(url) => open_url(&url),
+                MainEvent::ImportMe2Decisions(path) => {
+                    tokio::spawn(import_me2_decisions(path, ui_addr)).await?
+                }
+                MainEvent::ExportZipMember(path, output) => {
+                    tokio::spawn(export_zip_member(path, output, ui_addr)).await?
+                }
+                MainEvent::ImportZipMember(member, path) => {
+                    tokio::spawn(import_zip_member(member, path, ui_addr)).await?
+                }
+                MainEvent::CompareZipMember(member, current, path) => {
+                    tokio::spawn(compare_zip_member(member, current, path, ui_addr)).await?
+                }
+                MainEvent::LoadConfig => {
+                    let config = Config::load(&config::config_path())?;
+                    verify_on_open = config.verify_on_open;
+                    preserve_mtime = config.preserve_mtime;
+                    let _ = ui_addr.send_async(UiEvent::LoadedConfig(config)).await;
+                    Ok(())
+                }
+                MainEvent::SaveConfig(config) => {
+                    verify_on_open = config.verify_on_open;
+                    preserve_mtime = config.preserve_mtime;
+                    config.save(&config::config_path())
+                }
+                MainEvent::ImportPalette(path) => {
+                    let input = fs::read_to_string(&path).await?;
+                    let _ = ui_addr.send_async(UiEvent::ImportedPalette(input)).await;
+                    Ok(())
+                }
+                MainEvent::ExportPalette(path, palette) => {
+                    fs::write(&path, palette).await?;
+                    let _ = ui_addr.send_async(UiEvent::Notification("Exported")).await;
+                    Ok(())
+                }
+                MainEvent::ImportInventory(path) => {
+                    let input = fs::read_to_string(&path).await?;
+                    let _ = ui_addr.send_async(UiEvent::ImportedInventory(input)).await;
+                    Ok(())
+                }
+                MainEvent::ExportInventory(path, inventory) => {
+                    fs::write(&path, inventory).await?;
+                    let _ = ui_addr.send_async(UiEvent::Notification("Exported")).await;
+                    Ok(())
+                }
+                MainEvent::BrowseSaves(dir) => {
+                    let context = format!("Failed to read `{}`", dir);
+                    tokio::spawn(browse_saves(dir, ui_addr)).await?.context(context)
+                }
+                MainEvent::ExportEditLog(path, edit_log) => {
+                    fs::write(&path, edit_log).await?;
+                    let _ = ui_addr.send_async(UiEvent::Notification("Exported")).await;
+                    Ok(())
+                }
+                MainEvent::ExportOverview(path, save_game) => {
+                    let format = OverviewFormat::from_path(Path::new(&path));
+                    let overview = Overview::new(&save_game)?.render(format);
+                    fs::write(&path, overview).await?;
+                    let _ = ui_addr.send_async(UiEvent::Notification("Exported")).await;
+                    Ok(())
+                }
+                MainEvent::ExportPatch(path, to_path, save_game) => {
+                    let input = fs::read(&to_path).await?;
+                    let to = SaveGame::load(to_path, &input)?;
+                    let patch = SavePatch::between(&save_game, &to)?;
+                    fs::write(&path, patch.to_json()?).await?;
+                    let _ = ui_addr.send_async(UiEvent::Notification("Exported")).await;
+                    Ok(())
+                }
+                MainEvent::ImportPatch(path) => {
+                    let input = fs::read_to_string(&path).await?;
+                    let patch = SavePatch::from_json(&input)?;
+                    let _ = ui_addr.send_async(UiEvent::ImportedPatch(patch)).await;
+                    Ok(())
+                }
+                MainEvent::MergePlots(path, save_game) => {
+                    let input = fs::read(&path).await?;
+                    let other = SaveGame::load(path, &input)?;
+                    let (other, changes, conflicts) = task::spawn_blocking(move || {
+                        merge_preview(&save_game, &other).map(|(merged, changes)| {
+                            let conflicts = save_game.merge_conflicts(&merged);
+                            (other, changes, conflicts)
+                        })
+                    })
+                    .await??;
+                    let _ = ui_addr
+                        .send_async(UiEvent::MergedPlots(Box::new(other), changes, conflicts))
+                        .await;
+                    Ok(())
+                }
+                MainEvent::AutoSave(save_game) => {
+                    let path = save_game.file_path().to_owned();
+                    let skipped = if is_read_only(Path::new(&path)).await {
+                        Some("Auto-save skipped, the save is read-only")
+                    } else if save_game.check_name().is_err() {
+                        Some("Auto-save skipped, invalid character name")
+                    } else {
+                        None
+                    };
+                    if let Some(notification) = skipped {
+                        let skip = (path, notification);
+                        if auto_save_skipped.as_ref() != Some(&skip) {
+                            let _ = ui_addr.send_async(UiEvent::Notification(notification)).await;
+                            auto_save_skipped = Some(skip);
+                        }
+                        Ok(())
+                    } else {
+                        auto_save_skipped = None;
+                        let context = format!("Failed to auto-save `{}`", path);
+                        tokio::spawn(auto_save(save_game, preserve_mtime, ui_addr))
+                            .await?
+                            .context(context)
+                    }
+                }
+            }
+        };
+
+        if let Err(err) = result.await {
+            let _ = ui_addr.send_async(UiEvent::Error(err)).await;
+        }
+    }
+}
+
+async fn open_save(file_path: String, verify: bool, ui_addr: Sender<UiEvent>) -> Result<()> {
+    // Une archive ouvre le navigateur, une sauvegarde de l'archive est extraite à côté
+    if save_archive::is_archive(Path::new(&file_path)) {
+        return browse_archive(file_path, ui_addr).await;
+    }
+    let mut writing = None;
+    let (file_path, input) = match save_archive::split_path(&file_path) {
+        Some((archive_path, member)) => {
+            let (archive, _) = read_save_file(archive_path).await?;
+            let input = save_archive::read_member(&archive, member)?;
+            (save_archive::extracted_path(archive_path, member), input)
+        }
+        None => {
+            let (input, being_written) = read_save_file(&file_path).await?;
+            if being_written {
+                writing = Some(file_path.clone());
+            }
+            (file_path, input)
+        }
+    };
+
+    // La désérialisation peut être longue, elle ne doit pas bloquer les autres events
+    let loaded = task::spawn_blocking(move || {
+        SaveGame::load(file_path, &input).map(|save_game| {
+            let checksums = checksums(save_game.game_version(), &input).ok().flatten();
+            let round_trip = if verify { save_game.verify_round_trip(&input).err() } else { None };
+            (save_game, checksums, round_trip)
+        })
+    })
+    .await?;
+    let (save_game, checksums, round_trip) = loaded.map_err(|err| match &writing {
+        Some(_) => err.context("The file was being written, try again once the sync is done"),
+        None => err,
+    })?;
+
+    let _ = ui_addr.send_async(UiEvent::OpenedSave(save_game)).await;
+    let _ = ui_addr.send_async(UiEvent::Notification("Opened")).await;
+
+    if let Some(err) = open_warning(writing, checksums, round_trip) {
+        let _ = ui_addr.send_async(UiEvent::Error(err)).await;
+    }
+
+    Ok(())
+}
+
+// Un seul popup d'erreur pour tous les avertissements de l'ouverture
+fn open_warning(
+    writing: Option<String>, checksums: Option<(u32, u32)>, round_trip: Option<anyhow::Error>,
+) -> Option<anyhow::Error> {
+    // Le jeu refuserait la sauvegarde sans rien dire, le checksum est recalculé à l'enregistrement
+    let checksum = checksums.filter(|(stored, computed)| stored != computed).map(|(stored, computed)| {
+        anyhow!(
+            "The stored checksum ({:08X}) doesn't match the content ({:08X}), the save was corrupted before being opened. It will be fixed on save.",
+            stored,
+            computed
+        )
+    });
+
+    // Le checksum réécrit diffère forcément de l'original, l'aller-retour échoue aussi
+    let warning = checksum.or(round_trip);
+
+    // La relecture en cours prime, les autres avertissements suivent
+    match writing {
+        Some(file_path) => {
+            let context = format!(
+                "`{}` changed while being read, the game or a cloud sync client may still be writing it. Reopen it before saving.",
+                file_path
+            );
+            Some(match warning {
+                Some(err) => err.context(context),
+                None => anyhow!(context),
+            })
+        }
+        None => warning,
+    }
+}
+
+// Sauvegarde ouverte par le jeu ou un client de synchronisation (Steam, EA)
+const LOCK_RETRIES: u32 = 5;
+const LOCK_BACKOFF: Duration = Duration::from_millis(100);
+
+// `ERROR_SHARING_VIOLATION` et `ERROR_LOCK_VIOLATION` sous Windows
+fn is_locked(err: &io::Error) -> bool {
+    err.kind() == ErrorKind::WouldBlock
+        || (cfg!(windows) && matches!(err.raw_os_error(), Some(32) | Some(33)))
+}
+
+// Le délai double à chaque essai, les autres erreurs remontent tout de suite
+async fn retry_locked<T, F, Fut>(mut read: F) -> io::Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = io::Result<T>>,
+{
+    let mut delay = LOCK_BACKOFF;
+    for _ in 0..LOCK_RETRIES {
+        match read().await {
+            Err(err) if is_locked(&err) => {
+                time::sleep(delay).await;
+                delay *= 2;
+            }
+            result => return result,
+        }
+    }
+    read().await
+}
+
+// Rien n'est appliqué ici, l'UI montre les changements puis refait la fusion à la confirmation
+fn merge_preview(save_game: &SaveGame, other: &SaveGame) -> Result<(SaveGame, Vec<Change>)> {
+    let mut merged = save_game.clone();
+    merged.merge_plots(other)?;
+    let changes = edit_log::diff(&save_game.to_json_value()?, &merged.to_json_value()?);
+    Ok((merged, changes))
+}
+
+// Renvoie aussi `true` si la taille a changé pendant la lecture
+async fn read_save_file(path: &str) -> Result<(Vec<u8>, bool)> {
+    let len_before = retry_locked(|| fs::metadata(path)).await?.len();
+    let input = retry_locked(|| fs::read(path)).await?;
+    let len_after = retry_locked(|| fs::metadata(path)).await?.len();
+    let being_written = len_before != len_after || len_after != input.len() as u64;
+    Ok((input, being_written))
+}
+
+// Chaque confirmation de l'UI retire une vérification
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SaveChecks {
+    All,
+    Size,
+    None,
+}
+
+async fn save_save_busy(
+    path: String, save_game: SaveGame, checks: SaveChecks, preserve_mtime: bool,
+    ui_addr: Sender<UiEvent>,
+) -> Result<()> {
+    let context = format!("Failed to save `{}`", path);
+    let _ = ui_addr.send_async(UiEvent::Busy(Some("Saving"))).await;
+    let result =
+        tokio::spawn(save_save(path, save_game, checks, preserve_mtime, Sender::clone(&ui_addr)))
+            .await;
+    let _ = ui_addr.send_async(UiEvent::Busy(None)).await;
+    result?.context(context)
+}
+
+async fn save_save(
+    path: String, save_game: SaveGame, checks: SaveChecks, preserve_mtime: bool,
+    ui_addr: Sender<UiEvent>,
+) -> Result<()> {
+    // Fréquent avec les dossiers synchronisés, l'UI propose d'enregistrer une copie
+    if is_read_only(Path::new(&path)).await {
+        let _ = ui_addr.send_async(UiEvent::ReadOnlySave(path)).await;
+        return Ok(());
+    }
+
+    // Le jeu refuse ou tronque le nom, on n'écrit pas une sauvegarde invalide
+    save_game.check_name()?;
+
+    if checks == SaveChecks::All {
+        let changes = pending_changes(Path::new(&path), &save_game).await?;
+        if !changes.is_empty() {
+            let _ = ui_addr.send_async(UiEvent::ConfirmSave(path, changes)).await;
+            return Ok(());
+        }
+    }
+
+    let saved = save_game.clone();
+    let output = task::spawn_blocking(move || match checks {
+        SaveChecks::None => save_game.self_check_any_len(),
+        _ => save_game.self_check(),
+    })
+    .await?;
+
+    // Rien n'est écrit avant que l'utilisateur confirme
+    let output = match output {
+        Err(err) if err.is::<SaveTooLarge>() => {
+            let _ = ui_addr.send_async(UiEvent::LargeSave(path, err.to_string())).await;
+            return Ok(());
+        }
+        output => output?,
+    };
+
+    // Backup si fichier existe
+    let path = PathBuf::from(path);
+    if fs::metadata(&path).await.is_ok() {
+        if let Some(to) = backup_path(&path) {
+            fs::copy(&path, to).await?;
+        }
+    }
+
+    write_save(&path, &output, preserve_mtime).await?;
+
+    let _ = ui_addr.send_async(UiEvent::SavedSave(saved)).await;
+    let _ = ui_addr.send_async(UiEvent::Notification("Saved")).await;
+    Ok(())
+}
+
+// Diff avec le fichier sur le disque, vide s'il n'existe pas ou n'est pas une sauvegarde du même jeu
+async fn pending_changes(path: &Path, save_game: &SaveGame) -> Result<Vec<Change>> {
+    let input = match fs::read(path).await {
+        Ok(input) => input,
+        Err(_) => return Ok(Vec::new()),
+    };
+    let file_path = path.to_string_lossy().into_owned();
+    let save_game = save_game.clone();
+    task::spawn_blocking(move || {
+        let on_disk = match SaveGame::load(file_path, &input) {
+            Ok(on_disk) if on_disk.game_version() == save_game.game_version() => on_disk,
+            _ => return Ok(Vec::new()),
+        };
+        Ok(edit_log::diff(&on_disk.to_json_value()?, &save_game.to_json_value()?))
+    })
+    .await?
+}
+
+// Un nouveau fichier garde la date d'écriture
+async fn write_save(path: &Path, output: &[u8], preserve_mtime: bool) -> Result<()> {
+    let modified = if preserve_mtime {
+        fs::metadata(path).await.and_then(|metadata| metadata.modified()).ok()
+    } else {
+        None
+    };
+    fs::write(path, output).await?;
+
+    if let Some(modified) = modified {
+        let file = fs::OpenOptions::new().write(true).open(path).await?.into_std().await;
+        file.set_modified(modified)?;
+    }
+    Ok(())
+}
+
+// Un fichier absent n'est pas en lecture seule
+async fn is_read_only(path: &Path) -> bool {
+    fs::metadata(path).await.map(|metadata| metadata.permissions().readonly()).unwrap_or(false)
+}
+
+// Passe par `self_check`, rien n'est écrit si le fichier n'a pas changé
+async fn auto_save(
+    save_game: SaveGame, preserve_mtime: bool, ui_addr: Sender<UiEvent>,
+) -> Result<()> {
+    let output = save_game.self_check().map_err(|err| {
+        if err.is::<SaveTooLarge>() {
+            err.context("Auto-save skipped, save manually to confirm")
+        } else {
+            err
+        }
+    })?;
+
+    let path = PathBuf::from(save_game.file_path());
+    if fs::read(&path).await.ok().as_deref() == Some(output.as_slice()) {
+        let _ = ui_addr.send_async(UiEvent::SavedSave(save_game)).await;
+        return Ok(());
+    }
+
+    if fs::metadata(&path).await.is_ok() {
+        if let Some(to) = backup_path(&path) {
+            fs::copy(&path, to).await?;
+        }
+    }
+    write_save(&path, &output, preserve_mtime).await?;
+
+    let _ = ui_addr.send_async(UiEvent::SavedSave(save_game)).await;
+    let _ = ui_addr.send_async(UiEvent::Notification("Auto-saved")).await;
+    Ok(())
+}
+
+async fn rename_save(path: String, new_name: String, ui_addr: Sender<UiEvent>) -> Result<()> {
+    let to = save_slot::rename_target(Path::new(&path), &new_name)?;
+    if fs::metadata(&to).await.is_ok() {
+        bail!("`{}` already exists", to.display());
+    }
+    fs::rename(&path, &to).await?;
+
+    let _ = ui_addr.send_async(UiEvent::MovedSave(to.to_string_lossy().into_owned())).await;
+    let _ = ui_addr.send_async(UiEvent::Notification("Renamed")).await;
+    Ok(())
+}
+
+// Dans le slot donné ou le prochain libre
+async fn duplicate_save(path: String, slot: Option<u32>, ui_addr: Sender<UiEvent>) -> Result<()> {
+    let path = PathBuf::from(path);
+
+    let to = match slot {
+        Some(slot) => save_slot::slot_target(&path, slot)?,
+        None => save_slot::duplicate_target(&path, &sibling_files(&path).await?),
+    };
+    if fs::metadata(&to).await.is_ok() {
+        bail!("`{}` already exists", to.display());
+    }
+    fs::copy(&path, &to).await?;
+
+    let _ = ui_addr.send_async(UiEvent::Notification("Duplicated")).await;
+    Ok(())
+}
+
+// Le contenu ne change pas, le jeu ne se fie qu'au nom du fichier
+async fn convert_save(path: String, ui_addr: Sender<UiEvent>) -> Result<()> {
+    let path = PathBuf::from(path);
+
+    let to = save_slot::convert_target(&path, &sibling_files(&path).await?)?;
+    if fs::metadata(&to).await.is_ok() {
+        bail!("`{}` already exists", to.display());
+    }
+    fs::copy(&path, &to).await?;
+
+    let _ = ui_addr.send_async(UiEvent::Notification("Converted")).await;
+    Ok(())
+}
+
+// Noms des fichiers du dossier de la sauvegarde
+async fn sibling_files(path: &Path) -> Result<Vec<String>> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+
+    let mut existing = Vec::new();
+    let mut entries = fs::read_dir(dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        existing.push(entry.file_name().to_string_lossy().into_owned());
+    }
+    Ok(existing)
+}
+
+async fn browse_archive(archive_path: String, ui_addr: Sender<UiEvent>) -> Result<()> {
+    let archive = fs::read(&archive_path).await?;
+    let entries = task::spawn_blocking(move || save_archive::list_saves(&archive)).await??;
+
+    let entries = entries
+        .into_iter()
+        .map(|(member, integrity)| SaveEntry {
+            file_path: save_archive::join_path(&archive_path, &member),
+            integrity,
+        })
+        .collect();
+    let _ = ui_addr.send_async(UiEvent::BrowsedSaves(archive_path, entries)).await;
+    Ok(())
+}
+
+// Assez profond pour partir du dossier `BioWare` :
+// `Mass Effect Legendary Edition/Save/ME1/<perso>`
+const BROWSE_DEPTH: usize = 4;
+
+async fn browse_saves(dir: String, ui_addr: Sender<UiEvent>) -> Result<()> {
+    let mut entries = Vec::new();
+    let mut dirs = vec![(PathBuf::from(&dir), 0)];
+    while let Some((current_dir, depth)) = dirs.pop() {
+        let mut read_dir = match fs::read_dir(&current_dir).await {
+            Ok(read_dir) => read_dir,
+            // Un sous-dossier illisible n'empêche pas de lister les autres
+            Err(_) if depth > 0 => continue,
+            Err(err) => return Err(err.into()),
+        };
+        while let Some(entry) = read_dir.next_entry().await? {
+            let path = entry.path();
+            if entry.file_type().await?.is_dir() {
+                if depth < BROWSE_DEPTH {
+                    dirs.push((path, depth + 1));
+                }
+                continue;
+            }
+            if !save_slot::is_save(&path) {
+                continue;
+            }
+
+            let integrity = match fs::read(&path).await {
+                Ok(input) => quick_check(&path, &input).map_err(|err| format!("{:#}", err)),
+                Err(err) => Err(err.to_string()),
+            };
+            entries.push(SaveEntry { file_path: path.to_string_lossy().into_owned(), integrity });
+        }
+    }
+    entries.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+
+    let _ = ui_addr.send_async(UiEvent::BrowsedSaves(dir, entries)).await;
+    Ok(())
+}
+
+async fn delete_save(path: String, ui_addr: Sender<UiEvent>) -> Result<()> {
+    fs::remove_file(&path).await?;
+
+    let _ = ui_addr.send_async(UiEvent::DeletedSave).await;
+    let _ = ui_addr.send_async(UiEvent::Notification("Deleted")).await;
+    Ok(())
+}
+
+async fn import_me2_decisions(path: String, ui_addr: Sender<UiEvent>) -> Result<()> {
+    let mut input = Vec::new();
+    {
+        let mut file = File::open(&path).await?;
+        file.read_to_end(&mut input).await?;
+    }
+
+    let plot = if unreal::Deserializer::from_bytes::<Me2Version>(&input).is_ok() {
+        unreal::Deserializer::from_bytes::<Me2SaveGame>(&input)?.plot
+    } else if unreal::Deserializer::from_bytes::<Me2LegVersion>(&input).is_ok() {
+        unreal::Deserializer::from_bytes::<Me2LegSaveGame>(&input)?.plot
+    } else {
+        bail!("`{}` isn't a Mass Effect 2 save", path);
+    };
+
+    let _ = ui_addr.send_async(UiEvent::ImportedMe2Plot(Box::new(plot))).await;
+    Ok(())
+}
+
+// Navigateur par défaut
+fn open_url(url: &str) -> Result<()> {
+    if !url.starts_with("https://") && !url.starts_with("http://") {
+        bail!("`{}` isn't a web link", url);
+    }
+
+    // Sans passer par `cmd`, qui couperait l'url au premier `&`
+    let program = if cfg!(target_os = "windows") {
+        "explorer"
+    } else if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    };
+
+    Command::new(program)
+        .arg(url)
+        .spawn()
+        .with_context(|| format!("Failed to open `{}` in your browser", url))?;
+    Ok(())
+}
+
+// Seulement les bases du jeu ouvert, elles restent en cache
+async fn load_known_plots(
+    game: GameVersion, locale: Option<String>, ui_addr: Sender<UiEvent>,
+) -> Result<()> {
+    // Une traduction illisible ne doit pas empêcher d'afficher les plots
+    let locale = match locale {
+        Some(locale) => match task::spawn_blocking(move || PlotLocale::load(&locale)).await? {
+            Ok(locale) => Some(locale),
+            Err(err) => {
+                let _ = ui_addr.send_async(UiEvent::Error(err)).await;
+                None
+            }
+        },
+        None => None,
+    };
+
+    for &game in plot_db::dependencies(game) {
+        let mut plot_db = task::spawn_blocking(move || plot_db::for_game(game)).await??;
+        if let Some(locale) = &locale {
+            plot_db = plot_db.localized(locale);
+        }
+        let _ = ui_addr.send_async(UiEvent::LoadedPlotDb(plot_db)).await;
+    }
+    Ok(())
+}
+
+async fn import_head_morph(path: String, ui_addr: Sender<UiEvent>) -> Result<()> {
+    let mut import = String::new();
+    {
+        let mut file = File::open(&path).await?;
+        file.read_to_string(&mut import).await?;
+    }
+
+    let head_morph = HeadMorph::from_ron(&import)?;
+
+    let _ = ui_addr.send_async(UiEvent::ImportedHeadMorph(head_morph)).await;
+    let _ = ui_addr.send_async(UiEvent::Notification("Imported")).await;
+    Ok(())
+}
+
+async fn export_head_morph(
+    path: String, head_morph: Box<HeadMorph>, ui_addr: Sender<UiEvent>,
+) -> Result<()> {
+    let export = head_morph.to_ron()?;
+    {
+        let mut file = File::create(&path).await?;
+        file.write_all(export.as_bytes()).await?;
+    }
+
+    let _ = ui_addr.send_async(UiEvent::Notification("Exported")).await;
+    Ok(())
+}
+
+async fn export_zip_member(path: String, output: Vec<u8>, ui_addr: Sender<UiEvent>) -> Result<()> {
+    fs::write(&path, output).await?;
+
+    let _ = ui_addr.send_async(UiEvent::Notification("Exported")).await;
+    Ok(())
+}
+
+async fn import_zip_member(member: String, path: String, ui_addr: Sender<UiEvent>) -> Result<()> {
+    let input = fs::read(&path).await?;
+
+    let _ = ui_addr.send_async(UiEvent::ImportedZipMember(member, input)).await;
+    Ok(())
+}
+
+// Le même membre relu dans l'autre sauvegarde, l'autre sauvegarde est l'état de départ
+async fn compare_zip_member(
+    member: String, current: Vec<u8>, path: String, ui_addr: Sender<UiEvent>,
+) -> Result<()> {
+    let input = fs::read(&path).await?;
+    let other = match SaveGame::load(path.clone(), &input)? {
+        SaveGame::MassEffect1 { save_game, .. } => save_game.export_member(&member)?,
+        _ => bail!("`{}` isn't a Mass Effect 1 save", path),
+    };
+    let changes = task::spawn_blocking(move || byte_diff(&other, &current)).await?;
+
+    let _ = ui_addr.send_async(UiEvent::ComparedZipMember(member, path, changes)).await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::Result;
+    use indexmap::IndexMap;
+    use std::{fs::File, io::Read};
+
+    use crate::{
+        autosave::AutosaveTimer,
+        save_data::{
+            mass_effect_1::data::Property,
+            shared::{location, Vector},
+        },
+        unreal,
+    };
+
+    use super::*;
+
+    #[test]
+    fn import_head_morph_only_changes_head_morph() -> Result<()> {
+        let mut input = Vec::new();
+        {
+            let mut file = File::open("test/ME3Save.pcsav")?;
+            file.read_to_end(&mut input)?;
+        }
+
+        let me3_save_game: Me3SaveGame = unreal::Deserializer::from_bytes(&input)?;
+        let before = unreal::Serializer::to_byte_buf(&me3_save_game)?;
+        let old_head_morph =
+            unreal::Serializer::to_byte_buf(&me3_save_game.player.appearance.head_morph)?;
+
+        // Même modèle de tête que la sauvegarde
+        let current = me3_save_game.player.appearance.head_morph.head_morph.as_ref().unwrap();
+        let lod = |vertices: &Vec<Vector>| vec![Vector::default(); vertices.len()];
+
+        let mut offset_bones = IndexMap::new();
+        offset_bones.insert(ImString::new("jaw").into(), Vector::default());
+        let head_morph = HeadMorph {
+            hair_mesh: ImString::new("BIOG_HMM_HIR_PRO.Test.HMM_Import_Test_Hair").into(),
+            accessory_mesh: Vec::new(),
+            morph_features: IndexMap::new(),
+            offset_bones,
+            lod0_vertices: lod(&current.lod0_vertices),
+            lod1_vertices: lod(&current.lod1_vertices),
+            lod2_vertices: lod(&current.lod2_vertices),
+            lod3_vertices: lod(&current.lod3_vertices),
+            scalar_parameters: IndexMap::new(),
+            vector_parameters: IndexMap::new(),
+            texture_parameters: IndexMap::new(),
+        };
+
+        let mut save_game =
+            SaveGame::MassEffect3 { file_path: String::new(), save_game: Box::new(me3_save_game) };
+        assert!(save_game.import_head_morph(head_morph)?.is_empty());
+
+        let me3_save_game = match save_game {
+            SaveGame::MassEffect3 { save_game, .. } => save_game,
+            _ => unreachable!(),
+        };
+        let after = unreal::Serializer::to_byte_buf(&me3_save_game)?;
+        let new_head_morph =
+            unreal::Serializer::to_byte_buf(&me3_save_game.player.appearance.head_morph)?;
+
+        // Le nouveau head morph est unique grâce au nom de la coiffure
+        let start = after
+            .windows(new_head_morph.len())
+            .position(|window| window == new_head_morph.as_slice())
+            .expect("head morph not found");
+
+        // Tout ce qui est avant et après le head morph est identique
+        assert_eq!(before[..start], after[..start]);
+        assert_eq!(before[start + old_head_morph.len()..], after[start + new_head_morph.len()..]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn name_length_refused() -> Result<()> {
+        let path = std::env::temp_dir().join("trilogy_save_editor_name_length_refused.pcsav");
+        std::fs::copy("test/ME2Save.pcsav", &path)?;
+        let before = std::fs::read(&path)?;
+        let path_str = path.to_string_lossy().into_owned();
+        let mut save_game = SaveGame::load(path_str.clone(), &before)?;
+        let set_name = |save_game: &mut SaveGame, name: &str| {
+            if let SaveGame::MassEffect2 { save_game, .. } = save_game {
+                save_game.player.first_name = ImString::new(name).into();
+            }
+        };
+
+        // Limite : le préfixe de longueur est recalculé
+        let name = "a".repeat(*limits::NAME_LEN.end());
+        set_name(&mut save_game, &name);
+        let output = save_game.to_bytes()?;
+        match SaveGame::load(String::new(), &output)? {
+            SaveGame::MassEffect2 { save_game, .. } => {
+                assert_eq!(save_game.player.first_name.to_str(), name)
+            }
+            _ => panic!("not a Mass Effect 2 save"),
+        }
+        assert!(save_game.range_report().iter().all(|(path, _)| path != "player.first_name"));
+
+        // Un caractère de trop ou un nom vide : signalé et l'enregistrement est refusé
+        let (ui_addr, rx) = flume::unbounded();
+        for name in &[format!("{}a", name), String::new()] {
+            set_name(&mut save_game, name);
+            let report = save_game.range_report();
+            let (_, error) = report.iter().find(|(path, _)| path == "player.first_name").unwrap();
+            assert!(error.starts_with("Invalid character name"));
+
+            let result = save_save(
+                path_str.clone(),
+                save_game.clone(),
+                SaveChecks::None,
+                false,
+                Sender::clone(&ui_addr),
+            )
+            .await;
+            assert!(result.is_err());
+            assert!(rx.try_recv().is_err());
+            assert_eq!(std::fs::read(&path)?, before);
+        }
+
+        // Sauvegarde automatique : ignorée avec une seule notification
+        let (event_addr, event_rx) = flume::unbounded();
+        event_addr.send(MainEvent::AutoSave(save_game.clone()))?;
+        event_addr.send(MainEvent::AutoSave(save_game))?;
+        drop(event_addr);
+        event_loop(event_rx, ui_addr).await;
+
+        let events: Vec<_> = rx.drain().collect();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], UiEvent::Notification(_)));
+        assert_eq!(std::fs::read(&path)?, before);
+
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn read_location() -> Result<()> {
+        let files = [
+            ("test/Clare00_AutoSave.MassEffectSave", "bioa_end00", Some("Ending")),
+            ("test/ME2Save.pcsav", "BioP_OmgHub", Some("Omega")),
+            ("test/ME2LegSave.pcsav", "Biop_Nor", Some("Normandy")),
+            ("test/ME3Save.pcsav", "BioP_Nor", Some("Normandy")),
+        ];
+        for (file, map, name) in &files {
+            let mut save_game = SaveGame::load(file.to_string(), &std::fs::read(file)?)?;
+            assert_eq!(save_game.location(), *map);
+            assert_eq!(location::location_name(save_game.game_version(), map), *name);
+
+            // Relu depuis les octets
+            save_game.set_location("BioP_CitHub");
+            let save_game = SaveGame::load(file.to_string(), &save_game.to_bytes()?)?;
+            assert_eq!(save_game.location(), "BioP_CitHub");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn plot_by_name() -> Result<()> {
+        let input = std::fs::read("test/ME2Save.pcsav")?;
+        let mut save_game = SaveGame::load(String::new(), &input)?;
+
+        let loyal = save_game.get_plot_bool("Garrus is loyal")?;
+        save_game.set_plot_bool("garrus is loyal", !loyal)?;
+        save_game.set_plot_int("Paragon points", 1234)?;
+
+        // Relu depuis les octets
+        let save_game = SaveGame::load(String::new(), &save_game.to_bytes()?)?;
+        assert_eq!(save_game.get_plot_bool("Garrus is loyal")?, !loyal);
+        assert_eq!(save_game.get_plot_int("Paragon points")?, 1234);
+
+        let err = save_game.get_plot_bool("Nobody is loyal").unwrap_err();
+        assert_eq!(err.to_string(), "Unknown plot `Nobody is loyal` for MassEffect2");
+        let err = save_game.get_plot_int("Garrus is loyal").unwrap_err();
+        assert_eq!(err.to_string(), "`Garrus is loyal` is a boolean plot, not an integer");
+        Ok(())
+    }
+
+    #[test]
+    fn iter_plots() -> Result<()> {
+        let input = std::fs::read("test/ME2Save.pcsav")?;
+        let mut save_game = SaveGame::load(String::new(), &input)?;
+        save_game.set_plot_int("Paragon points", 1234)?;
+        let names = plot_db::for_game(GameVersion::MassEffect2)?.names();
+
+        // Tous les plots, nommés ou non
+        let booleans: Vec<_> = save_game.iter_plot_bools(&names).collect();
+        assert_eq!(booleans.len(), save_game.plot_booleans().len());
+        assert!(booleans.iter().any(|(_, name, _)| name.is_none()));
+        let loyal = save_game.find_bool("Garrus is loyal")?;
+        assert_eq!(
+            booleans[loyal],
+            (loyal, Some("Garrus is loyal"), save_game.get_plot_bool("Garrus is loyal")?)
+        );
+
+        let paragon = save_game.find_int("Paragon points")?;
+        let ints: Vec<_> = save_game.iter_plot_ints(&names).collect();
+        assert_eq!(ints[paragon], (paragon, Some("Paragon points"), 1234));
+        assert!(ints.iter().enumerate().all(|(i, (id, ..))| i == *id));
+        if let SaveGame::MassEffect2 { save_game: me2_save_game, .. } = &save_game {
+            assert_eq!(ints.len(), me2_save_game.plot.int_variables.len());
+            assert_eq!(
+                save_game.iter_plot_floats().count(),
+                me2_save_game.plot.float_variables.len()
+            );
+        }
+
+        // ME3 : ids des maps
+        let input = std::fs::read("test/ME3Save.pcsav")?;
+        let save_game = SaveGame::load(String::new(), &input)?;
+        let names = plot_db::for_game(GameVersion::MassEffect3)?.names();
+        if let SaveGame::MassEffect3 { save_game: me3_save_game, .. } = &save_game {
+            let ints: Vec<_> = save_game.iter_plot_ints(&names).collect();
+            assert_eq!(ints.len(), me3_save_game.plot.int_variables.len());
+            for (id, name, value) in ints {
+                assert_eq!(me3_save_game.plot.int_variables[&(id as i32)], value);
+                assert_eq!(name, names.ints.get(&id).map(String::as_str));
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn plot_at_index() -> Result<()> {
+        let input = std::fs::read("test/ME2Save.pcsav")?;
+        let mut save_game = SaveGame::load(String::new(), &input)?;
+        let names = plot_db::for_game(GameVersion::MassEffect2)?.names();
+
+        let loyal = save_game.find_bool("Garrus is loyal")?;
+        let value = save_game.get_plot_bool("Garrus is loyal")?;
+        assert_eq!(save_game.plot_bool_at(loyal, &names), Some((Some("Garrus is loyal"), value)));
+        save_game.set_plot(PlotId::Boolean(loyal), &(!value).to_string())?;
+        assert_eq!(save_game.plot_bool_at(loyal, &names), Some((Some("Garrus is loyal"), !value)));
+
+        let paragon = save_game.find_int("Paragon points")?;
+        save_game.set_plot(PlotId::Int(paragon), "1234")?;
+        assert_eq!(save_game.plot_int_at(paragon, &names), Some((Some("Paragon points"), 1234)));
+
+        // Hors de la sauvegarde
+        let len = save_game.plot_booleans().len();
+        assert_eq!(save_game.plot_bool_at(len, &names), None);
+        assert_eq!(save_game.plot_int_at(usize::MAX, &names), None);
+
+        // ME3 : absent de la map
+        let input = std::fs::read("test/ME3Save.pcsav")?;
+        let save_game = SaveGame::load(String::new(), &input)?;
+        assert_eq!(save_game.plot_int_at(usize::MAX, &PlotNames::default()), Some((None, 0)));
+        Ok(())
+    }
+
+    #[test]
+    fn format_version() -> Result<()> {
+        let files = [
+            ("test/Clare00_AutoSave.MassEffectSave", "Package 491, licensee 1008"),
+            ("test/ME1Leg00_QuickSave.pcsav", "Save format 50"),
+            ("test/ME2Save.pcsav", "Save format 29"),
+            ("test/ME2LegSave.pcsav", "Save format 30"),
+            ("test/ME3Save.pcsav", "Save format 59"),
+        ];
+        for (file, version) in &files {
+            let input = std::fs::read(file)?;
+            let save_game = SaveGame::load(file.to_string(), &input)?;
+            assert_eq!(save_game.format_version(), *version, "{}", file);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn merge_plots() -> Result<()> {
+        let input = std::fs::read("test/ME2Save.pcsav")?;
+        let mut save_a = SaveGame::load(String::new(), &input)?;
+        let mut save_b = SaveGame::load(String::new(), &input)?;
+        let names = PlotNames::default();
+
+        let booleans = save_a.plot_booleans().clone();
+        let only_b = booleans.iter().position(|value| !*value).unwrap();
+        let only_a = booleans.iter().position(|value| *value).unwrap();
+        let beyond_a = booleans.len() + 40;
+        save_b.set_bool(only_b, true);
+        save_b.set_bool(only_a, false);
+        save_b.set_bool(beyond_a, true);
+        save_a.set_int(10, 5);
+        save_b.set_int(10, 2);
+        save_a.set_int(11, 1);
+        save_b.set_int(11, 7);
+
+        let (merged, changes) = merge_preview(&save_a, &save_b)?;
+        let paths: Vec<_> = changes.iter().map(Change::path).collect();
+        assert!(paths.contains(&format!("plot.bool_variables[{}]", only_b).as_str()));
+        assert!(!paths.contains(&format!("plot.bool_variables[{}]", only_a).as_str()));
+        assert!(paths.contains(&"plot.int_variables[11]"));
+        assert!(!paths.contains(&"plot.int_variables[10]"));
+
+        // Le maximum de chaque plot
+        assert_eq!(merged.plot_bool_at(only_b, &names), Some((None, true)));
+        assert_eq!(merged.plot_bool_at(only_a, &names), Some((None, true)));
+        assert_eq!(merged.plot_bool_at(beyond_a, &names), Some((None, true)));
+        assert_eq!(merged.plot_int_at(10, &names), Some((None, 5)));
+        assert_eq!(merged.plot_int_at(11, &names), Some((None, 7)));
+
+        // Déjà fusionnée, plus rien ne change
+        let (_, changes) = merge_preview(&merged, &save_b)?;
+        assert!(changes.is_empty());
+
+        // Une tenue n'est pas une progression
+        save_a.set_int(318, 0);
+        save_b.set_int(318, 2);
+        let (merged, _) = merge_preview(&save_a, &save_b)?;
+        assert_eq!(merged.plot_int_at(318, &names), Some((None, 0)));
+
+        // Deux romances différentes
+        for &(_, plot_id) in ME2_DECISIONS[1].1 {
+            save_a.set_bool(plot_id, false);
+            save_b.set_bool(plot_id, false);
+        }
+        save_a.set_bool(5206, true);
+        save_b.set_bool(5207, true);
+        let (merged, _) = merge_preview(&save_a, &save_b)?;
+        assert_eq!(save_a.merge_conflicts(&merged), vec!["Mass Effect 2 romance : Thane, Garrus"]);
+        assert!(merged.merge_conflicts(&merged).is_empty());
+
+        let input = std::fs::read("test/ME3Save.pcsav")?;
+        let me3_save_game = SaveGame::load(String::new(), &input)?;
+        assert!(save_a.merge_plots(&me3_save_game).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn detect_me1_format_from_content() -> Result<()> {
+        // Extensions volontairement trompeuses
+        let files = [
+            (
+                "test/Clare00_AutoSave.MassEffectSave",
+                "Clare00_AutoSave.pcsav",
+                GameVersion::MassEffect1,
+            ),
+            (
+                "test/ME1Leg00_QuickSave.pcsav",
+                "ME1Leg00_QuickSave.MassEffectSave",
+                GameVersion::MassEffect1Leg,
+            ),
+        ];
+
+        for (file, file_name, game) in &files {
+            let mut input = Vec::new();
+            {
+                let mut file = File::open(file)?;
+                file.read_to_end(&mut input)?;
+            }
+
+            assert_eq!(detect_game(Path::new(file_name), &input), *game);
+
+            // Round-trip avec le bon format
+            let save_game = SaveGame::load(file_name.to_string(), &input)?;
+            let output = save_game.to_bytes()?;
+            let reloaded = SaveGame::load(file_name.to_string(), &output)?;
+            assert_eq!(reloaded.to_bytes()?, output);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn too_small_files() {
+        let files = ["Save.MassEffectSave", "Save_0001.pcsav", "ME3Save.pcsav"];
+        for file in &files {
+            for input in &[&[][..], &[1, 2, 3, 4, 5][..]] {
+                let err = SaveGame::load(file.to_string(), input).err().expect("should fail");
+                assert_eq!(
+                    err.to_string(),
+                    format!("File too small to be a valid save ({} bytes)", input.len())
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn quick_check_flags_corruption() -> Result<()> {
+        let files = [
+            ("test/Clare00_AutoSave.MassEffectSave", GameVersion::MassEffect1),
+            ("test/ME1Leg00_QuickSave.pcsav", GameVersion::MassEffect1Leg),
+            ("test/ME2Save.pcsav", GameVersion::MassEffect2),
+            ("test/ME2LegSave.pcsav", GameVersion::MassEffect2Leg),
+            ("test/ME3Save.pcsav", GameVersion::MassEffect3),
+        ];
+
+        for (file, game) in &files {
+            let mut input = Vec::new();
+            {
+                let mut file = File::open(file)?;
+                file.read_to_end(&mut input)?;
+            }
+            let path = Path::new(file);
+            assert_eq!(quick_check(path, &input)?, *game);
+
+            // ME1 : répertoire du zip tronqué, sinon un octet modifié
+            let corrupted = if *game == GameVersion::MassEffect1 {
+                input[..input.len() - 8].to_vec()
+            } else {
+                let mut corrupted = input.clone();
+                let middle = corrupted.len() / 2;
+                corrupted[middle] = !corrupted[middle];
+                corrupted
+            };
+            assert!(quick_check(path, &corrupted).is_err(), "{}", file);
+        }
+        assert!(quick_check(Path::new("Save_0001.pcsav"), &[]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn range_report() -> Result<()> {
+        let input = std::fs::read("test/ME2Save.pcsav")?;
+        let mut save_game = SaveGame::load(String::new(), &input)?;
+        assert!(save_game.range_report().is_empty());
+
+        if let SaveGame::MassEffect2 { save_game, .. } = &mut save_game {
+            save_game.player.level = 31;
+        }
+        let report = save_game.range_report();
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].0, "player.level");
+        assert_eq!(report[0].1, limits::range_error(&31, &limits::ME2_LEVEL).unwrap());
+
+        // Ressources sans limite dans l'UI seulement
+        if let SaveGame::MassEffect2 { save_game, .. } = &mut save_game {
+            save_game.player.level = 30;
+            save_game.player.probes = -1;
+        }
+        let paths: Vec<_> = save_game.range_report().into_iter().map(|(path, _)| path).collect();
+        assert_eq!(paths, vec!["player.probes"]);
+
+        // ME1 : propriétés du joueur
+        let input = std::fs::read("test/Clare00_AutoSave.MassEffectSave")?;
+        let save_game = SaveGame::load(String::new(), &input)?;
+        let has_credits = |save_game: &SaveGame| {
+            save_game.range_report().iter().any(|(path, _)| path == "player.credits")
+        };
+        assert!(!has_credits(&save_game));
+        if let SaveGame::MassEffect1 { save_game, .. } = &save_game {
+            let player = &save_game.player;
+            for i in 1..=player.objects.len() as i32 {
+                for property in player.get_data(i).borrow_mut().properties.iter_mut() {
+                    if let Property::Int { name_id, value, .. } = property {
+                        if player.get_name(*name_id).borrow().to_str() == "m_nResourceCredits" {
+                            *value = 10_000_000;
+                        }
+                    }
+                }
+            }
+        }
+        assert!(has_credits(&save_game));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn retry_locked_file() -> Result<()> {
+        let lock_error = || {
+            if cfg!(windows) {
+                io::Error::from_raw_os_error(32)
+            } else {
+                io::Error::from(ErrorKind::WouldBlock)
+            }
+        };
+
+        // Verrou passager, la lecture finit par passer
+        let mut attempts = 0;
+        let read = retry_locked(|| {
+            attempts += 1;
+            let result = if attempts < 3 { Err(lock_error()) } else { Ok(attempts) };
+            async move { result }
+        })
+        .await?;
+        assert_eq!(read, 3);
+
+        // Toujours verrouillé, on abandonne après les essais
+        let mut attempts = 0;
+        let read: io::Result<()> = retry_locked(|| {
+            attempts += 1;
+            let err = lock_error();
+            async move { Err(err) }
+        })
+        .await;
+        assert!(is_locked(&read.unwrap_err()));
+        assert_eq!(attempts, LOCK_RETRIES + 1);
+
+        // Les autres erreurs ne sont pas retentées
+        let mut attempts = 0;
+        let read: io::Result<()> = retry_locked(|| {
+            attempts += 1;
+            async { Err(io::Error::from(ErrorKind::NotFound)) }
+        })
+        .await;
+        assert!(read.is_err());
+        assert_eq!(attempts, 1);
+
+        let (input, being_written) = read_save_file("test/ME2Save.pcsav").await?;
+        assert_eq!(input, std::fs::read("test/ME2Save.pcsav")?);
+        assert!(!being_written);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn browse_saves_badges() -> Result<()> {
+        let dir = std::env::temp_dir().join("trilogy_save_editor_browse_saves");
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir)?;
+        }
+        std::fs::create_dir_all(&dir)?;
+
+        std::fs::copy("test/ME3Save.pcsav", dir.join("ME3Save.pcsav"))?;
+        let mut corrupted = std::fs::read("test/ME2Save.pcsav")?;
+        let middle = corrupted.len() / 2;
+        corrupted[middle] = !corrupted[middle];
+        std::fs::write(dir.join("Corrupted.pcsav"), corrupted)?;
+        std::fs::write(dir.join("notes.txt"), b"ignored")?;
+
+        let (ui_addr, rx) = flume::unbounded();
+        browse_saves(dir.to_string_lossy().into_owned(), ui_addr).await?;
+
+        match rx.try_recv()? {
+            UiEvent::BrowsedSaves(_, entries) => {
+                assert_eq!(entries.len(), 2);
+                assert!(entries[0].file_path.ends_with("Corrupted.pcsav"));
+                assert_eq!(entries[0].integrity, Err("Invalid checksum".to_owned()));
+                assert!(entries[1].file_path.ends_with("ME3Save.pcsav"));
+                assert_eq!(entries[1].integrity, Ok(GameVersion::MassEffect3));
+            }
+            _ => panic!("expected the browsed saves"),
+        }
+
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn browse_trilogy_folders() -> Result<()> {
+        let dir = std::env::temp_dir().join("trilogy_save_editor_browse_trilogy");
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir)?;
+        }
+        let me2 = dir.join("Mass Effect 2").join("Save").join("Shepard_01");
+        let me3 = dir.join("Mass Effect Legendary Edition").join("Save").join("ME3");
+        std::fs::create_dir_all(&me2)?;
+        std::fs::create_dir_all(me3.join("Jane"))?;
+        std::fs::create_dir_all(me3.join("John"))?;
+
+        std::fs::copy("test/ME2Save.pcsav", me2.join("Save_0001.pcsav"))?;
+        std::fs::copy("test/ME3Save.pcsav", me3.join("John").join("Save_0001.pcsav"))?;
+        std::fs::copy("test/ME3Save.pcsav", me3.join("Jane").join("AutoSave.pcsav"))?;
+        // Illisible, le dossier donne le jeu
+        std::fs::write(me3.join("Jane").join("Save_0002.pcsav"), b"not a save")?;
+        std::fs::write(dir.join("Save_0001.pcsav"), b"not a save")?;
+
+        let (ui_addr, rx) = flume::unbounded();
+        browse_saves(dir.to_string_lossy().into_owned(), ui_addr).await?;
+        let entries = match rx.try_recv()? {
+            UiEvent::BrowsedSaves(_, entries) => entries,
+            _ => panic!("expected the browsed saves"),
+        };
+        assert_eq!(entries.len(), 5);
+
+        let groups = group_saves(&dir, entries);
+        let games: Vec<_> = groups.iter().map(|group| group.game).collect();
+        assert_eq!(games, [Some(GameVersion::MassEffect2), Some(GameVersion::MassEffect3), None]);
+
+        let me3_characters: Vec<_> =
+            groups[1].characters.iter().map(|(name, saves)| (name.as_str(), saves.len())).collect();
+        assert_eq!(me3_characters, [("Jane", 2), ("John", 1)]);
+        assert_eq!(groups[2].characters[0].0, "");
+
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn auto_save_writes_checked_save() -> Result<()> {
+        let (ui_addr, _rx) = flume::unbounded();
+
+        let dir = std::env::temp_dir().join("trilogy_save_editor_auto_save");
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir)?;
+        }
+        std::fs::create_dir_all(&dir)?;
+        let path = dir.join("Save_0001.pcsav");
+        let backup = dir.join("Save_0001.pcsav.bak");
+        std::fs::copy("test/ME2Save.pcsav", &path)?;
+
+        let file_path = path.to_string_lossy().into_owned();
+        let mut save_game = SaveGame::load(file_path, &std::fs::read(&path)?)?;
+        save_game.set_plot(PlotId::Int(2), "1234")?;
+
+        // Le minuteur déclenche l'écriture
+        let mut timer = AutosaveTimer::default();
+        timer.edited(0.0);
+        assert!(!timer.poll(29.0, Some(30)));
+        assert!(timer.poll(30.0, Some(30)));
+        auto_save(save_game.clone(), false, Sender::clone(&ui_addr)).await?;
+
+        assert!(backup.exists());
+        assert_eq!(std::fs::read(&path)?, save_game.to_bytes()?);
+
+        // Rien n'a changé depuis
+        std::fs::remove_file(&backup)?;
+        auto_save(save_game, false, ui_addr).await?;
+        assert!(!backup.exists());
+
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn open_save_yields_parsed_save() -> Result<()> {
+        let (ui_addr, rx) = flume::unbounded();
+        open_save("test/ME3Save.pcsav".to_owned(), false, ui_addr).await?;
+
+        match rx.try_recv()? {
+            UiEvent::OpenedSave(save_game) => {
+                assert_eq!(save_game.game_version(), GameVersion::MassEffect3);
+                assert_eq!(save_game.file_path(), "test/ME3Save.pcsav");
+            }
+            _ => panic!("expected the opened save"),
+        }
+        assert!(matches!(rx.try_recv()?, UiEvent::Notification("Opened")));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn open_save_checksum_mismatch() -> Result<()> {
+        let mut input = std::fs::read("test/ME3Save.pcsav")?;
+        let (stored, computed) = checksums(GameVersion::MassEffect3, &input)?.unwrap();
+        assert_eq!(stored, computed);
+
+        let len = input.len();
+        input[len - 1] ^= 0xFF;
+        let (stored, computed) = checksums(GameVersion::MassEffect3, &input)?.unwrap();
+        assert_ne!(stored, computed);
+        assert_eq!(checksums(GameVersion::MassEffect1, &input)?, None);
+
+        let path = std::env::temp_dir().join("trilogy_save_editor_checksum.pcsav");
+        std::fs::write(&path, &input)?;
+
+        // La sauvegarde s'ouvre quand même, avec un avertissement
+        let (ui_addr, rx) = flume::unbounded();
+        open_save(path.to_string_lossy().into_owned(), false, ui_addr).await?;
+        let save_game = match rx.try_recv()? {
+            UiEvent::OpenedSave(save_game) => save_game,
+            _ => panic!("expected the opened save"),
+        };
+        assert!(matches!(rx.try_recv()?, UiEvent::Notification("Opened")));
+        match rx.try_recv()? {
+            UiEvent::Error(err) => assert!(err.to_string().starts_with("The stored checksum")),
+            _ => panic!("expected the checksum warning"),
+        }
+        assert!(rx.try_recv().is_err());
+
+        // L'aller-retour échoue sur le checksum, seul son avertissement est affiché
+        let (ui_addr, rx) = flume::unbounded();
+        open_save(path.to_string_lossy().into_owned(), true, ui_addr).await?;
+        assert!(matches!(rx.try_recv()?, UiEvent::OpenedSave(_)));
+        assert!(matches!(rx.try_recv()?, UiEvent::Notification("Opened")));
+        match rx.try_recv()? {
+            UiEvent::Error(err) => assert!(err.to_string().starts_with("The stored checksum")),
+            _ => panic!("expected the checksum warning"),
+        }
+        assert!(rx.try_recv().is_err());
+
+        // Recalculé à l'enregistrement
+        let output = save_game.to_bytes()?;
+        let (stored, computed) = checksums(GameVersion::MassEffect3, &output)?.unwrap();
+        assert_eq!(stored, computed);
+
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn open_warnings_combined() {
+        assert!(open_warning(None, Some((1, 1)), None).is_none());
+
+        // Relecture en cours et checksum dans le même popup
+        let err =
+            open_warning(Some("a.pcsav".to_owned()), Some((1, 2)), Some(anyhow!("round trip")))
+                .expect("warning");
+        let messages: Vec<_> = err.chain().map(|cause| cause.to_string()).collect();
+        assert_eq!(messages.len(), 2);
+        assert!(messages[0].starts_with("`a.pcsav` changed while being read"));
+        assert!(messages[1].starts_with("The stored checksum (00000001)"));
+
+        let err = open_warning(None, None, Some(anyhow!("round trip"))).expect("warning");
+        assert_eq!(err.to_string(), "round trip");
+    }
+
+    #[tokio::test]
+    async fn verify_on_open() -> Result<()> {
+        // Sauvegarde entièrement supportée, aucun avertissement
+        let (ui_addr, rx) = flume::unbounded();
+        open_save("test/ME2Save.pcsav".to_owned(), true, ui_addr).await?;
+        assert!(matches!(rx.try_recv()?, UiEvent::OpenedSave(_)));
+        assert!(matches!(rx.try_recv()?, UiEvent::Notification("Opened")));
+        assert!(rx.try_recv().is_err());
+
+        // Le checksum corrompu est la seule différence
+        let mut input = std::fs::read("test/ME3Save.pcsav")?;
+        let len = input.len();
+        input[len - 1] ^= 0xFF;
+        let save_game = SaveGame::load("test/ME3Save.pcsav".to_owned(), &input)?;
+        let err = save_game.verify_round_trip(&input).err().expect("checksum differs");
+        assert!(err.to_string().contains(&format!("0x{:X}", len - 4)));
+
+        let input = std::fs::read("test/Clare00_AutoSave.MassEffectSave")?;
+        let save_game = SaveGame::load("test/Clare00_AutoSave.MassEffectSave".to_owned(), &input)?;
+        save_game.verify_round_trip(&input)?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn save_preserves_mtime() -> Result<()> {
+        let path = std::env::temp_dir().join("trilogy_save_editor_mtime.pcsav");
+        std::fs::copy("test/ME2Save.pcsav", &path)?;
+        let input = std::fs::read(&path)?;
+        let old = std::time::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+        std::fs::OpenOptions::new().write(true).open(&path)?.set_modified(old)?;
+
+        let save = |preserve_mtime| {
+            let save_game = SaveGame::load(path.to_string_lossy().into_owned(), &input).unwrap();
+            let (ui_addr, _rx) = flume::unbounded();
+            save_save(
+                path.to_string_lossy().into_owned(),
+                save_game,
+                SaveChecks::All,
+                preserve_mtime,
+                ui_addr,
+            )
+        };
+
+        save(true).await?;
+        assert_eq!(std::fs::metadata(&path)?.modified()?, old);
+
+        // Par défaut, la date est celle de l'écriture
+        save(false).await?;
+        assert!(std::fs::metadata(&path)?.modified()? > old);
+
+        let _ = std::fs::remove_file(backup_path(&path).unwrap());
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn save_confirms_changes() -> Result<()> {
+        let path = std::env::temp_dir().join("trilogy_save_editor_confirm.pcsav");
+        let _ = std::fs::remove_file(backup_path(&path).unwrap());
+        std::fs::copy("test/ME2Save.pcsav", &path)?;
+        let before = std::fs::read(&path)?;
+        let mut save_game = SaveGame::load(path.to_string_lossy().into_owned(), &before)?;
+        if let SaveGame::MassEffect2 { save_game, .. } = &mut save_game {
+            save_game.player.level = 30;
+        }
+
+        // Rien n'est écrit, l'UI liste les différences avec le fichier
+        let (ui_addr, rx) = flume::unbounded();
+        let file_path = path.to_string_lossy().into_owned();
+        let changed = save_game.clone();
+        save_save(file_path.clone(), changed, SaveChecks::All, false, Sender::clone(&ui_addr))
+            .await?;
+        match rx.try_recv()? {
+            UiEvent::ConfirmSave(confirm, changes) => {
+                assert_eq!(confirm, file_path);
+                let paths: Vec<_> = changes.iter().map(Change::path).collect();
+                assert_eq!(paths, ["player.level"]);
+            }
+            _ => panic!("expected the save confirmation"),
+        }
+        assert_eq!(std::fs::read(&path)?, before);
+
+        // Une fois confirmé
+        save_save(file_path, save_game.clone(), SaveChecks::Size, false, ui_addr).await?;
+        assert!(matches!(rx.try_recv()?, UiEvent::SavedSave(_)));
+        assert!(pending_changes(&path, &save_game).await?.is_empty());
+
+        let _ = std::fs::remove_file(backup_path(&path).unwrap());
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn save_read_only() -> Result<()> {
+        let path = std::env::temp_dir().join("trilogy_save_editor_read_only.pcsav");
+        let backup = backup_path(&path).unwrap();
+        let _ = std::fs::remove_file(&backup);
+        std::fs::copy("test/ME2Save.pcsav", &path)?;
+        let before = std::fs::read(&path)?;
+        let save_game = SaveGame::load(path.to_string_lossy().into_owned(), &before)?;
+
+        let mut permissions = std::fs::metadata(&path)?.permissions();
+        permissions.set_readonly(true);
+        std::fs::set_permissions(&path, permissions.clone())?;
+        assert!(is_read_only(&path).await);
+        assert!(!is_read_only(Path::new("test/missing.pcsav")).await);
+
+        // Rien n'est écrit, l'UI propose d'enregistrer une copie
+        let (ui_addr, rx) = flume::unbounded();
+        save_save(path.to_string_lossy().into_owned(), save_game, SaveChecks::All, false, ui_addr)
+            .await?;
+        match rx.try_recv()? {
+            UiEvent::ReadOnlySave(read_only) => assert_eq!(Path::new(&read_only), path),
+            _ => panic!("expected the read-only prompt"),
+        }
+        assert_eq!(std::fs::read(&path)?, before);
+        assert!(!backup.exists());
+
+        permissions.set_readonly(false);
+        std::fs::set_permissions(&path, permissions)?;
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn auto_save_read_only() -> Result<()> {
+        let path = std::env::temp_dir().join("trilogy_save_editor_auto_save_read_only.pcsav");
+        std::fs::copy("test/ME2Save.pcsav", &path)?;
+        let before = std::fs::read(&path)?;
+        let mut save_game = SaveGame::load(path.to_string_lossy().into_owned(), &before)?;
+        save_game.set_plot(PlotId::Int(2), "1234")?;
+
+        let mut permissions = std::fs::metadata(&path)?.permissions();
+        permissions.set_readonly(true);
+        std::fs::set_permissions(&path, permissions.clone())?;
+
+        // Deux délais écoulés, une seule notification et aucune erreur
+        let (event_addr, event_rx) = flume::unbounded();
+        let (ui_addr, rx) = flume::unbounded();
+        event_addr.send(MainEvent::AutoSave(save_game.clone()))?;
+        event_addr.send(MainEvent::AutoSave(save_game))?;
+        drop(event_addr);
+        event_loop(event_rx, ui_addr).await;
+
+        let events: Vec<_> = rx.drain().collect();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], UiEvent::Notification(_)));
+        assert_eq!(std::fs::read(&path)?, before);
+
+        permissions.set_readonly(false);
+        std::fs::set_permissions(&path, permissions)?;
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn open_save_errors_have_hint() -> Result<()> {
+        let (ui_addr, _rx) = flume::unbounded();
+
+        // Fichier inexistant
+        let err = open_save("test/missing.pcsav".to_owned(), false, Sender::clone(&ui_addr))
+            .await
+            .err()
+            .expect("missing file should fail");
+        assert_eq!(
+            error_hint(&err),
+            Some("The file doesn't exist anymore, it may have been moved or deleted.")
+        );
+
+        // Fichier tronqué
+        let mut input = Vec::new();
+        {
+            let mut file = File::open("test/ME3Save.pcsav")?;
+            file.read_to_end(&mut input)?;
+        }
+        let path = std::env::temp_dir().join("trilogy_save_editor_truncated.pcsav");
+        std::fs::write(&path, &input[..input.len() / 2])?;
+
+        let err = open_save(path.to_string_lossy().into_owned(), false, Sender::clone(&ui_addr))
+            .await
+            .err()
+            .expect("truncated file should fail");
+        std::fs::remove_file(&path)?;
+
+        assert!(err.to_string().starts_with("unexpected end of file"));
+        assert_eq!(
+            error_hint(&err),
+            Some("The save seems truncated, try to restore the `.bak` backup made by the editor.")
+        );
+
+        // Flux deflate abîmé dans le zip d'une sauvegarde ME1
+        let mut input = std::fs::read("test/Clare00_AutoSave.MassEffectSave")?;
+        let zip_offset = u32::from_le_bytes([input[8], input[9], input[10], input[11]]) as usize;
+        let name_len = u16::from_le_bytes([input[zip_offset + 26], input[zip_offset + 27]]);
+        let extra_len = u16::from_le_bytes([input[zip_offset + 28], input[zip_offset + 29]]);
+        let data_start = zip_offset + 30 + name_len as usize + extra_len as usize;
+        for byte in &mut input[data_start + 100..data_start + 200] {
+            *byte = !*byte;
+        }
+        let path = std::env::temp_dir().join("trilogy_save_editor_damaged.MassEffectSave");
+        std::fs::write(&path, &input)?;
+
+        let err = open_save(path.to_string_lossy().into_owned(), false, Sender::clone(&ui_addr))
+            .await
+            .err()
+            .expect("damaged member should fail");
+        std::fs::remove_file(&path)?;
+
+        assert!(err.chain().any(|cause| cause.is::<TruncatedMember>()));
+        assert_eq!(
+            error_hint(&err),
+            Some("The compressed data is damaged and can't be rebuilt by the editor, restore the `.bak` backup or a copy of the save.")
+        );
+
+        Ok(())
+    }
 }
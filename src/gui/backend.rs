@@ -21,7 +21,7 @@
 // From https://github.com/Yatekii/imgui-wgpu-rs/blob/master/examples/hello_world.rs
 
 use clipboard::{ClipboardContext, ClipboardProvider};
-use imgui::{ClipboardBackend, Context, FontConfig, FontSource, ImStr, ImString, Ui};
+use imgui::{ClipboardBackend, ConfigFlags, Context, FontConfig, FontSource, ImStr, ImString, Ui};
 use imgui_wgpu::{Renderer, RendererConfig};
 use imgui_winit_support::{HiDpiMode, WinitPlatform};
 use std::time::{Duration, Instant};
@@ -126,6 +126,13 @@ pub fn init(title: &str, width: f64, height: f64) -> Backend {
     Backend { window, event_loop, surface, device, queue, swap_chain, imgui, platform, renderer }
 }
 
+// Réglages de l'UI, appliqués par le backend avant chaque frame
+pub struct FrameSettings {
+    pub font_scale: f32,
+    // Flèches pour se déplacer dans l'arbre, Entrée pour éditer, Tab entre les champs
+    pub keyboard_navigation: bool,
+}
+
 pub struct Backend {
     window: Window,
     event_loop: EventLoop<()>,
@@ -139,9 +146,10 @@ pub struct Backend {
 }
 
 impl Backend {
+    // `ui_builder` choisit l'échelle de la police, appliquée à la frame suivante
     pub fn main_loop<F>(self, mut ui_builder: F)
     where
-        F: FnMut(&mut bool, &mut Ui) + 'static,
+        F: FnMut(&mut bool, &mut FrameSettings, &mut Ui) + 'static,
     {
         let Backend {
             window,
@@ -160,6 +168,8 @@ impl Backend {
         let mut last_frame = Instant::now();
         let mut last_cursor = None;
         let mut run = true;
+        let mut settings = FrameSettings { font_scale: 1.0, keyboard_navigation: false };
+        let base_font_scale = imgui.io().font_global_scale;
 
         event_loop.run(move |event, _, control_flow| {
             *control_flow = ControlFlow::Poll;
@@ -183,9 +193,8 @@ impl Backend {
 
                     swap_chain = device.create_swap_chain(&surface, &sc_desc);
                 }
-                Event::WindowEvent { event: WindowEvent::CloseRequested, .. } => {
-                    *control_flow = ControlFlow::Exit;
-                }
+                // L'UI peut annuler la fermeture en remettant `run` à `true`
+                Event::WindowEvent { event: WindowEvent::CloseRequested, .. } => run = false,
                 Event::MainEventsCleared => window.request_redraw(),
                 Event::RedrawEventsCleared => {
                     // Prevent CPU tanking when minimized
@@ -211,8 +220,12 @@ impl Backend {
                         .prepare_frame(imgui.io_mut(), &window)
                         .expect("Failed to prepare frame");
 
+                    let io = imgui.io_mut();
+                    io.font_global_scale = base_font_scale * settings.font_scale;
+                    io.config_flags
+                        .set(ConfigFlags::NAV_ENABLE_KEYBOARD, settings.keyboard_navigation);
                     let mut ui = imgui.frame();
-                    ui_builder(&mut run, &mut ui);
+                    ui_builder(&mut run, &mut settings, &mut ui);
 
                     if !run {
                         *control_flow = ControlFlow::Exit;
@@ -1,6 +1,8 @@
 use imgui::{im_str, sys, Condition, ImStr, ImString, TreeNode, TreeNodeFlags, TreeNodeToken, Ui};
 use std::marker::PhantomData;
 
+use crate::event_handler::MainEvent;
+
 use super::Gui;
 
 impl<'ui> Gui<'ui> {
@@ -51,6 +53,20 @@ impl<'ui> Gui<'ui> {
         }
     }
 
+    pub fn draw_wiki_link(&self, ident: &str, url: Option<&String>) {
+        let ui = self.ui;
+
+        if let Some(url) = url {
+            ui.same_line();
+            if ui.small_button(&im_str!("Wiki##{}", ident)) {
+                let _ = self.event_addr.send(MainEvent::OpenUrl(url.clone()));
+            }
+            if ui.is_item_hovered() {
+                ui.tooltip_text(url);
+            }
+        }
+    }
+
     pub fn table_next_row(&self) {
         unsafe {
             sys::igTableNextRow(sys::ImGuiTableRowFlags_None as i32, 19.0);
@@ -5,26 +5,36 @@ use std::{
     cmp::Ordering,
 };
 
-use crate::save_data::{
-    mass_effect_1::{
-        data::{ArrayType, Data, Property, StructType},
-        known_plot::Me1KnownPlot,
-        player::{Name, Player},
-        Me1SaveGame,
+use crate::{
+    event_handler::{GameVersion, MainEvent},
+    save_data::{
+        mass_effect_1::{
+            data::{ArrayType, Data, Property, StructType},
+            known_plot::Me1KnownPlot,
+            player::{Name, Player},
+            Me1SaveGame,
+        },
+        mass_effect_1_leg::{
+            self,
+            player::{Inventory, ItemFilter, ItemLevel},
+            Me1LegSaveData,
+        },
+        shared::{
+            limits,
+            player::{Notoriety, Origin},
+            plot::{Me1PlotTable, PlotCategory, QuestState, SideQuest},
+        },
+        summary::CharacterSummary,
+        ImguiString, List, RawUi,
     },
-    mass_effect_1_leg::{self, Me1LegSaveData},
-    shared::{
-        player::{Notoriety, Origin},
-        plot::{Me1PlotTable, PlotCategory},
-    },
-    ImguiString, List, RawUi,
 };
 
-use super::{Gui, KnownPlotsState};
+use super::{Gui, KnownPlotsState, UiEvent};
 
 impl<'ui> Gui<'ui> {
     pub fn draw_mass_effect_1_leg(
         &self, save_game: &mut Me1LegSaveData, known_plots: &KnownPlotsState,
+        inventory_filter: &mut ItemFilter,
     ) -> Option<()> {
         let ui = self.ui;
 
@@ -36,7 +46,8 @@ impl<'ui> Gui<'ui> {
             if let Some(_t) = TabItem::new(im_str!("General")).begin(ui);
             if let Some(_t) = ChildWindow::new(im_str!("scroll")).begin(ui);
             then {
-                self.draw_me1_leg_general(save_game);
+                self.draw_character_summary(&CharacterSummary::me1_leg(save_game));
+                self.draw_me1_leg_general(save_game, inventory_filter);
             }
         }
         // Plot
@@ -67,9 +78,11 @@ impl<'ui> Gui<'ui> {
         Some(())
     }
 
-    fn draw_me1_leg_general(&self, save_game: &mut Me1LegSaveData) -> Option<()> {
+    fn draw_me1_leg_general(
+        &self, save_game: &mut Me1LegSaveData, inventory_filter: &mut ItemFilter,
+    ) -> Option<()> {
         let ui = self.ui;
-        let Me1LegSaveData { plot, player, difficulty, .. } = save_game;
+        let Me1LegSaveData { plot, player, difficulty, map_name, .. } = save_game;
         let mass_effect_1_leg::player::Player {
             is_female,
             level,
@@ -83,6 +96,7 @@ impl<'ui> Gui<'ui> {
             grenades,
             omnigel,
             face_code,
+            inventory,
             ..
         } = player;
 
@@ -96,7 +110,7 @@ impl<'ui> Gui<'ui> {
             self.set_next_item_open(true);
             if let Some(_t) = self.push_tree_node("Role-Play") {
                 self.table_next_row();
-                first_name.draw_raw_ui(self, "Name");
+                self.draw_edit_name("Name", first_name);
 
                 // Gender
                 self.table_next_row();
@@ -175,14 +189,14 @@ impl<'ui> Gui<'ui> {
             self.set_next_item_open(true);
             if let Some(_t) = self.push_tree_node("Gameplay") {
                 self.table_next_row();
-                level.draw_raw_ui(self, "Level");
+                self.draw_edit_i32_clamped("Level", level, limits::ME1_LEVEL);
                 ui.same_line();
                 self.draw_help_marker("Classic mode (1 - 60)");
 
                 self.table_next_row();
-                current_xp.draw_raw_ui(self, "Current XP");
+                self.draw_edit_f32_clamped("Current XP", current_xp, limits::POSITIVE_F32);
                 self.table_next_row();
-                talent_points.draw_raw_ui(self, "Talent Points");
+                self.draw_edit_i32_clamped("Talent Points", talent_points, limits::POSITIVE);
             }
         }
 
@@ -196,6 +210,8 @@ impl<'ui> Gui<'ui> {
             if let Some(_t) = self.push_tree_node("General") {
                 self.table_next_row();
                 difficulty.draw_raw_ui(self, "Difficulty");
+                self.table_next_row();
+                self.draw_edit_location(GameVersion::MassEffect1Leg, map_name);
             }
         }
 
@@ -222,18 +238,116 @@ impl<'ui> Gui<'ui> {
             self.set_next_item_open(true);
             if let Some(_t) = self.push_tree_node("Resources") {
                 self.table_next_row();
-                credits.draw_raw_ui(self, "Credits");
+                self.draw_edit_i32_clamped("Credits", credits, limits::ME1_CREDITS);
                 self.table_next_row();
-                medigel.draw_raw_ui(self, "Medigel");
+                self.draw_edit_i32_clamped("Medigel", medigel, limits::POSITIVE);
                 self.table_next_row();
-                grenades.draw_raw_ui(self, "Grenades");
+                self.draw_edit_f32_clamped("Grenades", grenades, limits::POSITIVE_F32);
+                self.table_next_row();
+                self.draw_edit_f32_clamped("Omnigel", omnigel, limits::POSITIVE_F32);
+            }
+        }
+
+        // Inventory
+        if let Some(_t) = self.begin_table(im_str!("inventory-table"), 1) {
+            self.table_next_row();
+            self.set_next_item_open(true);
+            if let Some(_t) = self.push_tree_node("Inventory") {
                 self.table_next_row();
-                omnigel.draw_raw_ui(self, "Omnigel");
+                self.draw_inventory_buttons(inventory);
+                self.draw_inventory_filter(inventory, inventory_filter);
             }
         }
         Some(())
     }
 
+    // Seuls les objets retenus par le filtre sont listés, l'ordre des listes ne change pas
+    fn draw_inventory_filter(&self, inventory: &mut Inventory, filter: &mut ItemFilter) {
+        let ui = self.ui;
+
+        self.table_next_row();
+        let mut by_manufacturer = filter.manufacturer_id.is_some();
+        ui.checkbox(im_str!("##by-manufacturer"), &mut by_manufacturer);
+        ui.same_line();
+        let mut manufacturer_id = filter.manufacturer_id.unwrap_or_default();
+        self.draw_edit_i32("Manufacturer", &mut manufacturer_id);
+        filter.manufacturer_id = by_manufacturer.then(|| manufacturer_id);
+
+        self.table_next_row();
+        let mut level_idx = filter.item_level.map_or(0, |level| level as usize + 1);
+        const LEVEL_LIST: [&ImStr; 12] = [
+            im_str!("Any"),
+            im_str!("None"),
+            im_str!("I"),
+            im_str!("II"),
+            im_str!("III"),
+            im_str!("IV"),
+            im_str!("V"),
+            im_str!("VI"),
+            im_str!("VII"),
+            im_str!("VIII"),
+            im_str!("IX"),
+            im_str!("X"),
+        ];
+        if self.draw_edit_enum("Level", &mut level_idx, &LEVEL_LIST) {
+            filter.item_level = level_idx.checked_sub(1).map(|idx| ItemLevel::ALL[idx]);
+        }
+
+        if !filter.is_active() {
+            return;
+        }
+        for (name, list) in inventory.lists_mut().iter_mut() {
+            let mut remove = None;
+            for (i, item) in list.iter_mut().enumerate().filter(|(_, item)| filter.matches(item)) {
+                self.table_next_row();
+                if ui.small_button(&im_str!("remove##remove-{}-{}", name, i)) {
+                    remove = Some(i);
+                }
+                ui.same_line();
+                item.draw_raw_ui(self, &format!("{} {}##filtered-{}-{}", name, i, name, i));
+            }
+            if let Some(i) = remove {
+                list.remove(i);
+            }
+        }
+    }
+
+    fn draw_inventory_buttons(&self, inventory: &Inventory) {
+        let ui = self.ui;
+
+        if ui.button(im_str!("Import inventory")) {
+            let file = tinyfiledialogs::open_file_dialog(
+                "",
+                "",
+                Some((&["*.json"], "Inventory (*.json)")),
+            );
+            if let Some(path) = file {
+                let _ = self.event_addr.send(MainEvent::ImportInventory(path));
+            }
+        }
+        ui.same_line();
+        if ui.button(im_str!("Export inventory")) {
+            let file = tinyfiledialogs::save_file_dialog_with_filter(
+                "",
+                "",
+                &["*.json"],
+                "Inventory (*.json)",
+            );
+            if let Some(path) = file {
+                match inventory.export_json() {
+                    Ok(json) => {
+                        let _ = self.event_addr.send(MainEvent::ExportInventory(path, json));
+                    }
+                    Err(err) => {
+                        let _ = self.ui_addr.send(UiEvent::Error(err));
+                    }
+                }
+            }
+        }
+        ui.same_line();
+        self.draw_help_marker("Equipped items, quick slots and backpack, without the rest of the character.\nItem IDs aren't checked, an unknown item may not show up in game.");
+    }
+
     pub fn draw_mass_effect_1(
         &self, save_game: &mut Me1SaveGame, known_plots: &KnownPlotsState,
     ) -> Option<()> {
@@ -255,6 +369,8 @@ impl<'ui> Gui<'ui> {
             if let Some(_t) = TabItem::new(im_str!("General")).begin(ui);
             if let Some(_t) = ChildWindow::new(im_str!("scroll")).begin(ui);
             then {
+                // Le joueur n'est qu'une liste de propriétés, pas de `CharacterSummary`
+                ui.text_disabled("Summary not available, the Mass Effect 1 player isn't mapped");
                 self.draw_me1_general(save_game);
             }
         }
@@ -280,7 +396,90 @@ impl<'ui> Gui<'ui> {
 
             }
         }
+        // Archive
+        if_chain! {
+            if let Some(_t) = TabItem::new(im_str!("Archive")).begin(ui);
+            if let Some(_t) = ChildWindow::new(im_str!("scroll")).begin(ui);
+            then {
+                self.draw_me1_archive(save_game);
+            }
+        }
+
+        Some(())
+    }
+
+    fn draw_me1_archive(&self, save_game: &Me1SaveGame) -> Option<()> {
+        let ui = self.ui;
 
+        let _t = self.begin_table(im_str!("archive-table"), 1)?;
+        for member in save_game.zip_members() {
+            self.table_next_row();
+            ui.text(member);
+            if save_game.is_extra_member(member) {
+                ui.same_line();
+                ui.text_disabled("(kept as is)");
+                if ui.is_item_hovered() {
+                    ui.tooltip_text("Unknown file, probably added by a mod");
+                }
+            }
+
+            // Export
+            ui.same_line();
+            if ui.small_button(&im_str!("Export##{}", member)) {
+                match save_game.export_member(member) {
+                    Ok(output) => {
+                        let file = tinyfiledialogs::save_file_dialog_with_filter(
+                            member,
+                            member,
+                            &["*.sav"],
+                            "Raw file (*.sav)",
+                        );
+                        if let Some(path) = file {
+                            let _ = self.event_addr.send(MainEvent::ExportZipMember(path, output));
+                        }
+                    }
+                    Err(err) => {
+                        let _ = self.ui_addr.send(UiEvent::Error(err));
+                    }
+                }
+            }
+            // Replace
+            ui.same_line();
+            if ui.small_button(&im_str!("Replace##{}", member)) {
+                let file = tinyfiledialogs::open_file_dialog(
+                    "",
+                    "",
+                    Some((&["*.sav"], "Raw file (*.sav)")),
+                );
+                if let Some(path) = file {
+                    let _ =
+                        self.event_addr.send(MainEvent::ImportZipMember(member.to_owned(), path));
+                }
+            }
+            // Compare
+            ui.same_line();
+            if ui.small_button(&im_str!("Compare##{}", member)) {
+                let file = tinyfiledialogs::open_file_dialog(
+                    "",
+                    "",
+                    Some((&["*.MassEffectSave"], "Mass Effect 1 save (*.MassEffectSave)")),
+                );
+                if let Some(path) = file {
+                    match save_game.export_member(member) {
+                        Ok(current) => {
+                            let _ = self.event_addr.send(MainEvent::CompareZipMember(
+                                member.to_owned(),
+                                current,
+                                path,
+                            ));
+                        }
+                        Err(err) => {
+                            let _ = self.ui_addr.send(UiEvent::Error(err));
+                        }
+                    }
+                }
+            }
+        }
         Some(())
     }
 
@@ -392,7 +591,7 @@ impl<'ui> Gui<'ui> {
                     Self::me1_find_int_property(player, &mut m_player.properties, "m_XPLevel")
                 {
                     self.table_next_row();
-                    level.draw_raw_ui(self, "Level");
+                    self.draw_edit_i32_clamped("Level", level, limits::ME1_LEVEL);
                 }
 
                 // Current XP
@@ -402,7 +601,7 @@ impl<'ui> Gui<'ui> {
                     "m_nSquadExperience",
                 ) {
                     self.table_next_row();
-                    current_xp.draw_raw_ui(self, "Current XP");
+                    self.draw_edit_i32_clamped("Current XP", current_xp, limits::POSITIVE);
                 }
             }
         }
@@ -475,7 +674,7 @@ impl<'ui> Gui<'ui> {
                     "m_nResourceCredits",
                 ) {
                     self.table_next_row();
-                    credits.draw_raw_ui(self, "Credits");
+                    self.draw_edit_i32_clamped("Credits", credits, limits::ME1_CREDITS);
                 }
 
                 // Medigel
@@ -485,7 +684,7 @@ impl<'ui> Gui<'ui> {
                     "m_fResourceMedigel",
                 ) {
                     self.table_next_row();
-                    medigel.draw_raw_ui(self, "Medigel");
+                    self.draw_edit_f32_clamped("Medigel", medigel, limits::POSITIVE_F32);
                 }
 
                 // Grenades
@@ -495,7 +694,7 @@ impl<'ui> Gui<'ui> {
                     "m_nResourceGrenades",
                 ) {
                     self.table_next_row();
-                    grenades.draw_raw_ui(self, "Grenades");
+                    self.draw_edit_i32_clamped("Grenades", grenades, limits::POSITIVE);
                 }
 
                 // Salvage
@@ -505,7 +704,7 @@ impl<'ui> Gui<'ui> {
                     "m_fResourceSalvage",
                 ) {
                     self.table_next_row();
-                    omnigel.draw_raw_ui(self, "Omnigel");
+                    self.draw_edit_f32_clamped("Omnigel", omnigel, limits::POSITIVE_F32);
                 }
             }
         }
@@ -630,12 +829,77 @@ impl<'ui> Gui<'ui> {
             }
         }
 
+        // Side Quests
+        if_chain! {
+            if let Some(_t) = TabItem::new(im_str!("Side Quests")).begin(ui);
+            if let Some(_t) = ChildWindow::new(im_str!("scroll")).begin(ui);
+            then {
+                for (hub, known_plot) in missions.iter() {
+                    let side_quests = known_plot.side_quests();
+                    if side_quests.is_empty() {
+                        continue;
+                    }
+
+                    if let Some(_t) = self.begin_table(&im_str!("{}-quests-table", hub), 1) {
+                        self.table_next_row();
+                        if let Some(_t) = self.push_tree_node(hub) {
+                            for (quest, side_quest) in side_quests.iter() {
+                                self.table_next_row();
+                                if let Some(_t) = self.push_tree_node(quest) {
+                                    self.draw_me1_side_quest(me1_plot_table, side_quest);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
         Some(())
     }
 
+    fn draw_me1_side_quest(&self, plot_table: &mut Me1PlotTable, side_quest: &SideQuest) {
+        let SideQuest { booleans, ints } = side_quest;
+
+        if let Some(mut state) =
+            side_quest.state(&plot_table.bool_variables, &plot_table.int_variables)
+        {
+            const STATE_LIST: [&ImStr; 3] =
+                [im_str!("Not started"), im_str!("In progress"), im_str!("Complete")];
+            let mut index = state as usize;
+            self.table_next_row();
+            if self.draw_edit_enum("State", &mut index, &STATE_LIST) {
+                state = match index {
+                    0 => QuestState::NotStarted,
+                    1 => QuestState::InProgress,
+                    _ => QuestState::Complete,
+                };
+                side_quest.set_state(
+                    state,
+                    &mut plot_table.bool_variables,
+                    &mut plot_table.int_variables,
+                );
+            }
+        }
+
+        for (plot_id, desc) in booleans {
+            if let Some(mut plot) = plot_table.bool_variables.get_mut(*plot_id) {
+                self.table_next_row();
+                plot.draw_raw_ui(self, &format!("{}##quest-bool-{}", desc, plot_id));
+            }
+        }
+        // Pas de libellé pour les valeurs, elles restent numériques
+        for (plot_id, desc) in ints {
+            if let Some(plot) = plot_table.int_variables.get_mut(*plot_id) {
+                self.table_next_row();
+                plot.draw_raw_ui(self, &format!("{}##quest-int-{}", desc, plot_id));
+            }
+        }
+    }
+
     fn draw_me1_plot_category(&self, plot_table: &mut Me1PlotTable, known_plot: &PlotCategory) {
         let ui = self.ui;
-        let PlotCategory { booleans, ints } = known_plot;
+        let PlotCategory { booleans, ints, wiki } = known_plot;
 
         if booleans.is_empty() && ints.is_empty() {
             return;
@@ -650,6 +914,7 @@ impl<'ui> Gui<'ui> {
                 if let Some(mut plot) = plot {
                     self.table_next_row();
                     plot.draw_raw_ui(self, &format!("{}##bool-{}", plot_desc, plot_desc));
+                    self.draw_wiki_link(&format!("bool-{}", plot_id), wiki.booleans.get(plot_id));
                 }
             }
         }
@@ -663,6 +928,7 @@ impl<'ui> Gui<'ui> {
                 if let Some(plot) = plot {
                     self.table_next_row();
                     plot.draw_raw_ui(self, &format!("{}##int-{}", plot_desc, plot_desc));
+                    self.draw_wiki_link(&format!("int-{}", plot_id), wiki.ints.get(plot_id));
                 }
             }
         }
@@ -4,8 +4,10 @@ use imgui::{
 };
 
 use crate::{
-    event_handler::MainEvent,
+    event_handler::{GameVersion, MainEvent},
+    safe_mode::{self, Guarded},
     save_data::{
+        carry_forward::{self, ME2_IMPORT_FLAGS},
         mass_effect_2::{
             known_plot::Me2KnownPlot,
             player::{Player, Power},
@@ -14,14 +16,17 @@ use crate::{
         },
         shared::{
             appearance::{HasHeadMorph, HeadMorph},
+            limits,
+            outfits::{self, ME2_SQUAD_OUTFITS},
             player::{Notoriety, Origin},
             plot::{Me1PlotTable, PlotCategory},
         },
+        summary::CharacterSummary,
         RawUi,
     },
 };
 
-use super::{Gui, KnownPlotsState, Theme};
+use super::{Gui, KnownPlotsState, Theme, UiEvent};
 
 enum Me2Type<'a> {
     Vanilla(&'a mut Me2SaveGame),
@@ -42,7 +47,8 @@ impl<'ui> Gui<'ui> {
             if let Some(_t) = TabItem::new(im_str!("General")).begin(ui);
             if let Some(_t) = ChildWindow::new(im_str!("scroll")).begin(ui);
             then {
-                self.draw_me2_general(Me2Type::Vanilla(save_game));
+                self.draw_character_summary(&CharacterSummary::me2(save_game));
+                self.draw_me2_general(Me2Type::Vanilla(save_game), known_plots);
             }
         }
         // Plot
@@ -86,7 +92,8 @@ impl<'ui> Gui<'ui> {
             if let Some(_t) = TabItem::new(im_str!("General")).begin(ui);
             if let Some(_t) = ChildWindow::new(im_str!("scroll")).begin(ui);
             then {
-                self.draw_me2_general(Me2Type::Legendary(save_game));
+                self.draw_character_summary(&CharacterSummary::me2_leg(save_game));
+                self.draw_me2_general(Me2Type::Legendary(save_game), known_plots);
             }
         }
         // Plot
@@ -117,11 +124,12 @@ impl<'ui> Gui<'ui> {
         Some(())
     }
 
-    fn draw_me2_general(&self, save_game: Me2Type) -> Option<()> {
+    fn draw_me2_general(&self, save_game: Me2Type, known_plots: &KnownPlotsState) -> Option<()> {
         let ui = self.ui;
 
         match save_game {
             Me2Type::Vanilla(Me2SaveGame {
+                base_level_name,
                 difficulty,
                 end_game_state,
                 player,
@@ -130,6 +138,7 @@ impl<'ui> Gui<'ui> {
                 ..
             })
             | Me2Type::Legendary(Me2LegSaveGame {
+                base_level_name,
                 difficulty,
                 end_game_state,
                 player,
@@ -169,7 +178,7 @@ impl<'ui> Gui<'ui> {
                     self.set_next_item_open(true);
                     if let Some(_t) = self.push_tree_node("Role-Play") {
                         self.table_next_row();
-                        first_name.draw_raw_ui(self, "Name");
+                        self.draw_edit_name("Name", first_name);
 
                         // Gender
                         self.table_next_row();
@@ -317,6 +326,17 @@ impl<'ui> Gui<'ui> {
                         face_code.draw_raw_ui(self, "Identity Code");
                         ui.same_line();
                         self.draw_help_marker("If you change this you can display whatever you want in the menus\nin place of your `Identity Code`, which is pretty cool !");
+
+                        self.draw_import_flags(&mut plot.bool_variables, &ME2_IMPORT_FLAGS);
+                        let (_, played_me1) = ME2_IMPORT_FLAGS[0];
+                        let imported =
+                            plot.bool_variables.get(played_me1).map_or(false, |value| *value);
+                        if !imported && !me1_plot.bool_variables.is_empty() {
+                            self.table_next_row();
+                            if ui.button(im_str!("Reset ME1 decisions to new game defaults")) {
+                                carry_forward::clear_me1_import(me1_plot);
+                            }
+                        }
                     }
                 }
 
@@ -346,19 +366,23 @@ impl<'ui> Gui<'ui> {
                         self.draw_me2_class(class_name);
 
                         self.table_next_row();
-                        level.draw_raw_ui(self, "Level");
+                        self.draw_edit_i32_clamped("Level", level, limits::ME2_LEVEL);
 
                         self.table_next_row();
-                        current_xp.draw_raw_ui(self, "Current XP");
+                        self.draw_edit_f32_clamped("Current XP", current_xp, limits::POSITIVE_F32);
 
                         self.table_next_row();
-                        talent_points.draw_raw_ui(self, "Talent Points");
+                        self.draw_edit_i32_clamped(
+                            "Talent Points",
+                            talent_points,
+                            limits::POSITIVE,
+                        );
 
                         self.table_next_row();
-                        credits.draw_raw_ui(self, "Credits");
+                        self.draw_edit_i32_clamped("Credits", credits, limits::ME2_CREDITS);
 
                         self.table_next_row();
-                        medigel.draw_raw_ui(self, "Medi-gel");
+                        self.draw_edit_i32_clamped("Medi-gel", medigel, limits::POSITIVE);
                     }
                 }
 
@@ -368,22 +392,26 @@ impl<'ui> Gui<'ui> {
                     self.set_next_item_open(true);
                     if let Some(_t) = self.push_tree_node("Resources") {
                         self.table_next_row();
-                        eezo.draw_raw_ui(self, "Eezo");
+                        self.draw_edit_i32_clamped("Eezo", eezo, limits::POSITIVE);
 
                         self.table_next_row();
-                        iridium.draw_raw_ui(self, "Iridium");
+                        self.draw_edit_i32_clamped("Iridium", iridium, limits::POSITIVE);
 
                         self.table_next_row();
-                        palladium.draw_raw_ui(self, "Palladium");
+                        self.draw_edit_i32_clamped("Palladium", palladium, limits::POSITIVE);
 
                         self.table_next_row();
-                        platinum.draw_raw_ui(self, "Platinum");
+                        self.draw_edit_i32_clamped("Platinum", platinum, limits::POSITIVE);
 
                         self.table_next_row();
-                        probes.draw_raw_ui(self, "Probes");
+                        self.draw_edit_i32_clamped("Probes", probes, limits::POSITIVE);
 
                         self.table_next_row();
-                        current_fuel.draw_raw_ui(self, "Current Fuel");
+                        self.draw_edit_f32_clamped(
+                            "Current Fuel",
+                            current_fuel,
+                            limits::POSITIVE_F32,
+                        );
                     }
                 }
 
@@ -399,6 +427,26 @@ impl<'ui> Gui<'ui> {
                         difficulty.draw_raw_ui(self, "Difficulty");
                         self.table_next_row();
                         end_game_state.draw_raw_ui(self, "End Game State");
+                        self.table_next_row();
+                        self.draw_edit_location(GameVersion::MassEffect2, base_level_name);
+                    }
+                }
+
+                // Loyalty
+                if_chain! {
+                    if let Some(me2_known_plot) = known_plots.me2.as_deref();
+                    if let Some(_t) = self.begin_table(im_str!("loyalty-table"), 1);
+                    then {
+                        self.table_next_row();
+                        self.set_next_item_open(true);
+                        if let Some(_t) = self.push_tree_node("Loyalty") {
+                            for (name, plot_id) in me2_known_plot.loyalty() {
+                                if let Some(mut loyal) = plot.bool_variables.get_mut(plot_id) {
+                                    self.table_next_row();
+                                    loyal.draw_raw_ui(self, &format!("{}##loyalty-{}", name, plot_id));
+                                }
+                            }
+                        }
                     }
                 }
 
@@ -427,14 +475,19 @@ impl<'ui> Gui<'ui> {
                 None
             }
         }) {
-            let width = ui.push_item_width(200.0);
+            let width = ui.push_item_width(self.scaled(200.0));
             if ComboBox::new(im_str!("Class")).build_simple(
                 self.ui,
                 &mut class_id,
                 &CLASS_LIST,
                 &|&(_, name)| name.into(),
             ) {
-                *class_name = CLASS_LIST[class_id].0.to_owned();
+                match safe_mode::check(self.safe_mode, Guarded::ClassChange) {
+                    Ok(()) => *class_name = CLASS_LIST[class_id].0.to_owned(),
+                    Err(err) => {
+                        let _ = self.ui_addr.send(UiEvent::Error(err));
+                    }
+                }
             }
             width.pop(ui);
         }
@@ -522,7 +575,7 @@ impl<'ui> Gui<'ui> {
         known_plots: &KnownPlotsState,
     ) -> Option<()> {
         let ui = self.ui;
-        let me2_known_plot = known_plots.me2.as_ref()?;
+        let me2_known_plot = known_plots.me2.as_deref()?;
 
         let Me2KnownPlot {
             player,
@@ -637,7 +690,7 @@ impl<'ui> Gui<'ui> {
 
     fn draw_me2_plot_category(&self, plot_table: &mut PlotTable, known_plot: &PlotCategory) {
         let ui = self.ui;
-        let PlotCategory { booleans, ints } = known_plot;
+        let PlotCategory { booleans, ints, wiki } = known_plot;
 
         if booleans.is_empty() && ints.is_empty() {
             return;
@@ -652,6 +705,7 @@ impl<'ui> Gui<'ui> {
                 if let Some(mut plot) = plot {
                     self.table_next_row();
                     plot.draw_raw_ui(self, &format!("{}##bool-{}", plot_desc, plot_desc));
+                    self.draw_wiki_link(&format!("bool-{}", plot_id), wiki.booleans.get(plot_id));
                 }
             }
         }
@@ -663,7 +717,12 @@ impl<'ui> Gui<'ui> {
                 let plot = plot_table.int_variables.get_mut(*plot_id);
                 if let Some(plot) = plot {
                     self.table_next_row();
-                    plot.draw_raw_ui(self, &format!("{}##int-{}", plot_desc, plot_desc));
+                    let ident = format!("{}##int-{}", plot_desc, plot_desc);
+                    match outfits::find(&ME2_SQUAD_OUTFITS, *plot_id) {
+                        Some(squad_outfits) => self.draw_edit_outfit(&ident, plot, squad_outfits),
+                        None => plot.draw_raw_ui(self, &ident),
+                    }
+                    self.draw_wiki_link(&format!("int-{}", plot_id), wiki.ints.get(plot_id));
                 }
             }
         }
@@ -700,6 +759,8 @@ impl<'ui> Gui<'ui> {
                             .send(MainEvent::ExportHeadMorph(path, Box::new(head_morph.clone())));
                     }
                 }
+                ui.same_line();
+                self.draw_palette_buttons(head_morph);
                 // Toggle head morph
                 if !is_female {
                     ui.same_line();
@@ -724,6 +785,10 @@ impl<'ui> Gui<'ui> {
                     } = head_morph;
 
                     if let Some(_t) = self.begin_table(im_str!("plot-table"), 1) {
+                        self.table_next_row();
+                        if let Some(_t) = self.push_tree_node("Bones") {
+                            self.draw_offset_bones(offset_bones);
+                        }
                         self.table_next_row();
                         self.set_next_item_open(true);
                         if let Some(_t) = self.push_tree_node("Raw") {
@@ -1,30 +1,36 @@
 use if_chain::if_chain;
 use imgui::{
-    im_str, ChildWindow, ComboBox, ImStr, ImString, ListClipper, Selectable, TabBar, TabItem,
+    im_str, ChildWindow, ComboBox, Drag, ImStr, ImString, ListClipper, Selectable, TabBar, TabItem,
 };
 use indexmap::IndexMap;
 
 use crate::{
-    event_handler::MainEvent,
+    event_handler::{GameVersion, MainEvent},
+    safe_mode::{self, Guarded},
     save_data::{
+        carry_forward::{self, ME2_TO_ME3_ROMANCE_BOOLEANS, ME3_IMPORT_FLAGS},
         mass_effect_1::known_plot::Me1KnownPlot,
         mass_effect_2::known_plot::Me2KnownPlot,
         mass_effect_3::{
             known_plot::{Me3KnownPlot, PlotVariable},
             player::{Player, Power},
-            plot::PlotTable,
+            plot::{PlotTable, NEW_GAME_PLUS_COUNT},
             Me3SaveGame,
         },
         shared::{
-            appearance::{HasHeadMorph, HeadMorph},
+            appearance::{bone_label, HasHeadMorph, HeadMorph},
+            limits,
+            outfits::{self, ME3_SQUAD_OUTFITS},
             player::{Notoriety, Origin},
-            plot::PlotCategory,
+            plot::{BoolVec, PlotCategory},
+            Vector,
         },
+        summary::CharacterSummary,
         ImguiString, RawUi,
     },
 };
 
-use super::{Gui, KnownPlotsState, Theme};
+use super::{Gui, KnownPlotsState, Theme, UiEvent};
 
 impl<'ui> Gui<'ui> {
     pub fn draw_mass_effect_3(
@@ -40,11 +46,24 @@ impl<'ui> Gui<'ui> {
             if let Some(_t) = TabItem::new(im_str!("General")).begin(ui);
             if let Some(_t) = ChildWindow::new(im_str!("scroll")).begin(ui);
             then {
-                self.draw_me3_general(save_game);
+                self.draw_character_summary(&CharacterSummary::me3(save_game));
+                self.draw_me3_general(save_game, known_plots);
             }
         }
         // Plot
         if let Some(_t) = TabItem::new(im_str!("Plot")).begin(ui) {
+            // Report des décisions de ME2
+            if ui.button(im_str!("Import ME2 decisions")) {
+                let file = tinyfiledialogs::open_file_dialog(
+                    "",
+                    "",
+                    Some((&["*.pcsav"], "Mass Effect 2 Save (*.pcsav)")),
+                );
+
+                if let Some(path) = file {
+                    let _ = self.event_addr.send(MainEvent::ImportMe2Decisions(path));
+                }
+            }
             self.draw_me3_known_plot(
                 &mut save_game.plot,
                 &mut save_game.player_variables,
@@ -71,10 +90,20 @@ impl<'ui> Gui<'ui> {
         Some(())
     }
 
-    fn draw_me3_general(&self, save_game: &mut Me3SaveGame) -> Option<()> {
+    fn draw_me3_general(
+        &self, save_game: &mut Me3SaveGame, known_plots: &KnownPlotsState,
+    ) -> Option<()> {
         let ui = self.ui;
-        let Me3SaveGame { difficulty, end_game_state, conversation_mode, player, plot, .. } =
-            save_game;
+        let last_name_id = save_game.player.localized_last_name();
+        let Me3SaveGame {
+            base_level_name,
+            difficulty,
+            end_game_state,
+            conversation_mode,
+            player,
+            plot,
+            ..
+        } = save_game;
         let Player {
             is_female,
             class_name,
@@ -103,7 +132,10 @@ impl<'ui> Gui<'ui> {
             self.set_next_item_open(true);
             if let Some(_t) = self.push_tree_node("Role-Play") {
                 self.table_next_row();
-                first_name.draw_raw_ui(self, "Name");
+                self.draw_edit_name("First name", first_name);
+                // Le nom de famille vient des textes du jeu, il n'est pas modifiable
+                self.table_next_row();
+                ui.text_disabled(format!("Last name : localized string {}", last_name_id));
 
                 // Gender
                 self.table_next_row();
@@ -275,6 +307,8 @@ impl<'ui> Gui<'ui> {
                 self.draw_help_marker(
                     "If you change this you can display whatever you want in the menus\nin place of your `Identity Code`, which is pretty cool !"
                 );
+
+                self.draw_import_flags(&mut plot.bool_variables, &ME3_IMPORT_FLAGS);
             }
         }
 
@@ -314,25 +348,25 @@ impl<'ui> Gui<'ui> {
                 self.draw_me3_class(class_name);
 
                 self.table_next_row();
-                level.draw_raw_ui(self, "Level");
+                self.draw_edit_i32_clamped("Level", level, limits::ME3_LEVEL);
 
                 self.table_next_row();
-                current_xp.draw_raw_ui(self, "Current XP");
+                self.draw_edit_f32_clamped("Current XP", current_xp, limits::POSITIVE_F32);
 
                 self.table_next_row();
-                talent_points.draw_raw_ui(self, "Talent Points");
+                self.draw_edit_i32_clamped("Talent Points", talent_points, limits::POSITIVE);
 
                 self.table_next_row();
-                credits.draw_raw_ui(self, "Credits");
+                self.draw_edit_i32_clamped("Credits", credits, limits::ME3_CREDITS);
 
                 self.table_next_row();
-                medigel.draw_raw_ui(self, "Medi-gel");
+                self.draw_edit_i32_clamped("Medi-gel", medigel, limits::POSITIVE);
 
                 self.table_next_row();
-                grenades.draw_raw_ui(self, "Grenades");
+                self.draw_edit_i32_clamped("Grenades", grenades, limits::POSITIVE);
 
                 self.table_next_row();
-                current_fuel.draw_raw_ui(self, "Current Fuel");
+                self.draw_edit_f32_clamped("Current Fuel", current_fuel, limits::POSITIVE_F32);
             }
         }
 
@@ -350,14 +384,72 @@ impl<'ui> Gui<'ui> {
                 conversation_mode.draw_raw_ui(self, "Conversation Mode");
                 self.table_next_row();
                 end_game_state.draw_raw_ui(self, "End Game State");
+                self.table_next_row();
+                self.draw_edit_location(GameVersion::MassEffect3, base_level_name);
+
+                // New Game +
+                let label = known_plots
+                    .me3
+                    .as_deref()
+                    .and_then(|known_plot| {
+                        known_plot.general.ints.get(&(NEW_GAME_PLUS_COUNT as usize))
+                    })
+                    .map(String::as_str)
+                    .unwrap_or("New Game Plus Count");
+
+                self.table_next_row();
+                let mut count = plot.new_game_plus_count();
+                self.draw_edit_i32_clamped(label, &mut count, limits::POSITIVE);
+                if count != plot.new_game_plus_count() {
+                    plot.set_new_game_plus_count(count);
+                }
             }
         }
 
+        // Power Evolutions
+        self.draw_me3_evolutions(powers);
+
         // Bonus Powers
         self.set_next_item_open(true);
         self.draw_me3_bonus_powers(powers)
     }
 
+    fn draw_me3_evolutions(&self, powers: &mut Vec<Power>) -> Option<()> {
+        let ui = self.ui;
+
+        // Table
+        let _t = self.begin_table(im_str!("evolutions-table"), 1)?;
+
+        // Tree node
+        self.table_next_row();
+        let _t = self.push_tree_node("Power Evolutions")?;
+        ui.same_line();
+        self.draw_help_marker("Left and right branches, as in the game's power tree.");
+
+        for (i, power) in powers.iter_mut().enumerate() {
+            for rank in 4..=6 {
+                if power.rank < rank as f32 {
+                    break;
+                }
+                self.table_next_row();
+                ui.align_text_to_frame_padding();
+                ui.text(format!("{} rank {}", power.name, rank));
+
+                let mut branch = power.evolution(rank);
+                for (value, label) in [(0, "Left"), (1, "Right")].iter().copied() {
+                    ui.same_line();
+                    let label = im_str!("{}##evolution-{}-{}", label, i, rank);
+                    if ui.radio_button(&label, &mut branch, Some(value)) {
+                        if let Err(err) = power.set_evolution(rank, value) {
+                            let _ = self.ui_addr.send(UiEvent::Error(err));
+                        }
+                    }
+                }
+            }
+        }
+        Some(())
+    }
+
     fn draw_me3_class(&self, class_name: &mut ImString) {
         let ui = self.ui;
         const CLASS_LIST: [(&ImStr, &ImStr); 12] = [
@@ -394,14 +486,30 @@ impl<'ui> Gui<'ui> {
                 None
             }
         }) {
-            let width = ui.push_item_width(200.0);
+            let width = ui.push_item_width(self.scaled(200.0));
             if ComboBox::new(im_str!("Class")).build_simple(
                 self.ui,
                 &mut class_id,
                 &CLASS_LIST,
                 &|&(_, name)| name.into(),
             ) {
-                *class_name = CLASS_LIST[class_id].0.to_owned();
+                // Hors combat, c'est toujours la même classe
+                let new_class = CLASS_LIST[class_id].0.to_str();
+                let same_class = unicase::eq(
+                    new_class.trim_end_matches("NonCombat"),
+                    class_name.to_str().trim_end_matches("NonCombat"),
+                );
+                let guard = if same_class {
+                    Ok(())
+                } else {
+                    safe_mode::check(self.safe_mode, Guarded::ClassChange)
+                };
+                match guard {
+                    Ok(()) => *class_name = ImString::new(new_class),
+                    Err(err) => {
+                        let _ = self.ui_addr.send(UiEvent::Error(err));
+                    }
+                }
             }
             width.pop(ui);
         }
@@ -497,7 +605,7 @@ impl<'ui> Gui<'ui> {
         known_plots: &KnownPlotsState,
     ) -> Option<()> {
         let ui = self.ui;
-        let me3_known_plot = known_plots.me3.as_ref()?;
+        let me3_known_plot = known_plots.me3.as_deref()?;
 
         let Me3KnownPlot {
             general,
@@ -539,6 +647,9 @@ impl<'ui> Gui<'ui> {
                 if let Some(_t) = TabItem::new(title).begin(ui);
                 if let Some(_t) = ChildWindow::new(im_str!("scroll")).begin(ui);
                 then {
+                    if *title == im_str!("Romance") {
+                        self.draw_romance_history(plot_table, known_plots.me2.as_deref(), me1_imported);
+                    }
                     for (category_name, known_plot) in plot_map.iter() {
                         if let Some(_t) = self.begin_table(&im_str!("{}-table", category_name), 1) {
                             self.table_next_row();
@@ -577,7 +688,7 @@ impl<'ui> Gui<'ui> {
         }
 
         // Mass Effect 2
-        let me2_known_plot = known_plots.me2.as_ref()?;
+        let me2_known_plot = known_plots.me2.as_deref()?;
 
         let _colors = self.style_colors(Theme::MassEffect2);
         if let Some(_t) = TabItem::new(im_str!("Mass Effect 2")).begin(ui) {
@@ -593,6 +704,47 @@ impl<'ui> Gui<'ui> {
         Some(())
     }
 
+    // Romances des jeux précédents que ME3 relit pour les continuer, normalement fixées
+    // à l'import, les libellés viennent des bases de plots de ME2 et ME1
+    fn draw_romance_history(
+        &self, plot_table: &mut PlotTable, me2_known_plot: Option<&Me2KnownPlot>,
+        me1_imported: &Me1KnownPlot,
+    ) {
+        let ui = self.ui;
+
+        let me2_romances = me2_known_plot
+            .into_iter()
+            .flat_map(|me2_known_plot| me2_known_plot.romance.values())
+            .flat_map(|category| category.booleans.iter())
+            .filter(|(plot_id, _)| ME2_TO_ME3_ROMANCE_BOOLEANS.contains(*plot_id))
+            .map(|plot| ("ME2", plot));
+        let me1_romances = me1_imported
+            .player_crew
+            .get("Romance")
+            .into_iter()
+            .flat_map(|category| category.booleans.iter())
+            .map(|plot| ("ME1", plot));
+
+        if let Some(_t) = self.begin_table(im_str!("romance-history-table"), 1) {
+            self.table_next_row();
+            if let Some(_t) = self.push_tree_node("Romance history") {
+                for warning in carry_forward::me3_romance_warnings(&plot_table.bool_variables) {
+                    self.table_next_row();
+                    ui.text_colored([1.0, 0.6, 0.2, 1.0], warning);
+                }
+                for (game, (plot_id, plot_desc)) in me2_romances.chain(me1_romances) {
+                    if let Some(mut plot) = plot_table.bool_variables.get_mut(*plot_id) {
+                        self.table_next_row();
+                        plot.draw_raw_ui(
+                            self,
+                            &format!("[{}] {}##history-{}", game, plot_desc, plot_id),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
     pub fn draw_me2_imported_known_plot(
         &self, me3_plot_table: &mut PlotTable, me2_known_plot: &Me2KnownPlot,
     ) -> Option<()> {
@@ -701,7 +853,7 @@ impl<'ui> Gui<'ui> {
 
     fn draw_me3_plot_category(&self, plot_table: &mut PlotTable, known_plot: &PlotCategory) {
         let ui = self.ui;
-        let PlotCategory { booleans, ints } = known_plot;
+        let PlotCategory { booleans, ints, wiki } = known_plot;
 
         if booleans.is_empty() && ints.is_empty() {
             return;
@@ -716,6 +868,7 @@ impl<'ui> Gui<'ui> {
                 if let Some(mut plot) = plot {
                     self.table_next_row();
                     plot.draw_raw_ui(self, &format!("{}##bool-{}", plot_desc, plot_desc));
+                    self.draw_wiki_link(&format!("bool-{}", plot_id), wiki.booleans.get(plot_id));
                 }
             }
         }
@@ -727,7 +880,12 @@ impl<'ui> Gui<'ui> {
                 let plot = plot_table.int_variables.entry(*plot_id as i32).or_default();
 
                 self.table_next_row();
-                plot.draw_raw_ui(self, &format!("{}##int-{}", plot_desc, plot_desc));
+                let ident = format!("{}##int-{}", plot_desc, plot_desc);
+                match outfits::find(&ME3_SQUAD_OUTFITS, *plot_id) {
+                    Some(squad_outfits) => self.draw_edit_outfit(&ident, plot, squad_outfits),
+                    None => plot.draw_raw_ui(self, &ident),
+                }
+                self.draw_wiki_link(&format!("int-{}", plot_id), wiki.ints.get(plot_id));
             }
         }
     }
@@ -775,6 +933,65 @@ impl<'ui> Gui<'ui> {
         }
     }
 
+    // Les décisions importées ne sont pas recalculées par le jeu quand ces plots changent
+    pub fn draw_import_flags(&self, booleans: &mut BoolVec, flags: &[(&str, usize)]) {
+        let ui = self.ui;
+
+        for (label, plot_id) in flags {
+            if let Some(mut value) = booleans.get_mut(*plot_id) {
+                self.table_next_row();
+                value.draw_raw_ui(self, label);
+                ui.same_line();
+                self.draw_help_marker(
+                    "Changing this doesn't change the decisions imported from the previous game,\nthey may not match anymore."
+                );
+            }
+        }
+    }
+
+    pub fn draw_palette_buttons(&self, head_morph: &HeadMorph) {
+        let ui = self.ui;
+
+        if ui.button(im_str!("Import palette")) {
+            let file =
+                tinyfiledialogs::open_file_dialog("", "", Some((&["*.txt"], "Palette (*.txt)")));
+            if let Some(path) = file {
+                let _ = self.event_addr.send(MainEvent::ImportPalette(path));
+            }
+        }
+        ui.same_line();
+        if ui.button(im_str!("Export palette")) {
+            let file = tinyfiledialogs::save_file_dialog_with_filter(
+                "",
+                "",
+                &["*.txt"],
+                "Palette (*.txt)",
+            );
+            if let Some(path) = file {
+                let _ = self
+                    .event_addr
+                    .send(MainEvent::ExportPalette(path, head_morph.export_palette()));
+            }
+        }
+    }
+
+    // Libellés de `bone_label`, les os inconnus gardent leur nom
+    pub fn draw_offset_bones(&self, offset_bones: &mut IndexMap<ImguiString, Vector>) {
+        let ui = self.ui;
+
+        for (bone, offset) in offset_bones.iter_mut() {
+            self.table_next_row();
+            let label = bone_label(bone.to_str()).unwrap_or_else(|| bone.to_str());
+            let mut xyz = [offset.x, offset.y, offset.z];
+            let width = ui.push_item_width(self.scaled(300.0));
+            if Drag::new(&im_str!("{}##{}", label, bone)).speed(0.01).build_array(ui, &mut xyz) {
+                let [x, y, z] = xyz;
+                *offset = Vector { x, y, z };
+            }
+            width.pop(ui);
+        }
+    }
+
     pub fn draw_me3_and_le_head_morph(&self, head_morph: &mut HasHeadMorph) {
         let ui = self.ui;
         let HasHeadMorph { has_head_morph, head_morph } = head_morph;
@@ -806,6 +1023,8 @@ impl<'ui> Gui<'ui> {
                             .send(MainEvent::ExportHeadMorph(path, Box::new(head_morph.clone())));
                     }
                 }
+                ui.same_line();
+                self.draw_palette_buttons(head_morph);
                 // Toggle head morph
                 ui.same_line();
                 has_head_morph.draw_raw_ui(self, "Enable head morph");
@@ -828,6 +1047,10 @@ impl<'ui> Gui<'ui> {
                     } = head_morph;
 
                     if let Some(_t) = self.begin_table(im_str!("plot-table"), 1) {
+                        self.table_next_row();
+                        if let Some(_t) = self.push_tree_node("Bones") {
+                            self.draw_offset_bones(offset_bones);
+                        }
                         self.table_next_row();
                         self.set_next_item_open(true);
                         if let Some(_t) = self.push_tree_node("Raw") {
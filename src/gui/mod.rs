@@ -1,21 +1,43 @@
-use std::path::PathBuf;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use anyhow::Error;
 use flume::{Receiver, Sender};
 use if_chain::if_chain;
 use imgui::{
-    im_str, ChildWindow, ColorStackToken, Condition, ImString, PopupModal, ProgressBar, StyleColor,
-    TabBar, TabItem, Ui, Window,
+    im_str, ChildWindow, ColorStackToken, ComboBox, Condition, Drag, ImStr, ImString, InputInt,
+    ListClipper, MenuItem, PopupModal, ProgressBar, Selectable, StyleColor, TabBar, TabItem,
+    TreeNode, Ui, Window,
 };
 
 use crate::{
-    event_handler::{MainEvent, SaveGame},
+    autosave::AutosaveTimer,
+    config::{Config, NumberBase, FONT_SCALE_RANGE},
+    edit_log::{Change, EditLog},
+    event_handler::{error_hint, group_saves, GameVersion, MainEvent, SaveEntry, SaveGame},
+    history::History,
+    keybindings::{Action, KeyBinding, KeyBindings, ACTIONS, KEYS},
+    patch::SavePatch,
+    plot_db::{self, PlotDb},
+    revive,
+    safe_mode::{self, Guarded},
     save_data::{
-        mass_effect_1::known_plot::Me1KnownPlot,
-        mass_effect_2::known_plot::Me2KnownPlot,
+        build::Build,
+        carry_forward,
+        mass_effect_1::{known_plot::Me1KnownPlot, MemberSize, SizeBreakdown},
+        mass_effect_1_leg::player::ItemFilter,
+        mass_effect_2::{known_plot::Me2KnownPlot, plot::PlotTable as Me2PlotTable},
         mass_effect_3::known_plot::Me3KnownPlot,
-        shared::appearance::{HasHeadMorph, HeadMorph},
+        shared::{
+            appearance::HeadMorph,
+            byte_diff::{self, ByteChange},
+            plot::{PlotId, PlotNames},
+        },
     },
+    save_slot::{self, SlotKind},
+    unreal::{Coverage, DecodedRegion, Decoders},
 };
 
 mod backend;
@@ -24,7 +46,12 @@ mod imgui_utils;
 mod mass_effect_1;
 mod mass_effect_2;
 mod mass_effect_3;
+mod new_character;
 mod raw_ui;
+mod save_browser;
+mod summary;
+
+use self::{new_character::NewCharacterState, save_browser::SaveBrowserState};
 
 static NOTIFICATION_TIME: f64 = 1.5; // seconde
 
@@ -37,9 +64,86 @@ struct NotificationState {
 
 #[derive(Default)]
 pub struct KnownPlotsState {
-    me1: Option<Me1KnownPlot>,
-    me2: Option<Me2KnownPlot>,
-    me3: Option<Me3KnownPlot>,
+    me1: Option<Arc<Me1KnownPlot>>,
+    me2: Option<Arc<Me2KnownPlot>>,
+    me3: Option<Arc<Me3KnownPlot>>,
+}
+
+enum FilePopup {
+    Rename(ImString),
+    Duplicate {
+        next_free: bool,
+        slot: i32,
+    },
+    Delete,
+    ReplacePlotInts {
+        from: i32,
+        to: i32,
+        affected: Option<Vec<usize>>,
+    },
+    // `scroll` amène la ligne de `index` dans la liste, `editing` tient le groupe d'annulation
+    JumpToPlot {
+        boolean: bool,
+        index: i32,
+        query: ImString,
+        names: PlotNames,
+        scroll: bool,
+        editing: bool,
+    },
+    MaxCharacter,
+    Revive(&'static str),
+    SuicideMission,
+    ImportBuild(ImString),
+    SizeBreakdown(SizeBreakdown),
+    Coverage(Coverage, Vec<DecodedRegion>),
+    Changes(Vec<Change>),
+    Ranges(Vec<(String, String)>),
+    ReadOnly(String),
+    LargeSave {
+        path: String,
+        warning: String,
+    },
+    ConfirmSave {
+        path: String,
+        changes: Vec<Change>,
+    },
+    MergePlots {
+        other: Box<SaveGame>,
+        changes: Vec<Change>,
+        conflicts: Vec<String>,
+    },
+    ByteDiff {
+        member: String,
+        path: String,
+        changes: Vec<ByteChange>,
+    },
+}
+
+// Action mise en attente tant que les modifications ne sont ni enregistrées ni abandonnées
+enum PendingAction {
+    Open(SaveGame),
+    Close,
+}
+
+struct UnsavedGuard {
+    action: PendingAction,
+    // Enregistrement demandé, l'action attend `UiEvent::SavedSave`
+    saving: bool,
+    // Le disque diffère, l'enregistrement attend la confirmation de l'écrasement
+    confirming: bool,
+}
+
+impl UnsavedGuard {
+    fn new(action: PendingAction) -> Self {
+        UnsavedGuard { action, saving: false, confirming: false }
+    }
+
+    // L'enregistrement n'a pas eu lieu, l'action est annulée
+    fn abort(unsaved: &mut Option<Self>) {
+        if matches!(unsaved, Some(UnsavedGuard { saving: true, .. })) {
+            *unsaved = None;
+        }
+    }
 }
 
 #[derive(Default)]
@@ -48,6 +152,87 @@ struct State {
     error: Option<Error>,
     notification: Option<NotificationState>,
     known_plots: KnownPlotsState,
+    file_popup: Option<FilePopup>,
+    history: History<SaveGame>,
+    edit_log: EditLog,
+    editing: bool,
+    save_browser: Option<SaveBrowserState>,
+    new_character: Option<NewCharacterState>,
+    inventory_filter: ItemFilter,
+    busy: Option<&'static str>,
+    config: Config,
+    autosave: AutosaveTimer,
+    unsaved: Option<UnsavedGuard>,
+}
+
+impl State {
+    fn open(&mut self, save_game: SaveGame, event_addr: &Sender<MainEvent>) {
+        let _ = event_addr.send(MainEvent::LoadKnownPlots(
+            save_game.game_version(),
+            self.config.plot_locale.clone(),
+        ));
+        if let Err(err) = self.edit_log.start(&save_game) {
+            self.error = Some(err);
+        }
+        self.save_game = Some(save_game);
+        self.history = History::default();
+        self.autosave.cancel();
+        // Le filtre d'une autre sauvegarde masquerait une partie de l'inventaire
+        self.inventory_filter = ItemFilter::default();
+    }
+
+    // En cas d'erreur on préfère demander
+    fn is_dirty(&self) -> bool {
+        match &self.save_game {
+            Some(save_game) => self.edit_log.is_dirty(save_game).unwrap_or(true),
+            None => false,
+        }
+    }
+
+    fn proceed(&mut self, action: PendingAction, run: &mut bool, event_addr: &Sender<MainEvent>) {
+        match action {
+            PendingAction::Open(save_game) => self.open(save_game, event_addr),
+            PendingAction::Close => *run = false,
+        }
+    }
+
+    fn abort_saving(&mut self) {
+        UnsavedGuard::abort(&mut self.unsaved);
+    }
+
+    // L'action reste en attente jusqu'à la réponse de la popup
+    fn confirm_saving(&mut self) {
+        if let Some(unsaved) = self.unsaved.as_mut() {
+            if unsaved.saving {
+                unsaved.confirming = true;
+            }
+        }
+    }
+
+    fn saved(
+        &mut self, saved_save_game: &SaveGame, run: &mut bool, event_addr: &Sender<MainEvent>,
+    ) {
+        if let Err(err) = self.edit_log.mark_saved(saved_save_game) {
+            self.error = Some(err);
+        }
+        if matches!(self.unsaved, Some(UnsavedGuard { saving: true, .. })) {
+            if let Some(UnsavedGuard { action, .. }) = self.unsaved.take() {
+                self.proceed(action, run, event_addr);
+            }
+        }
+    }
+
+    fn undo(&mut self) {
+        if let Some(save_game) = &mut self.save_game {
+            self.history.undo(save_game);
+        }
+    }
+
+    fn redo(&mut self) {
+        if let Some(save_game) = &mut self.save_game {
+            self.history.redo(save_game);
+        }
+    }
 }
 
 // Events
@@ -55,17 +240,59 @@ pub enum UiEvent {
     Error(Error),
     Notification(&'static str),
     OpenedSave(SaveGame),
-    LoadedMe1KnownPlot(Me1KnownPlot),
-    LoadedMe2KnownPlot(Me2KnownPlot),
-    LoadedMe3KnownPlot(Me3KnownPlot),
+    // Contenu écrit sur le disque
+    SavedSave(SaveGame),
+    LoadedPlotDb(PlotDb),
     ImportedHeadMorph(HeadMorph),
+    MovedSave(String),
+    DeletedSave,
+    ImportedMe2Plot(Box<Me2PlotTable>),
+    ImportedZipMember(String, Vec<u8>),
+    LoadedConfig(Config),
+    ImportedPalette(String),
+    ImportedInventory(String),
+    ImportedPatch(SavePatch),
+    BrowsedSaves(String, Vec<SaveEntry>),
+    Busy(Option<&'static str>),
+    ReadOnlySave(String),
+    LargeSave(String, String),
+    ConfirmSave(String, Vec<Change>),
+    MergedPlots(Box<SaveGame>, Vec<Change>, Vec<String>),
+    ComparedZipMember(String, String, Vec<ByteChange>),
+}
+
+// Sans base de plots, les plots restent accessibles sans description
+fn jump_to_plot(save_game: &SaveGame) -> FilePopup {
+    let names = plot_db::for_game(save_game.game_version())
+        .map(|plot_db| plot_db.names())
+        .unwrap_or_default();
+    FilePopup::JumpToPlot {
+        boolean: true,
+        index: 0,
+        query: ImString::default(),
+        names,
+        scroll: false,
+        editing: false,
+    }
+}
+
+// Ids listés par le saut, tous les plots de la sauvegarde ou ceux dont le nom correspond
+fn jump_rows(save_game: &SaveGame, names: &PlotNames, boolean: bool, query: &str) -> Vec<usize> {
+    if !query.trim().is_empty() {
+        return names.search(boolean, query).into_iter().map(|(plot_id, _)| plot_id).collect();
+    }
+    if boolean {
+        save_game.iter_plot_bools(names).map(|(plot_id, ..)| plot_id).collect()
+    } else {
+        save_game.iter_plot_ints(names).map(|(plot_id, ..)| plot_id).collect()
+    }
 }
 
 // UI
-pub fn run(event_addr: Sender<MainEvent>, rx: Receiver<UiEvent>) {
+pub fn run(event_addr: Sender<MainEvent>, ui_addr: Sender<UiEvent>, rx: Receiver<UiEvent>) {
     let mut state = State::default();
 
-    let _ = event_addr.send(MainEvent::LoadKnownPlots);
+    let _ = event_addr.send(MainEvent::LoadConfig);
 
     // UI
     let system = backend::init(
@@ -73,9 +300,10 @@ pub fn run(event_addr: Sender<MainEvent>, rx: Receiver<UiEvent>) {
         1000.0,
         670.0,
     );
-    system.main_loop(move |run, ui| {
+    system.main_loop(move |run, settings, ui| {
         rx.try_iter().for_each(|ui_event| match ui_event {
             UiEvent::Error(err) => {
+                state.abort_saving();
                 state.error = Some(err);
             }
             UiEvent::Notification(string) => {
@@ -85,39 +313,144 @@ pub fn run(event_addr: Sender<MainEvent>, rx: Receiver<UiEvent>) {
                 })
             }
             UiEvent::OpenedSave(opened_save_game) => {
-                state.save_game = Some(opened_save_game);
+                if state.is_dirty() {
+                    let action = PendingAction::Open(opened_save_game);
+                    state.unsaved = Some(UnsavedGuard::new(action));
+                } else {
+                    state.open(opened_save_game, &event_addr);
+                }
             }
-            UiEvent::LoadedMe1KnownPlot(me1_known_plot) => {
-                state.known_plots.me1 = Some(me1_known_plot)
+            UiEvent::SavedSave(saved_save_game) => {
+                state.saved(&saved_save_game, &mut *run, &event_addr)
             }
-            UiEvent::LoadedMe2KnownPlot(me2_known_plot) => {
-                state.known_plots.me2 = Some(me2_known_plot)
+            UiEvent::LoadedConfig(config) => state.config = config,
+            UiEvent::Busy(busy) => state.busy = busy,
+            UiEvent::ReadOnlySave(path) => {
+                state.abort_saving();
+                state.file_popup = Some(FilePopup::ReadOnly(path))
             }
-            UiEvent::LoadedMe3KnownPlot(me3_known_plot) => {
-                state.known_plots.me3 = Some(me3_known_plot)
+            UiEvent::LargeSave(path, warning) => {
+                state.abort_saving();
+                state.file_popup = Some(FilePopup::LargeSave { path, warning })
             }
-            UiEvent::ImportedHeadMorph(head_morph) => {
-                let has_head_morph =
-                    HasHeadMorph { has_head_morph: true, head_morph: Some(head_morph) };
-                match state.save_game.as_mut() {
-                    Some(SaveGame::MassEffect1Leg { save_game, .. }) => {
-                        save_game.save_data.player.head_morph = has_head_morph
+            UiEvent::ConfirmSave(path, changes) => {
+                state.confirm_saving();
+                state.file_popup = Some(FilePopup::ConfirmSave { path, changes })
+            }
+            UiEvent::MergedPlots(other, changes, conflicts) => {
+                state.file_popup = Some(FilePopup::MergePlots { other, changes, conflicts })
+            }
+            UiEvent::ComparedZipMember(member, path, changes) => {
+                state.file_popup = Some(FilePopup::ByteDiff { member, path, changes })
+            }
+            UiEvent::BrowsedSaves(dir, entries) => {
+                let groups = group_saves(Path::new(&dir), entries);
+                state.save_browser = Some(SaveBrowserState { dir, groups })
+            }
+            UiEvent::ImportedPalette(palette) => {
+                if let Some(save_game) = state.save_game.as_mut() {
+                    match save_game.import_palette(&palette) {
+                        Ok(()) => {
+                            state.notification = Some(NotificationState {
+                                string: ImString::new("Palette applied"),
+                                close_time: ui.time() + NOTIFICATION_TIME,
+                            })
+                        }
+                        Err(err) => state.error = Some(err),
+                    }
+                }
+            }
+            UiEvent::ImportedInventory(inventory) => {
+                if let Some(save_game) = state.save_game.as_mut() {
+                    match save_game.import_inventory(&inventory) {
+                        Ok(()) => {
+                            state.notification = Some(NotificationState {
+                                string: ImString::new("Inventory imported"),
+                                close_time: ui.time() + NOTIFICATION_TIME,
+                            })
+                        }
+                        Err(err) => state.error = Some(err),
                     }
-                    Some(SaveGame::MassEffect2 { save_game, .. }) => {
-                        save_game.player.appearance.head_morph = has_head_morph
+                }
+            }
+            UiEvent::ImportedPatch(patch) => {
+                if let Some(save_game) = state.save_game.as_mut() {
+                    match state.history.transaction(save_game, |save_game| patch.apply(save_game)) {
+                        Ok(()) => {
+                            state.notification = Some(NotificationState {
+                                string: ImString::new(format!(
+                                    "Patch applied ({} fields)",
+                                    patch.fields.len()
+                                )),
+                                close_time: ui.time() + NOTIFICATION_TIME,
+                            })
+                        }
+                        Err(err) => state.error = Some(err),
                     }
-                    Some(SaveGame::MassEffect2Leg { save_game, .. }) => {
-                        save_game.player.appearance.head_morph = has_head_morph
+                }
+            }
+            UiEvent::LoadedPlotDb(plot_db) => match plot_db {
+                PlotDb::Me1(me1_known_plot) => state.known_plots.me1 = Some(me1_known_plot),
+                PlotDb::Me2(me2_known_plot) => state.known_plots.me2 = Some(me2_known_plot),
+                PlotDb::Me3(me3_known_plot) => state.known_plots.me3 = Some(me3_known_plot),
+            },
+            UiEvent::ImportedHeadMorph(head_morph) => {
+                if let Some(save_game) = state.save_game.as_mut() {
+                    match save_game.import_head_morph(head_morph) {
+                        Ok(warnings) if !warnings.is_empty() => {
+                            state.notification = Some(NotificationState {
+                                string: ImString::new(format!(
+                                    "Head morph imported from another head model : {}",
+                                    warnings.join(", ")
+                                )),
+                                close_time: ui.time() + NOTIFICATION_TIME,
+                            })
+                        }
+                        Ok(_) => (),
+                        Err(err) => state.error = Some(err),
                     }
-                    Some(SaveGame::MassEffect3 { save_game, .. }) => {
-                        save_game.player.appearance.head_morph = has_head_morph
+                }
+            }
+            UiEvent::MovedSave(path) => {
+                if let Some(save_game) = state.save_game.as_mut() {
+                    save_game.set_file_path(path);
+                }
+            }
+            UiEvent::DeletedSave => {
+                state.save_game = None;
+            }
+            UiEvent::ImportedMe2Plot(me2_plot) => {
+                if let Some(SaveGame::MassEffect3 { save_game, .. }) = state.save_game.as_mut() {
+                    carry_forward::me2_to_me3(&me2_plot, &mut save_game.plot);
+                    state.notification = Some(NotificationState {
+                        string: ImString::new("Imported ME2 decisions"),
+                        close_time: ui.time() + NOTIFICATION_TIME,
+                    });
+                }
+            }
+            UiEvent::ImportedZipMember(member, input) => {
+                if let Some(SaveGame::MassEffect1 { save_game, .. }) = state.save_game.as_mut() {
+                    let guard = if save_game.is_extra_member(&member) {
+                        safe_mode::check(state.config.safe_mode, Guarded::OpaqueMemberReplace)
+                    } else {
+                        Ok(())
+                    };
+                    match guard.and_then(|()| save_game.replace_member(&member, &input)) {
+                        Ok(()) => {
+                            state.notification = Some(NotificationState {
+                                string: ImString::new(format!("Replaced `{}`", member)),
+                                close_time: ui.time() + NOTIFICATION_TIME,
+                            })
+                        }
+                        Err(err) => state.error = Some(err),
                     }
-                    Some(SaveGame::MassEffect1 { .. }) | None => unreachable!(),
                 }
             }
         });
 
-        let ui = Gui::new(ui, &event_addr);
+        settings.font_scale = state.config.font_scale();
+        settings.keyboard_navigation = state.config.keyboard_navigation;
+        let ui = Gui::new(ui, &event_addr, &ui_addr, &state.config);
         ui.draw(run, &mut state);
     });
 }
@@ -125,16 +458,38 @@ pub fn run(event_addr: Sender<MainEvent>, rx: Receiver<UiEvent>) {
 pub struct Gui<'ui> {
     ui: &'ui Ui<'ui>,
     event_addr: Sender<MainEvent>,
+    ui_addr: Sender<UiEvent>,
+    number_base: NumberBase,
+    safe_mode: bool,
+    high_contrast: bool,
+    font_scale: f32,
 }
 
 impl<'ui> Gui<'ui> {
-    fn new(ui: &'ui Ui<'ui>, event_addr: &Sender<MainEvent>) -> Self {
-        Self { ui, event_addr: Sender::clone(event_addr) }
+    fn new(
+        ui: &'ui Ui<'ui>, event_addr: &Sender<MainEvent>, ui_addr: &Sender<UiEvent>,
+        config: &Config,
+    ) -> Self {
+        Self {
+            ui,
+            event_addr: Sender::clone(event_addr),
+            ui_addr: Sender::clone(ui_addr),
+            number_base: config.number_base,
+            safe_mode: config.safe_mode,
+            high_contrast: config.high_contrast,
+            font_scale: config.font_scale(),
+        }
     }
 
-    fn draw(&self, _: &mut bool, state: &mut State) {
+    fn draw(&self, run: &mut bool, state: &mut State) {
         let ui = self.ui;
 
+        // Fermeture demandée par la fenêtre
+        if !*run && (state.unsaved.is_some() || state.is_dirty()) {
+            *run = true;
+            state.unsaved = Some(UnsavedGuard::new(PendingAction::Close));
+        }
+
         // Main window
         let window = Window::new(im_str!("###main"))
             .size(ui.io().display_size, Condition::Always)
@@ -160,26 +515,124 @@ impl<'ui> Gui<'ui> {
 
         // Window
         if let Some(_t) = window.begin(ui) {
+            // Raccourcis
+            let mut action = self.pressed_action(&state.config.keybindings);
+
             // Main menu bar
             if let Some(_t) = ui.begin_menu_bar() {
                 if ui.button(im_str!("Open")) {
                     self.open_dialog();
                 }
+                if ui.button(im_str!("Browse")) {
+                    self.browse_dialog();
+                }
+                if ui.button(im_str!("New")) {
+                    state.new_character = Some(NewCharacterState::default());
+                }
                 if let Some(save_game) = &state.save_game {
                     if ui.button(im_str!("Save")) {
                         self.save_dialog(save_game);
                     }
+                    if let Some(_t) = ui.begin_menu(im_str!("File")) {
+                        if let Some(file_popup) = self.draw_file_menu(save_game) {
+                            state.file_popup = Some(file_popup);
+                        }
+                        ui.separator();
+                        let keybindings = &state.config.keybindings;
+                        if MenuItem::new(im_str!("Undo"))
+                            .shortcut(&ImString::new(keybindings.undo.to_string()))
+                            .enabled(state.history.can_undo())
+                            .build(ui)
+                        {
+                            action = Some(Action::Undo);
+                        }
+                        if MenuItem::new(im_str!("Redo"))
+                            .shortcut(&ImString::new(keybindings.redo.to_string()))
+                            .enabled(state.history.can_redo())
+                            .build(ui)
+                        {
+                            action = Some(Action::Redo);
+                        }
+                        ui.separator();
+                        if MenuItem::new(im_str!("Export edit log"))
+                            .enabled(!state.edit_log.entries().is_empty())
+                            .build(ui)
+                        {
+                            self.export_edit_log_dialog(&state.edit_log);
+                        }
+                        if MenuItem::new(im_str!("Export overview")).build(ui) {
+                            self.export_overview_dialog(save_game);
+                        }
+                        if MenuItem::new(im_str!("Export patch to another save")).build(ui) {
+                            self.export_patch_dialog(save_game);
+                        }
+                        if MenuItem::new(im_str!("Apply patch")).build(ui) {
+                            self.import_patch_dialog();
+                        }
+                        if MenuItem::new(im_str!("Merge plot progress from...")).build(ui) {
+                            self.merge_plots_dialog(save_game);
+                        }
+                        if MenuItem::new(im_str!("Changes since load")).build(ui) {
+                            match state.edit_log.changes_since_load(save_game) {
+                                Ok(changes) => state.file_popup = Some(FilePopup::Changes(changes)),
+                                Err(err) => state.error = Some(err),
+                            }
+                        }
+                        if MenuItem::new(im_str!("Check field ranges")).build(ui) {
+                            state.file_popup = Some(FilePopup::Ranges(save_game.range_report()));
+                        }
+                    }
+                }
+                if let Some(_t) = ui.begin_menu(im_str!("Settings")) {
+                    let game = state.save_game.as_ref().map(SaveGame::game_version);
+                    self.draw_settings(&mut state.config, game);
                 }
                 if let Some(_t) = ui.begin_menu(im_str!("About")) {
                     self.draw_about();
                 }
             }
 
+            match action {
+                Some(Action::Open) => self.open_dialog(),
+                Some(Action::Save) => {
+                    if let Some(save_game) = &state.save_game {
+                        self.save_dialog(save_game);
+                    }
+                }
+                Some(Action::Undo) => state.undo(),
+                Some(Action::Redo) => state.redo(),
+                Some(Action::Search) => {
+                    if let Some(save_game) = &state.save_game {
+                        state.file_popup = Some(jump_to_plot(save_game));
+                    }
+                }
+                None => (),
+            }
+
+            // File popup
+            if let Some(save_game) = &mut state.save_game {
+                self.draw_file_popup(
+                    &mut state.file_popup,
+                    save_game,
+                    &mut state.history,
+                    &state.edit_log,
+                    &mut state.unsaved,
+                );
+            }
+
+            // Modifications non enregistrées
+            self.draw_unsaved_guard(run, state);
+
+            // Save browser
+            self.draw_save_browser(&mut state.save_browser);
+            self.draw_new_character(&mut state.new_character);
+
             // Error popup
             self.draw_error(&mut state.error);
 
             // Notification
             self.draw_notification_overlay(&mut state.notification);
+            self.draw_busy_overlay(state.busy);
 
             // Game
             match &mut state.save_game {
@@ -187,9 +640,11 @@ impl<'ui> Gui<'ui> {
                 Some(SaveGame::MassEffect1 { save_game, .. }) => {
                     self.draw_mass_effect_1(save_game, &state.known_plots)
                 }
-                Some(SaveGame::MassEffect1Leg { save_game, .. }) => {
-                    self.draw_mass_effect_1_leg(&mut save_game.save_data, &state.known_plots)
-                }
+                Some(SaveGame::MassEffect1Leg { save_game, .. }) => self.draw_mass_effect_1_leg(
+                    &mut save_game.save_data,
+                    &state.known_plots,
+                    &mut state.inventory_filter,
+                ),
                 Some(SaveGame::MassEffect2 { save_game, .. }) => {
                     self.draw_mass_effect_2(save_game, &state.known_plots)
                 }
@@ -200,6 +655,30 @@ impl<'ui> Gui<'ui> {
                     self.draw_mass_effect_3(save_game, &state.known_plots)
                 }
             };
+
+            // Journal, une entrée à la fin de chaque édition
+            let editing = ui.is_any_item_active();
+            let undone = matches!(action, Some(Action::Undo) | Some(Action::Redo));
+            if (state.editing && !editing) || undone {
+                if let Some(save_game) = &state.save_game {
+                    match state.edit_log.record(save_game) {
+                        // Seule une vraie modification relance la sauvegarde auto
+                        Ok(count) if count > 0 => state.autosave.edited(ui.time()),
+                        Ok(_) => (),
+                        Err(err) => {
+                            let _ = self.ui_addr.send(UiEvent::Error(err));
+                        }
+                    }
+                }
+            }
+
+            // Autosave
+            if let Some(save_game) = &state.save_game {
+                if state.autosave.poll(ui.time(), state.config.autosave_interval) {
+                    let _ = self.event_addr.send(MainEvent::AutoSave(save_game.clone()));
+                }
+            }
+            state.editing = editing;
         }
     }
 
@@ -241,8 +720,8 @@ impl<'ui> Gui<'ui> {
             "",
             &dir.to_string_lossy(),
             Some((
-                &["*.pcsav", "*.MassEffectSave"],
-                "Mass Effect Trilogy Save (*.pcsav, *.MassEffectSave)",
+                &["*.pcsav", "*.MassEffectSave", "*.zip"],
+                "Mass Effect Trilogy Save (*.pcsav, *.MassEffectSave, *.zip)",
             )),
         );
 
@@ -251,6 +730,81 @@ impl<'ui> Gui<'ui> {
         }
     }
 
+    fn export_edit_log_dialog(&self, edit_log: &EditLog) {
+        let file = tinyfiledialogs::save_file_dialog_with_filter(
+            "",
+            "edit_log.txt",
+            &["*.txt"],
+            "Edit log (*.txt)",
+        );
+        if let Some(path) = file {
+            let _ = self.event_addr.send(MainEvent::ExportEditLog(path, edit_log.to_text()));
+        }
+    }
+
+    // Le format suit l'extension choisie
+    fn export_overview_dialog(&self, save_game: &SaveGame) {
+        let file = tinyfiledialogs::save_file_dialog_with_filter(
+            "",
+            "overview.md",
+            &["*.md", "*.txt"],
+            "Overview (*.md, *.txt)",
+        );
+        if let Some(path) = file {
+            let _ = self.event_addr.send(MainEvent::ExportOverview(path, save_game.clone()));
+        }
+    }
+
+    // Patch de la sauvegarde ouverte vers celle choisie
+    fn export_patch_dialog(&self, save_game: &SaveGame) {
+        let to_path = tinyfiledialogs::open_file_dialog(
+            "Save to compare with",
+            &Self::get_document_dir().to_string_lossy(),
+            Some((&["*.pcsav", "*.MassEffectSave"], "Mass Effect Trilogy Save")),
+        );
+        let to_path = match to_path {
+            Some(to_path) => to_path,
+            None => return,
+        };
+        let file = tinyfiledialogs::save_file_dialog_with_filter(
+            "",
+            "patch.json",
+            &["*.json"],
+            "Save patch (*.json)",
+        );
+        if let Some(path) = file {
+            let _ = self.event_addr.send(MainEvent::ExportPatch(path, to_path, save_game.clone()));
+        }
+    }
+
+    fn import_patch_dialog(&self) {
+        let file =
+            tinyfiledialogs::open_file_dialog("", "", Some((&["*.json"], "Save patch (*.json)")));
+        if let Some(path) = file {
+            let _ = self.event_addr.send(MainEvent::ImportPatch(path));
+        }
+    }
+
+    fn merge_plots_dialog(&self, save_game: &SaveGame) {
+        let file = tinyfiledialogs::open_file_dialog(
+            "Save to merge",
+            &Self::get_document_dir().to_string_lossy(),
+            Some((&["*.pcsav", "*.MassEffectSave"], "Mass Effect Trilogy Save")),
+        );
+        if let Some(path) = file {
+            let _ = self.event_addr.send(MainEvent::MergePlots(path, save_game.clone()));
+        }
+    }
+
+    fn browse_dialog(&self) {
+        let dir = Self::get_document_dir();
+
+        let dir = tinyfiledialogs::select_folder_dialog("", &dir.to_string_lossy());
+        if let Some(dir) = dir {
+            let _ = self.event_addr.send(MainEvent::BrowseSaves(dir));
+        }
+    }
+
     fn save_dialog(&self, save_game: &SaveGame) {
         let (file_path, description, extension) = match save_game {
             SaveGame::MassEffect1 { file_path, .. } => {
@@ -276,6 +830,944 @@ impl<'ui> Gui<'ui> {
         }
     }
 
+    fn draw_file_menu(&self, save_game: &SaveGame) -> Option<FilePopup> {
+        let ui = self.ui;
+        let file_path = Path::new(save_game.file_path());
+
+        match save_slot::slot_label(file_path) {
+            Some(slot) => ui.text_disabled(format!("Slot : {}", slot)),
+            None => ui.text_disabled("Slot : not listed by the game"),
+        }
+        ui.text_disabled(format!("Version : {}", save_game.format_version()));
+        ui.separator();
+        if MenuItem::new(im_str!("Rename")).build(ui) {
+            let stem = PathBuf::from(save_game.file_path())
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            return Some(FilePopup::Rename(ImString::new(stem)));
+        }
+        if MenuItem::new(im_str!("Duplicate")).build(ui) {
+            let slot = save_slot::slot_number(file_path).map_or(1, |number| number + 1);
+            return Some(FilePopup::Duplicate { next_free: true, slot: slot as i32 });
+        }
+        let convert = match save_slot::slot_kind(file_path) {
+            Some(SlotKind::Manual) => Some(im_str!("Copy as autosave")),
+            Some(_) => Some(im_str!("Copy as manual save")),
+            None => None,
+        };
+        if let Some(label) = convert {
+            if MenuItem::new(label).build(ui) {
+                let _ =
+                    self.event_addr.send(MainEvent::ConvertSave(save_game.file_path().to_owned()));
+            }
+        }
+        if MenuItem::new(im_str!("Delete")).build(ui) {
+            return Some(FilePopup::Delete);
+        }
+        ui.separator();
+        if MenuItem::new(im_str!("Replace plot integers")).build(ui) {
+            return Some(FilePopup::ReplacePlotInts { from: 0, to: 0, affected: None });
+        }
+        if MenuItem::new(im_str!("Jump to plot")).build(ui) {
+            return Some(jump_to_plot(save_game));
+        }
+        if MenuItem::new(im_str!("Max character")).build(ui) {
+            return Some(FilePopup::MaxCharacter);
+        }
+        if let Some(build) = save_game.build() {
+            if MenuItem::new(im_str!("Copy build code")).build(ui) {
+                ui.set_clipboard_text(&ImString::new(build.to_string()));
+                let _ = self.ui_addr.send(UiEvent::Notification("Copied to clipboard"));
+            }
+            if MenuItem::new(im_str!("Import build code")).build(ui) {
+                return Some(FilePopup::ImportBuild(ImString::default()));
+            }
+        }
+        let companions = revive::companions(save_game.game_version());
+        if !companions.is_empty() {
+            if let Some(_t) = ui.begin_menu(im_str!("Revive companion")) {
+                for companion in companions {
+                    if MenuItem::new(&ImString::new(*companion)).build(ui) {
+                        return Some(FilePopup::Revive(*companion));
+                    }
+                }
+            }
+        }
+        let is_me2 = plot_db::dependencies(save_game.game_version())[0] == GameVersion::MassEffect2;
+        if is_me2 && MenuItem::new(im_str!("Suicide Mission outcome")).build(ui) {
+            return Some(FilePopup::SuicideMission);
+        }
+        if MenuItem::new(im_str!("Parse coverage")).build(ui) {
+            let coverage = save_game.coverage().and_then(|coverage| {
+                let regions = save_game.opaque_regions()?;
+                Ok((coverage, Decoders::default().decode(&regions)))
+            });
+            match coverage {
+                Ok((coverage, decoded)) => return Some(FilePopup::Coverage(coverage, decoded)),
+                Err(err) => {
+                    let _ = self.ui_addr.send(UiEvent::Error(err));
+                }
+            }
+        }
+        if let SaveGame::MassEffect1 { save_game, .. } = save_game {
+            if MenuItem::new(im_str!("Size breakdown")).build(ui) {
+                match save_game.size_breakdown() {
+                    Ok(breakdown) => return Some(FilePopup::SizeBreakdown(breakdown)),
+                    Err(err) => {
+                        let _ = self.ui_addr.send(UiEvent::Error(err));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn draw_file_popup(
+        &self, file_popup: &mut Option<FilePopup>, save_game: &mut SaveGame,
+        history: &mut History<SaveGame>, edit_log: &EditLog, unsaved: &mut Option<UnsavedGuard>,
+    ) {
+        let ui = self.ui;
+        let file_path = save_game.file_path().to_owned();
+
+        match file_popup {
+            Some(FilePopup::Rename(new_name)) => {
+                ui.open_popup(im_str!("Rename###rename"));
+
+                if let Some(_t) = PopupModal::new(im_str!("Rename###rename"))
+                    .always_auto_resize(true)
+                    .begin_popup(ui)
+                {
+                    ui.input_text(im_str!("New name"), new_name).resize_buffer(true).build();
+
+                    let valid = save_slot::validate_name(new_name.to_str());
+                    if let Err(err) = &valid {
+                        ui.text(err.to_string());
+                    }
+                    ui.separator();
+
+                    if ui.button_with_size(im_str!("OK"), [self.scaled(70.0), 0.0]) && valid.is_ok()
+                    {
+                        let _ = self
+                            .event_addr
+                            .send(MainEvent::RenameSave(file_path, new_name.to_string()));
+                        *file_popup = None;
+                        ui.close_current_popup();
+                    } else {
+                        ui.same_line();
+                        if ui.button_with_size(im_str!("Cancel"), [self.scaled(70.0), 0.0]) {
+                            *file_popup = None;
+                            ui.close_current_popup();
+                        }
+                    }
+                }
+            }
+            Some(FilePopup::Duplicate { next_free, slot }) => {
+                ui.open_popup(im_str!("Duplicate###duplicate"));
+
+                if let Some(_t) = PopupModal::new(im_str!("Duplicate###duplicate"))
+                    .always_auto_resize(true)
+                    .begin_popup(ui)
+                {
+                    ui.checkbox(im_str!("Next free slot"), next_free);
+                    let target = if *next_free {
+                        None
+                    } else {
+                        ui.input_int(im_str!("Slot"), slot).build();
+                        let target =
+                            save_slot::slot_target(Path::new(&file_path), (*slot).max(0) as u32);
+                        match &target {
+                            Ok(target) => ui.text(format!("Copy to `{}`", target.display())),
+                            Err(err) => ui.text(err.to_string()),
+                        }
+                        Some(target)
+                    };
+                    ui.separator();
+
+                    let valid = !matches!(target, Some(Err(_)));
+                    if ui.button_with_size(im_str!("OK"), [self.scaled(70.0), 0.0]) && valid {
+                        let slot = if *next_free { None } else { Some((*slot).max(0) as u32) };
+                        let _ = self.event_addr.send(MainEvent::DuplicateSave(file_path, slot));
+                        *file_popup = None;
+                        ui.close_current_popup();
+                    } else {
+                        ui.same_line();
+                        if ui.button_with_size(im_str!("Cancel"), [self.scaled(70.0), 0.0]) {
+                            *file_popup = None;
+                            ui.close_current_popup();
+                        }
+                    }
+                }
+            }
+            Some(FilePopup::ReadOnly(path)) => {
+                ui.open_popup(im_str!("Read-only###read-only"));
+
+                if let Some(_t) = PopupModal::new(im_str!("Read-only###read-only"))
+                    .always_auto_resize(true)
+                    .begin_popup(ui)
+                {
+                    ui.text(format!("`{}` is read-only, save a copy ?", path));
+                    ui.separator();
+
+                    if ui.button_with_size(im_str!("Save as..."), [self.scaled(90.0), 0.0]) {
+                        *file_popup = None;
+                        ui.close_current_popup();
+                        self.save_dialog(save_game);
+                    } else {
+                        ui.same_line();
+                        if ui.button_with_size(im_str!("Cancel"), [self.scaled(90.0), 0.0]) {
+                            *file_popup = None;
+                            ui.close_current_popup();
+                        }
+                    }
+                }
+            }
+            Some(FilePopup::LargeSave { path, warning }) => {
+                ui.open_popup(im_str!("Large save###large-save"));
+
+                if let Some(_t) = PopupModal::new(im_str!("Large save###large-save"))
+                    .always_auto_resize(true)
+                    .begin_popup(ui)
+                {
+                    ui.text(warning.as_str());
+                    ui.separator();
+
+                    if ui.button_with_size(im_str!("Save anyway"), [self.scaled(90.0), 0.0]) {
+                        let _ = self
+                            .event_addr
+                            .send(MainEvent::SaveLargeSave(path.clone(), save_game.clone()));
+                        *file_popup = None;
+                        ui.close_current_popup();
+                    } else {
+                        ui.same_line();
+                        if ui.button_with_size(im_str!("Cancel"), [self.scaled(90.0), 0.0]) {
+                            *file_popup = None;
+                            ui.close_current_popup();
+                        }
+                    }
+                }
+            }
+            Some(FilePopup::ConfirmSave { path, changes }) => {
+                ui.open_popup(im_str!("Overwrite save###confirm-save"));
+
+                if let Some(_t) = PopupModal::new(im_str!("Overwrite save###confirm-save"))
+                    .always_auto_resize(true)
+                    .begin_popup(ui)
+                {
+                    ui.text(format!("`{}` differs in {} field(s) :", path, changes.len()));
+                    if let Some(_t) = ChildWindow::new("pending").size([600.0, 300.0]).begin(ui) {
+                        for change in changes.iter() {
+                            ui.text(change.to_string());
+                        }
+                    }
+                    ui.separator();
+
+                    if ui.button_with_size(im_str!("Save"), [self.scaled(90.0), 0.0]) {
+                        let _ = self
+                            .event_addr
+                            .send(MainEvent::SaveConfirmedSave(path.clone(), save_game.clone()));
+                        if let Some(unsaved) = unsaved.as_mut() {
+                            unsaved.confirming = false;
+                        }
+                        *file_popup = None;
+                        ui.close_current_popup();
+                    } else {
+                        ui.same_line();
+                        if ui.button_with_size(im_str!("Cancel"), [self.scaled(90.0), 0.0]) {
+                            UnsavedGuard::abort(unsaved);
+                            *file_popup = None;
+                            ui.close_current_popup();
+                        }
+                    }
+                }
+            }
+            Some(FilePopup::MergePlots { other, changes, conflicts }) => {
+                ui.open_popup(im_str!("Merge plot progress###merge-plots"));
+
+                if let Some(_t) = PopupModal::new(im_str!("Merge plot progress###merge-plots"))
+                    .always_auto_resize(true)
+                    .begin_popup(ui)
+                {
+                    if changes.is_empty() {
+                        ui.text("This save already has all the progress of the other one");
+                    } else {
+                        ui.text(format!("{} plot(s) will change :", changes.len()));
+                        if let Some(_t) = ChildWindow::new("merged").size([600.0, 300.0]).begin(ui)
+                        {
+                            for change in changes.iter() {
+                                ui.text(change.to_string());
+                            }
+                        }
+                        ui.text("Use File > Undo to revert.");
+                    }
+                    if !conflicts.is_empty() {
+                        ui.separator();
+                        ui.text_colored(
+                            [1.0, 0.6, 0.2, 1.0],
+                            "Both saves made a different choice, the merge keeps both :",
+                        );
+                        for conflict in conflicts.iter() {
+                            ui.text(conflict);
+                        }
+                    }
+                    ui.separator();
+
+                    if !changes.is_empty()
+                        && ui.button_with_size(im_str!("Merge"), [self.scaled(90.0), 0.0])
+                    {
+                        // Fusionnée à la confirmation, la sauvegarde a pu changer depuis l'aperçu
+                        if let Err(err) =
+                            history.transaction(save_game, |save_game| save_game.merge_plots(other))
+                        {
+                            let _ = self.ui_addr.send(UiEvent::Error(err));
+                        }
+                        *file_popup = None;
+                        ui.close_current_popup();
+                    } else {
+                        if !changes.is_empty() {
+                            ui.same_line();
+                        }
+                        if ui.button_with_size(im_str!("Cancel"), [self.scaled(90.0), 0.0]) {
+                            *file_popup = None;
+                            ui.close_current_popup();
+                        }
+                    }
+                }
+            }
+            Some(FilePopup::Delete) => {
+                ui.open_popup(im_str!("Delete###delete"));
+
+                if let Some(_t) = PopupModal::new(im_str!("Delete###delete"))
+                    .always_auto_resize(true)
+                    .begin_popup(ui)
+                {
+                    ui.text(format!("Delete `{}` ?", file_path));
+                    ui.separator();
+
+                    if ui.button_with_size(im_str!("Delete"), [self.scaled(70.0), 0.0]) {
+                        let _ = self.event_addr.send(MainEvent::DeleteSave(file_path));
+                        *file_popup = None;
+                        ui.close_current_popup();
+                    } else {
+                        ui.same_line();
+                        if ui.button_with_size(im_str!("Cancel"), [self.scaled(70.0), 0.0]) {
+                            *file_popup = None;
+                            ui.close_current_popup();
+                        }
+                    }
+                }
+            }
+            Some(FilePopup::ReplacePlotInts { from, to, affected }) => {
+                ui.open_popup(im_str!("Replace plot integers###replace-ints"));
+
+                if let Some(_t) = PopupModal::new(im_str!("Replace plot integers###replace-ints"))
+                    .always_auto_resize(true)
+                    .begin_popup(ui)
+                {
+                    ui.input_int(im_str!("Value"), from).build();
+                    ui.input_int(im_str!("Replace with"), to).build();
+
+                    if let Some(affected) = affected {
+                        ui.separator();
+                        ui.text(format!("{} plot(s)", affected.len()));
+                        if !affected.is_empty() {
+                            if let Some(_t) =
+                                ChildWindow::new("affected").size([400.0, 100.0]).begin(ui)
+                            {
+                                let affected: Vec<_> =
+                                    affected.iter().map(ToString::to_string).collect();
+                                ui.text_wrapped(&ImString::new(affected.join(", ")));
+                            }
+                        }
+                    }
+                    ui.separator();
+
+                    if ui.button_with_size(im_str!("Find"), [self.scaled(70.0), 0.0]) {
+                        *affected = Some(save_game.replace_plot_ints(*from, None));
+                    }
+                    ui.same_line();
+                    if ui.button_with_size(im_str!("Replace"), [self.scaled(70.0), 0.0]) {
+                        *affected = Some(save_game.replace_plot_ints(*from, Some(*to)));
+                    }
+                    ui.same_line();
+                    if ui.button_with_size(im_str!("Close"), [self.scaled(70.0), 0.0]) {
+                        *file_popup = None;
+                        ui.close_current_popup();
+                    }
+                }
+            }
+            Some(FilePopup::JumpToPlot { boolean, index, query, names, scroll, editing }) => {
+                ui.open_popup(im_str!("Jump to plot###jump-to-plot"));
+
+                if let Some(_t) = PopupModal::new(im_str!("Jump to plot###jump-to-plot"))
+                    .always_auto_resize(true)
+                    .begin_popup(ui)
+                {
+                    *scroll |= ui.radio_button(im_str!("Boolean"), boolean, true);
+                    ui.same_line();
+                    *scroll |= ui.radio_button(im_str!("Integer"), boolean, false);
+                    *scroll |= ui.input_int(im_str!("Index"), index).build();
+                    *scroll |= ui.input_text(im_str!("Search"), query).resize_buffer(true).build();
+
+                    let rows = jump_rows(save_game, names, *boolean, query.to_str());
+                    let selected = *index as usize;
+                    if let Some(_t) = ChildWindow::new("plots")
+                        .size([self.scaled(500.0), self.scaled(200.0)])
+                        .begin(ui)
+                    {
+                        // Le clipper ne dessine que les lignes visibles, on y amène d'abord la ligne
+                        let target = rows.iter().position(|plot_id| *plot_id == selected);
+                        match target {
+                            Some(row) if *scroll => {
+                                ui.set_scroll_y(row as f32 * ui.text_line_height_with_spacing())
+                            }
+                            Some(_) => (),
+                            None => *scroll = false,
+                        }
+
+                        let plot_names = if *boolean { &names.booleans } else { &names.ints };
+                        let mut clipper = ListClipper::new(rows.len() as i32).begin(ui);
+                        while clipper.step() {
+                            for row in clipper.display_start()..clipper.display_end() {
+                                let plot_id = rows[row as usize];
+                                let desc = plot_names.get(&plot_id).map_or("", String::as_str);
+                                let label = ImString::new(format!("{} : {}", plot_id, desc));
+                                if Selectable::new(&label).selected(plot_id == selected).build(ui) {
+                                    *index = plot_id as i32;
+                                }
+                                if *scroll && target == Some(row as usize) {
+                                    ui.set_scroll_here_y_with_ratio(0.5);
+                                    *scroll = false;
+                                }
+                            }
+                        }
+                    }
+                    ui.separator();
+
+                    let id = *index as usize;
+                    let edited = match (*index >= 0, *boolean) {
+                        (false, _) => None,
+                        (true, true) => save_game.plot_bool_at(id, names).map(|(name, value)| {
+                            ui.text(name.unwrap_or("Unknown plot"));
+                            let mut value = value;
+                            ui.checkbox(im_str!("Value"), &mut value)
+                                .then(|| (PlotId::Boolean(id), value.to_string()))
+                        }),
+                        (true, false) => save_game.plot_int_at(id, names).map(|(name, value)| {
+                            ui.text(name.unwrap_or("Unknown plot"));
+                            let mut value = value;
+                            ui.input_int(im_str!("Value"), &mut value)
+                                .build()
+                                .then(|| (PlotId::Int(id), value.to_string()))
+                        }),
+                    };
+                    match edited {
+                        Some(Some((plot_id, value))) => {
+                            // Une seule étape d'annulation par édition, pas une par caractère
+                            if !*editing {
+                                history.begin(save_game);
+                                *editing = true;
+                            }
+                            if let Err(err) = save_game.set_plot(plot_id, &value) {
+                                let _ = self.ui_addr.send(UiEvent::Error(err));
+                            }
+                        }
+                        Some(None) => (),
+                        None => ui.text("Not in this save"),
+                    }
+                    if *editing && !ui.is_item_active() {
+                        history.commit();
+                        *editing = false;
+                    }
+                    ui.separator();
+
+                    if ui.button_with_size(im_str!("Close"), [self.scaled(70.0), 0.0]) {
+                        if *editing {
+                            history.commit();
+                        }
+                        *file_popup = None;
+                        ui.close_current_popup();
+                    }
+                }
+            }
+            Some(FilePopup::MaxCharacter) => {
+                ui.open_popup(im_str!("Max character###max-character"));
+
+                if let Some(_t) = PopupModal::new(im_str!("Max character###max-character"))
+                    .always_auto_resize(true)
+                    .begin_popup(ui)
+                {
+                    ui.text("Max level, credits and consumables ?");
+                    ui.text(
+                        "Levels gained are converted to talent points, use File > Undo to revert.",
+                    );
+                    ui.separator();
+
+                    if ui.button_with_size(im_str!("OK"), [self.scaled(70.0), 0.0]) {
+                        if let Err(err) = history.transaction(save_game, SaveGame::max_character) {
+                            let _ = self.ui_addr.send(UiEvent::Error(err));
+                        }
+                        *file_popup = None;
+                        ui.close_current_popup();
+                    } else {
+                        ui.same_line();
+                        if ui.button_with_size(im_str!("Cancel"), [self.scaled(70.0), 0.0]) {
+                            *file_popup = None;
+                            ui.close_current_popup();
+                        }
+                    }
+                }
+            }
+            Some(FilePopup::ImportBuild(code)) => {
+                ui.open_popup(im_str!("Import build code###import-build"));
+
+                if let Some(_t) = PopupModal::new(im_str!("Import build code###import-build"))
+                    .always_auto_resize(true)
+                    .begin_popup(ui)
+                {
+                    ui.input_text(im_str!("Code"), code).resize_buffer(true).build();
+
+                    // Classe, niveau et pouvoirs sont remplacés, le reste du personnage est conservé
+                    let build = code.to_str().parse::<Build>();
+                    match &build {
+                        Ok(build) => ui.text(format!(
+                            "Level {} {} with {} powers",
+                            build.level,
+                            build.class_name,
+                            build.powers.len()
+                        )),
+                        Err(err) if !code.to_str().is_empty() => ui.text(err.to_string()),
+                        Err(_) => (),
+                    }
+                    ui.separator();
+
+                    if ui.button_with_size(im_str!("OK"), [self.scaled(70.0), 0.0]) {
+                        if let Ok(build) = &build {
+                            let guard = safe_mode::check_build(self.safe_mode, save_game, build);
+                            let applied = guard.and_then(|()| {
+                                history.transaction(save_game, |save_game| {
+                                    save_game.apply_build(build)
+                                })
+                            });
+                            if let Err(err) = applied {
+                                let _ = self.ui_addr.send(UiEvent::Error(err));
+                            }
+                            *file_popup = None;
+                            ui.close_current_popup();
+                        }
+                    } else {
+                        ui.same_line();
+                        if ui.button_with_size(im_str!("Cancel"), [self.scaled(70.0), 0.0]) {
+                            *file_popup = None;
+                            ui.close_current_popup();
+                        }
+                    }
+                }
+            }
+            Some(FilePopup::Revive(companion)) => {
+                ui.open_popup(im_str!("Revive companion###revive"));
+
+                if let Some(_t) = PopupModal::new(im_str!("Revive companion###revive"))
+                    .always_auto_resize(true)
+                    .begin_popup(ui)
+                {
+                    ui.text(format!("Revive {} ?", companion));
+                    if let Ok(revival) = revive::revival(save_game.game_version(), companion) {
+                        for (name, value) in &revival.plots {
+                            ui.text_disabled(format!("{} = {}", name, value));
+                        }
+                    }
+                    ui.separator();
+
+                    if ui.button_with_size(im_str!("OK"), [self.scaled(70.0), 0.0]) {
+                        let revived = history.transaction(save_game, |save_game| {
+                            revive::revive(save_game, companion)
+                        });
+                        if let Err(err) = revived {
+                            let _ = self.ui_addr.send(UiEvent::Error(err));
+                        }
+                        *file_popup = None;
+                        ui.close_current_popup();
+                    } else {
+                        ui.same_line();
+                        if ui.button_with_size(im_str!("Cancel"), [self.scaled(70.0), 0.0]) {
+                            *file_popup = None;
+                            ui.close_current_popup();
+                        }
+                    }
+                }
+            }
+            Some(FilePopup::SuicideMission) => {
+                ui.open_popup(im_str!("Suicide Mission outcome###suicide-mission"));
+
+                if let Some(_t) =
+                    PopupModal::new(im_str!("Suicide Mission outcome###suicide-mission"))
+                        .always_auto_resize(true)
+                        .begin_popup(ui)
+                {
+                    ui.text("Survivors carried into Mass Effect 3");
+                    ui.separator();
+                    match revive::survivors(save_game) {
+                        Ok(survivors) => {
+                            for (name, mut alive) in survivors {
+                                if ui.checkbox(&ImString::new(name), &mut alive) {
+                                    let edited = history.transaction(save_game, |save_game| {
+                                        revive::set_survival(save_game, name, alive)
+                                    });
+                                    if let Err(err) = edited {
+                                        let _ = self.ui_addr.send(UiEvent::Error(err));
+                                    }
+                                }
+                            }
+                        }
+                        Err(err) => ui.text_colored([1.0, 0.3, 0.3, 1.0], format!("{:#}", err)),
+                    }
+                    ui.separator();
+
+                    if ui.button_with_size(im_str!("Close"), [self.scaled(70.0), 0.0]) {
+                        *file_popup = None;
+                        ui.close_current_popup();
+                    }
+                }
+            }
+            Some(FilePopup::SizeBreakdown(breakdown)) => {
+                ui.open_popup(im_str!("Size breakdown###size-breakdown"));
+
+                if let Some(_t) = PopupModal::new(im_str!("Size breakdown###size-breakdown"))
+                    .always_auto_resize(true)
+                    .begin_popup(ui)
+                {
+                    let SizeBreakdown { header, no_mans_land, members, zip_overhead } = breakdown;
+                    ui.text(format!("Header : {} bytes", header));
+                    ui.text(format!("No man's land : {} bytes", no_mans_land));
+                    for MemberSize { name, size, compressed_size } in members.iter() {
+                        ui.text(format!(
+                            "{} : {} bytes ({} uncompressed)",
+                            name, compressed_size, size
+                        ));
+                    }
+                    ui.text(format!("Zip overhead : {} bytes", zip_overhead));
+                    ui.separator();
+                    ui.text(format!("Total : {} bytes", breakdown.total()));
+                    ui.separator();
+
+                    if ui.button_with_size(im_str!("Close"), [self.scaled(70.0), 0.0]) {
+                        *file_popup = None;
+                        ui.close_current_popup();
+                    }
+                }
+            }
+            Some(FilePopup::Coverage(coverage, decoded)) => {
+                ui.open_popup(im_str!("Parse coverage###coverage"));
+
+                if let Some(_t) = PopupModal::new(im_str!("Parse coverage###coverage"))
+                    .always_auto_resize(true)
+                    .begin_popup(ui)
+                {
+                    let ratio = coverage.known_ratio();
+                    ProgressBar::new(ratio)
+                        .overlay_text(&im_str!("{:.1}% mapped", ratio * 100.0))
+                        .size([300.0, 0.0])
+                        .build(ui);
+                    ui.text(format!("Named fields : {} bytes", coverage.known));
+                    ui.text(format!("Opaque : {} bytes", coverage.opaque));
+                    ui.text_disabled("Opaque bytes are kept as they are but can't be edited.");
+
+                    if !decoded.is_empty() {
+                        ui.separator();
+                        self.draw_decoded_regions(decoded);
+                    }
+                    ui.separator();
+
+                    if ui.button_with_size(im_str!("Close"), [self.scaled(70.0), 0.0]) {
+                        *file_popup = None;
+                        ui.close_current_popup();
+                    }
+                }
+            }
+            Some(FilePopup::Changes(changes)) => {
+                ui.open_popup(im_str!("Changes since load###changes"));
+
+                if let Some(_t) = PopupModal::new(im_str!("Changes since load###changes"))
+                    .always_auto_resize(true)
+                    .begin_popup(ui)
+                {
+                    let mut revert = None;
+                    if let Some(_t) = ChildWindow::new("changes").size([600.0, 300.0]).begin(ui) {
+                        if changes.is_empty() {
+                            ui.text("Nothing changed since the save was loaded");
+                        }
+                        for (i, change) in changes.iter().enumerate() {
+                            if ui.small_button(&im_str!("Revert##revert-{}", i)) {
+                                revert = Some(change.path().to_owned());
+                            }
+                            ui.same_line();
+                            ui.text(change.to_string());
+                        }
+                    }
+                    ui.text_disabled(
+                        "Reverting a field doesn't touch the others, it can be undone.",
+                    );
+                    ui.separator();
+
+                    if let Some(path) = revert {
+                        let reverted = history
+                            .transaction(save_game, |save_game| edit_log.revert(save_game, &path))
+                            .and_then(|()| edit_log.changes_since_load(save_game));
+                        match reverted {
+                            Ok(remaining) => *changes = remaining,
+                            Err(err) => {
+                                let _ = self.ui_addr.send(UiEvent::Error(err));
+                            }
+                        }
+                    }
+
+                    if ui.button_with_size(im_str!("Close"), [self.scaled(70.0), 0.0]) {
+                        *file_popup = None;
+                        ui.close_current_popup();
+                    }
+                }
+            }
+            Some(FilePopup::Ranges(report)) => {
+                ui.open_popup(im_str!("Field ranges###ranges"));
+
+                if let Some(_t) = PopupModal::new(im_str!("Field ranges###ranges"))
+                    .always_auto_resize(true)
+                    .begin_popup(ui)
+                {
+                    if let Some(_t) = ChildWindow::new("ranges").size([600.0, 300.0]).begin(ui) {
+                        if report.is_empty() {
+                            ui.text("Every field is within the game's limits");
+                        }
+                        for (path, error) in report.iter() {
+                            ui.text_colored([1.0, 0.3, 0.3, 1.0], path);
+                            ui.same_line();
+                            ui.text(error);
+                        }
+                    }
+                    ui.separator();
+
+                    if ui.button_with_size(im_str!("Close"), [self.scaled(70.0), 0.0]) {
+                        *file_popup = None;
+                        ui.close_current_popup();
+                    }
+                }
+            }
+            Some(FilePopup::ByteDiff { member, path, changes }) => {
+                ui.open_popup(im_str!("Byte diff###byte-diff"));
+
+                if let Some(_t) = PopupModal::new(im_str!("Byte diff###byte-diff"))
+                    .always_auto_resize(true)
+                    .begin_popup(ui)
+                {
+                    ui.text(format!("`{}` compared to `{}`", member, path));
+                    ui.text(format!("{} changed range(s)", changes.len()));
+                    ui.separator();
+
+                    if !changes.is_empty() {
+                        if let Some(_t) = ChildWindow::new(im_str!("byte-diff-scroll"))
+                            .size([600.0, 300.0])
+                            .begin(ui)
+                        {
+                            let mut clipper = ListClipper::new(changes.len() as i32).begin(ui);
+                            while clipper.step() {
+                                for i in clipper.display_start()..clipper.display_end() {
+                                    self.draw_byte_change(&changes[i as usize]);
+                                }
+                            }
+                        }
+                        ui.separator();
+                    }
+
+                    if ui.button_with_size(im_str!("Close"), [self.scaled(70.0), 0.0]) {
+                        *file_popup = None;
+                        ui.close_current_popup();
+                    }
+                }
+            }
+            None => (),
+        }
+    }
+
+    // Les longues plages ne montrent que leur début
+    fn draw_byte_change(&self, change: &ByteChange) {
+        const MAX_BYTES: usize = 16;
+        let ui = self.ui;
+
+        let preview = |bytes: &[u8]| {
+            if bytes.len() > MAX_BYTES {
+                format!("{} ... ({} bytes)", byte_diff::hex(&bytes[..MAX_BYTES]), bytes.len())
+            } else {
+                byte_diff::hex(bytes)
+            }
+        };
+        ui.text(format!("0x{:08X}", change.offset));
+        ui.same_line();
+        ui.text_disabled(preview(&change.before));
+        ui.same_line();
+        ui.text("->");
+        ui.same_line();
+        ui.text_colored([1.0, 0.6, 0.2, 1.0], preview(&change.after));
+    }
+
+    // Index des touches dans `keys_down`, le backend winit utilise `VirtualKeyCode`
+    fn key_index(key: char) -> Option<u32> {
+        match key.to_ascii_uppercase() {
+            key @ 'A'..='Z' => Some(10 + key as u32 - 'A' as u32),
+            '0' => Some(9),
+            key @ '1'..='9' => Some(key as u32 - '1' as u32),
+            _ => None,
+        }
+    }
+
+    fn pressed_action(&self, keybindings: &KeyBindings) -> Option<Action> {
+        let ui = self.ui;
+        let io = ui.io();
+
+        // Les raccourcis des champs texte passent avant
+        if io.want_text_input {
+            return None;
+        }
+
+        let key = KEYS.chars().find(|key| match Self::key_index(*key) {
+            Some(index) => ui.is_key_index_pressed(index),
+            None => false,
+        })?;
+        let pressed = KeyBinding { ctrl: io.key_ctrl, shift: io.key_shift, alt: io.key_alt, key };
+        keybindings.action_for(&pressed)
+    }
+
+    fn draw_settings(&self, config: &mut Config, game: Option<GameVersion>) {
+        let ui = self.ui;
+
+        let mut autosave = config.autosave_interval.is_some();
+        let mut interval = config.autosave_interval.unwrap_or(60) as i32;
+
+        let mut edited = ui.checkbox(im_str!("Autosave"), &mut autosave);
+        if autosave {
+            let width = ui.push_item_width(self.scaled(120.0));
+            edited |= InputInt::new(ui, im_str!("Delay (seconds)"), &mut interval).build();
+            width.pop(ui);
+        }
+
+        if edited {
+            config.autosave_interval = if autosave { Some(interval.max(1) as u32) } else { None };
+        }
+
+        edited |= ui.checkbox(im_str!("Keyboard navigation"), &mut config.keyboard_navigation);
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Arrow keys to move, Enter to edit, Tab to go to the next field");
+        }
+
+        let mut hex = config.number_base == NumberBase::Hexadecimal;
+        if ui.checkbox(im_str!("Hexadecimal integers"), &mut hex) {
+            config.number_base = if hex { NumberBase::Hexadecimal } else { NumberBase::Decimal };
+            edited = true;
+        }
+
+        edited |= ui.checkbox(im_str!("Safe mode"), &mut config.safe_mode);
+        if ui.is_item_hovered() {
+            ui.tooltip_text(
+                "Block edits known to corrupt saves, like class changes or relocations",
+            );
+        }
+
+        edited |= ui.checkbox(im_str!("Verify on open"), &mut config.verify_on_open);
+        if ui.is_item_hovered() {
+            ui.tooltip_text(
+                "Warn when the editor can't write the save back exactly as it was read",
+            );
+        }
+
+        edited |= ui.checkbox(im_str!("Keep modification date"), &mut config.preserve_mtime);
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Saving doesn't move the save to the most recent ones");
+        }
+
+        edited |= ui.checkbox(im_str!("High contrast"), &mut config.high_contrast);
+
+        let (min, max) = FONT_SCALE_RANGE;
+        let width = ui.push_item_width(self.scaled(120.0));
+        if Drag::new(im_str!("Font scale")).speed(0.01).build(ui, &mut config.font_scale) {
+            config.font_scale = config.font_scale.max(min).min(max);
+            edited = true;
+        }
+        width.pop(ui);
+
+        if let Some(_t) = ui.begin_menu(im_str!("Keybindings")) {
+            edited |= self.draw_keybindings(&mut config.keybindings);
+        }
+
+        // Langue des plots
+        let mut names = vec![ImString::new("English")];
+        names.extend(plot_db::LOCALES.iter().map(|(_, name)| ImString::new(*name)));
+        let names: Vec<&ImStr> = names.iter().map(ImString::as_ref).collect();
+        let mut index = config
+            .plot_locale
+            .as_deref()
+            .and_then(|locale| plot_db::LOCALES.iter().position(|(code, _)| *code == locale))
+            .map_or(0, |index| index + 1);
+
+        let width = ui.push_item_width(self.scaled(120.0));
+        if ComboBox::new(im_str!("Plot language")).build_simple_string(ui, &mut index, &names) {
+            config.plot_locale =
+                index.checked_sub(1).map(|index| plot_db::LOCALES[index].0.to_owned());
+            if let Some(game) = game {
+                let _ = self
+                    .event_addr
+                    .send(MainEvent::LoadKnownPlots(game, config.plot_locale.clone()));
+            }
+            edited = true;
+        }
+        width.pop(ui);
+
+        if edited {
+            let _ = self.event_addr.send(MainEvent::SaveConfig(config.clone()));
+        }
+    }
+
+    fn draw_keybindings(&self, keybindings: &mut KeyBindings) -> bool {
+        let ui = self.ui;
+        let keys: Vec<ImString> = KEYS.chars().map(|key| ImString::new(key.to_string())).collect();
+        let keys: Vec<&ImStr> = keys.iter().map(ImString::as_ref).collect();
+
+        let mut edited = false;
+        for action in ACTIONS.iter().copied() {
+            let binding = keybindings.binding_mut(action);
+            ui.text(format!("{:<8}", action));
+            ui.same_line();
+            edited |= ui.checkbox(&im_str!("Ctrl##{}", action), &mut binding.ctrl);
+            ui.same_line();
+            edited |= ui.checkbox(&im_str!("Shift##{}", action), &mut binding.shift);
+            ui.same_line();
+            edited |= ui.checkbox(&im_str!("Alt##{}", action), &mut binding.alt);
+            ui.same_line();
+
+            let mut index = KEYS.find(binding.key).unwrap_or_default();
+            let width = ui.push_item_width(self.scaled(50.0));
+            if ComboBox::new(&im_str!("##key-{}", action))
+                .build_simple_string(ui, &mut index, &keys)
+            {
+                binding.key = KEYS.as_bytes()[index] as char;
+                edited = true;
+            }
+            width.pop(ui);
+        }
+
+        // Avertissement
+        let conflicts = keybindings.conflicts();
+        if !conflicts.is_empty() {
+            ui.separator();
+        }
+        for (action, other) in conflicts {
+            ui.text_colored(
+                [1.0, 0.3, 0.3, 1.0],
+                format!("{} is used by both {} and {}", keybindings.binding(action), action, other),
+            );
+        }
+        edited
+    }
+
     fn draw_about(&self) {
         let ui = self.ui;
 
@@ -304,6 +1796,81 @@ impl<'ui> Gui<'ui> {
         }
     }
 
+    // Lecture proposée par les `FieldDecoder`
+    fn draw_decoded_regions(&self, decoded: &[DecodedRegion]) {
+        let ui = self.ui;
+
+        if let Some(_t) = ChildWindow::new("decoded").size([500.0, 250.0]).begin(ui) {
+            for (i, DecodedRegion { field, len, outputs }) in decoded.iter().enumerate() {
+                let label = im_str!("{} ({} bytes)##region-{}", field, len, i);
+                if let Some(_t) = TreeNode::new(&label).push(ui) {
+                    if outputs.is_empty() {
+                        ui.text_disabled("Nothing decoded");
+                    }
+                    for (name, lines) in outputs {
+                        ui.text_colored([0.3, 0.8, 1.0, 1.0], name);
+                        for line in lines {
+                            ui.text(line);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn draw_unsaved_guard(&self, run: &mut bool, state: &mut State) {
+        let ui = self.ui;
+
+        let save_game = match (&state.unsaved, &state.save_game) {
+            // La popup de confirmation prend la main
+            (Some(UnsavedGuard { confirming: true, .. }), _) => return,
+            (Some(_), Some(save_game)) => save_game,
+            _ => return,
+        };
+        ui.open_popup(im_str!("Unsaved changes###unsaved-changes"));
+
+        if let Some(_t) = PopupModal::new(im_str!("Unsaved changes###unsaved-changes"))
+            .always_auto_resize(true)
+            .begin_popup(ui)
+        {
+            let file_name = Path::new(save_game.file_path())
+                .file_name()
+                .map(|file_name| file_name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            ui.text(format!("`{}` has unsaved changes.", file_name));
+
+            let saving = state.unsaved.as_ref().map_or(false, |unsaved| unsaved.saving);
+            if saving {
+                ui.text_disabled("Saving...");
+            }
+            ui.separator();
+
+            if saving {
+                return;
+            }
+            if ui.button_with_size(im_str!("Save"), [self.scaled(70.0), 0.0]) {
+                let path = save_game.file_path().to_owned();
+                let _ = self.event_addr.send(MainEvent::SaveSave(path, save_game.clone()));
+                if let Some(unsaved) = state.unsaved.as_mut() {
+                    unsaved.saving = true;
+                }
+                return;
+            }
+            ui.same_line();
+            if ui.button_with_size(im_str!("Discard"), [self.scaled(70.0), 0.0]) {
+                if let Some(UnsavedGuard { action, .. }) = state.unsaved.take() {
+                    state.proceed(action, run, &self.event_addr);
+                }
+                ui.close_current_popup();
+            }
+            ui.same_line();
+            if ui.button_with_size(im_str!("Cancel"), [self.scaled(70.0), 0.0]) {
+                state.unsaved = None;
+                ui.close_current_popup();
+            }
+        }
+    }
+
     fn draw_error(&self, option_error: &mut Option<Error>) {
         let ui = self.ui;
 
@@ -322,11 +1889,27 @@ impl<'ui> Gui<'ui> {
                         ui.text(error.to_string());
                     }
                 }
+
+                if let Some(hint) = error_hint(error) {
+                    ui.separator();
+                    ui.text(format!("Hint: {}", hint));
+                }
                 ui.separator();
 
-                if ui.button_with_size(im_str!("OK"), [70.0, 0.0]) {
+                if ui.button_with_size(im_str!("OK"), [self.scaled(70.0), 0.0]) {
                     *option_error = None;
                     ui.close_current_popup();
+                } else {
+                    ui.same_line();
+                    if ui.button(im_str!("Copy to clipboard")) {
+                        // Pour les rapports de bug
+                        ui.set_clipboard_text(&im_str!(
+                            "Trilogy Save Editor v{}\n{:?}",
+                            env!("CARGO_PKG_VERSION"),
+                            error
+                        ));
+                        let _ = self.ui_addr.send(UiEvent::Notification("Copied to clipboard"));
+                    }
                 }
             }
         }
@@ -361,6 +1944,26 @@ impl<'ui> Gui<'ui> {
         }
     }
 
+    fn draw_busy_overlay(&self, busy: Option<&str>) {
+        if let Some(busy) = busy {
+            let ui = self.ui;
+            const SPINNER: [char; 4] = ['|', '/', '-', '\\'];
+            let frame = (ui.time() * 8.0) as usize % SPINNER.len();
+
+            let _style = ui.push_style_color(StyleColor::WindowBg, [0.0, 0.0, 0.0, 0.3]);
+            let window = Window::new(im_str!("###busy"))
+                .position([ui.io().display_size[0] / 2.0, 40.0], Condition::Always)
+                .title_bar(false)
+                .resizable(false)
+                .movable(false)
+                .always_auto_resize(true);
+
+            if let Some(_t) = window.begin(ui) {
+                ui.text(format!("{} {}...", SPINNER[frame], busy));
+            }
+        }
+    }
+
     fn draw_help_marker(&self, desc: &str) {
         let ui = self.ui;
 
@@ -371,9 +1974,15 @@ impl<'ui> Gui<'ui> {
         }
     }
 
-    // Style
+    // Les largeurs fixes suivent la taille du texte
+    fn scaled(&self, width: f32) -> f32 {
+        width * self.font_scale
+    }
+
+    // Style, le contraste élevé remplace le thème de chaque jeu
     fn style_colors(&self, game_theme: Theme) -> [ColorStackToken<'ui>; 22] {
         let ui = self.ui;
+        let game_theme = if self.high_contrast { Theme::HighContrast } else { game_theme };
         let theme = match game_theme {
             Theme::MassEffect1 => ColorTheme {
                 bg_color: [0.11, 0.32, 0.43, 1.0],
@@ -393,12 +2002,33 @@ impl<'ui> Gui<'ui> {
                 active_color: [0.70, 0.0, 0.0, 1.0],
                 hover_color: [0.02, 0.28, 0.43, 1.0],
             },
+            Theme::HighContrast => ColorTheme {
+                bg_color: [0.22, 0.22, 0.22, 1.0],
+                color: [0.35, 0.35, 0.35, 1.0],
+                active_color: [0.80, 0.62, 0.0, 1.0],
+                hover_color: [0.0, 0.42, 0.85, 1.0],
+            },
+        };
+        // Bordures et lignes du tableau bien visibles sur fond noir
+        let (window_bg, border, row_bg, row_bg_alt) = match game_theme {
+            Theme::HighContrast => (
+                [0.0, 0.0, 0.0, 1.0],
+                [1.0, 1.0, 1.0, 1.0],
+                [0.0, 0.0, 0.0, 1.0],
+                [0.12, 0.12, 0.12, 1.0],
+            ),
+            _ => (
+                [0.05, 0.05, 0.05, 1.0],
+                [0.20, 0.20, 0.20, 1.0],
+                [0.07, 0.07, 0.07, 1.0],
+                [0.1, 0.1, 0.1, 1.0],
+            ),
         };
 
         [
-            ui.push_style_color(StyleColor::WindowBg, [0.05, 0.05, 0.05, 1.0]),
-            ui.push_style_color(StyleColor::Border, [0.20, 0.20, 0.20, 1.0]),
-            ui.push_style_color(StyleColor::Separator, [0.20, 0.20, 0.20, 1.0]),
+            ui.push_style_color(StyleColor::WindowBg, window_bg),
+            ui.push_style_color(StyleColor::Border, border),
+            ui.push_style_color(StyleColor::Separator, border),
             ui.push_style_color(StyleColor::TitleBgActive, theme.active_color),
             ui.push_style_color(StyleColor::FrameBg, theme.bg_color),
             ui.push_style_color(StyleColor::FrameBgActive, theme.active_color),
@@ -415,9 +2045,9 @@ impl<'ui> Gui<'ui> {
             ui.push_style_color(StyleColor::HeaderHovered, theme.hover_color),
             ui.push_style_color(StyleColor::CheckMark, [1.0, 1.0, 1.0, 1.0]),
             ui.push_style_color(StyleColor::PlotHistogram, [1.0, 1.0, 1.0, 1.0]),
-            ui.push_style_color(StyleColor::TableRowBg, [0.07, 0.07, 0.07, 1.0]),
-            ui.push_style_color(StyleColor::TableRowBgAlt, [0.1, 0.1, 0.1, 1.0]),
-            ui.push_style_color(StyleColor::TableBorderStrong, [0.20, 0.20, 0.20, 1.0]),
+            ui.push_style_color(StyleColor::TableRowBg, row_bg),
+            ui.push_style_color(StyleColor::TableRowBgAlt, row_bg_alt),
+            ui.push_style_color(StyleColor::TableBorderStrong, border),
         ]
     }
 }
@@ -426,6 +2056,7 @@ enum Theme {
     MassEffect1,
     MassEffect2,
     MassEffect3,
+    HighContrast,
 }
 
 struct ColorTheme {
@@ -434,3 +2065,83 @@ struct ColorTheme {
     active_color: [f32; 4],
     hover_color: [f32; 4],
 }
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use anyhow::Result;
+
+    use super::*;
+
+    #[test]
+    fn unsaved_guard_confirm_save() -> Result<()> {
+        let input = fs::read("test/ME2Save.pcsav")?;
+        let save_game = SaveGame::load(String::new(), &input)?;
+        let (event_addr, _rx) = flume::unbounded();
+
+        let mut state = State::default();
+        state.open(save_game.clone(), &event_addr);
+
+        // Fermeture -> Save -> le disque diffère
+        state.unsaved = Some(UnsavedGuard::new(PendingAction::Close));
+        state.unsaved.as_mut().unwrap().saving = true;
+        state.confirm_saving();
+        assert!(matches!(state.unsaved, Some(UnsavedGuard { confirming: true, .. })));
+
+        // Écrasement confirmé puis enregistré : la fermeture reprend
+        state.unsaved.as_mut().unwrap().confirming = false;
+        let mut run = true;
+        state.saved(&save_game, &mut run, &event_addr);
+        assert!(!run);
+        assert!(state.unsaved.is_none());
+
+        // Écrasement annulé : la fermeture est abandonnée
+        state.unsaved = Some(UnsavedGuard::new(PendingAction::Close));
+        state.unsaved.as_mut().unwrap().saving = true;
+        state.confirm_saving();
+        UnsavedGuard::abort(&mut state.unsaved);
+        assert!(state.unsaved.is_none());
+
+        // Un SavedSave sans demande ne résout pas la garde
+        state.unsaved = Some(UnsavedGuard::new(PendingAction::Close));
+        let mut run = true;
+        state.saved(&save_game, &mut run, &event_addr);
+        assert!(run);
+        assert!(state.unsaved.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn open_resets_inventory_filter() -> Result<()> {
+        let input = fs::read("test/ME1Leg00_QuickSave.pcsav")?;
+        let save_game = SaveGame::load(String::new(), &input)?;
+        let (event_addr, _rx) = flume::unbounded();
+
+        let mut state = State::default();
+        state.inventory_filter.manufacturer_id = Some(10);
+        state.open(save_game, &event_addr);
+        assert!(!state.inventory_filter.is_active());
+        Ok(())
+    }
+
+    #[test]
+    fn jump_rows_focus() -> Result<()> {
+        let input = fs::read("test/ME2Save.pcsav")?;
+        let save_game = SaveGame::load(String::new(), &input)?;
+        let names = plot_db::for_game(save_game.game_version())?.names();
+
+        // Sans recherche, la ligne d'un index est l'index lui-même
+        let rows = jump_rows(&save_game, &names, true, "");
+        assert_eq!(rows.iter().position(|plot_id| *plot_id == 1533), Some(1533));
+        let rows = jump_rows(&save_game, &names, false, "");
+        assert!(rows.contains(&0));
+
+        // Avec recherche, seuls les plots nommés restent
+        let (plot_id, desc) = names.booleans.get_index(0).unwrap();
+        let rows = jump_rows(&save_game, &names, true, desc);
+        assert!(rows.contains(plot_id));
+        assert!(rows.iter().all(|plot_id| names.booleans.contains_key(plot_id)));
+        Ok(())
+    }
+}
@@ -0,0 +1,100 @@
+use imgui::{im_str, ImStr, ImString, PopupModal};
+
+use crate::{
+    event_handler::MainEvent,
+    save_data::{
+        new_character::{self, NewCharacter},
+        shared::player::{Notoriety, Origin},
+    },
+};
+
+use super::{Gui, UiEvent};
+
+#[derive(Default)]
+pub struct NewCharacterState {
+    pub first_name: ImString,
+    pub gender: usize,
+    pub origin: usize,
+    pub notoriety: usize,
+}
+
+impl<'ui> Gui<'ui> {
+    pub fn draw_new_character(&self, option_state: &mut Option<NewCharacterState>) {
+        let ui = self.ui;
+
+        if let Some(state) = option_state {
+            ui.open_popup(im_str!("New ME1 LE character###new-character"));
+
+            if let Some(_t) = PopupModal::new(im_str!("New ME1 LE character###new-character"))
+                .always_auto_resize(true)
+                .begin_popup(ui)
+            {
+                ui.input_text(im_str!("Name"), &mut state.first_name).resize_buffer(true).build();
+
+                const GENDER_LIST: [&ImStr; 2] = [im_str!("Male"), im_str!("Female")];
+                self.draw_edit_enum("Gender", &mut state.gender, &GENDER_LIST);
+                const ORIGIN_LIST: [&ImStr; 4] =
+                    [im_str!("None"), im_str!("Spacer"), im_str!("Colonist"), im_str!("Earthborn")];
+                self.draw_edit_enum("Origin", &mut state.origin, &ORIGIN_LIST);
+                const NOTORIETY_LIST: [&ImStr; 4] = [
+                    im_str!("None"),
+                    im_str!("Survivor"),
+                    im_str!("War Hero"),
+                    im_str!("Ruthless"),
+                ];
+                self.draw_edit_enum("Notoriety", &mut state.notoriety, &NOTORIETY_LIST);
+
+                ui.text_disabled("Class and talents come from the template save, plot and inventory start empty.");
+                ui.separator();
+
+                let mut close = false;
+                if ui.button_with_size(im_str!("OK"), [70.0, 0.0]) {
+                    let character = NewCharacter {
+                        first_name: state.first_name.to_string(),
+                        is_female: state.gender != 0,
+                        origin: match state.origin {
+                            1 => Origin::Spacer,
+                            2 => Origin::Colonist,
+                            3 => Origin::Earthborn,
+                            _ => Origin::None,
+                        },
+                        notoriety: match state.notoriety {
+                            1 => Notoriety::Survivor,
+                            2 => Notoriety::Warhero,
+                            3 => Notoriety::Ruthless,
+                            _ => Notoriety::None,
+                        },
+                    };
+                    let file = tinyfiledialogs::save_file_dialog_with_filter(
+                        "",
+                        &format!("{}00_QuickSave.pcsav", character.first_name),
+                        &["*.pcsav"],
+                        "Mass Effect 1 Legendary Save (*.pcsav)",
+                    );
+                    if let Some(path) = file {
+                        match new_character::me1_leg(path.clone(), &character) {
+                            Ok(save_game) => {
+                                let _ = self
+                                    .event_addr
+                                    .send(MainEvent::SaveSave(path, save_game.clone()));
+                                let _ = self.ui_addr.send(UiEvent::OpenedSave(save_game));
+                                close = true;
+                            }
+                            Err(err) => {
+                                let _ = self.ui_addr.send(UiEvent::Error(err));
+                            }
+                        }
+                    }
+                } else {
+                    ui.same_line();
+                    close = ui.button_with_size(im_str!("Cancel"), [70.0, 0.0]);
+                }
+
+                if close {
+                    *option_state = None;
+                    ui.close_current_popup();
+                }
+            }
+        }
+    }
+}
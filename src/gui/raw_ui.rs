@@ -1,17 +1,25 @@
 use if_chain::if_chain;
 use imgui::{
-    im_str, ColorEdit, ComboBox, Condition, ImStr, ImString, InputFloat, InputInt, ListClipper,
-    TreeNode,
+    im_str, ColorEdit, ComboBox, Condition, DragDropFlags, DragDropSource, DragDropTarget, ImStr,
+    ImString, InputFloat, InputInt, ListClipper, StyleColor, TreeNode,
 };
 use indexmap::IndexMap;
-use std::{fmt::Display, hash::Hash};
-
-use crate::save_data::{
-    shared::{plot::BoolSlice, Guid},
-    RawUi,
+use std::{fmt::Display, hash::Hash, ops::RangeInclusive};
+
+use crate::{
+    config::NumberBase,
+    event_handler::GameVersion,
+    safe_mode::Guarded,
+    save_data::{
+        self,
+        shared::{limits, location, outfits::SquadOutfits, plot::BoolSlice, Guid},
+        ImguiString, RawUi,
+    },
 };
 
-use super::Gui;
+use super::{Gui, UiEvent};
+
+const INVALID_BG: [f32; 4] = [0.6, 0.1, 0.1, 1.0];
 
 impl<'ui> Gui<'ui> {
     // Edit boxes
@@ -19,44 +27,177 @@ impl<'ui> Gui<'ui> {
         self.ui.input_text(&ImString::new(ident), value).resize_buffer(true).build();
     }
 
+    pub fn draw_edit_name(&self, ident: &str, value: &mut ImString) {
+        let ui = self.ui;
+
+        self.draw_edit_string(ident, value);
+        if let Err(err) = limits::check_name(value.to_str()) {
+            ui.text_colored([1.0, 0.3, 0.3, 1.0], err.to_string());
+        }
+    }
+
     pub fn draw_edit_bool(&self, ident: &str, value: &mut bool) {
         let ui = self.ui;
 
-        let width = ui.push_item_width(120.0);
+        let width = ui.push_item_width(self.scaled(120.0));
         ui.checkbox(&ImString::new(ident), value);
         width.pop(ui);
     }
 
     pub fn draw_edit_i32(&self, ident: &str, value: &mut i32) {
+        self.input_i32(ident, value);
+    }
+
+    // Dans la base choisie dans les réglages
+    fn input_i32(&self, ident: &str, value: &mut i32) -> bool {
         let ui = self.ui;
 
-        let width = ui.push_item_width(120.0);
-        InputInt::new(ui, &ImString::new(ident), value).build();
+        let width = ui.push_item_width(self.scaled(120.0));
+        let edited = match self.number_base {
+            NumberBase::Decimal => InputInt::new(ui, &ImString::new(ident), value).build(),
+            NumberBase::Hexadecimal => {
+                let mut input = ImString::new(self.number_base.format(*value));
+                let edited = ui
+                    .input_text(&ImString::new(ident), &mut input)
+                    .chars_hexadecimal(true)
+                    .resize_buffer(true)
+                    .build();
+                match self.number_base.parse(input.to_str()) {
+                    Some(parsed) if edited => {
+                        *value = parsed;
+                        true
+                    }
+                    _ => false,
+                }
+            }
+        };
         width.pop(ui);
+        edited
     }
 
     pub fn draw_edit_f32(&self, ident: &str, value: &mut f32) {
         let ui = self.ui;
 
-        let width = ui.push_item_width(120.0);
+        let width = ui.push_item_width(self.scaled(120.0));
         InputFloat::new(ui, &ImString::new(ident), value).build();
         width.pop(ui);
     }
 
+    pub fn draw_edit_i32_clamped(&self, ident: &str, value: &mut i32, range: RangeInclusive<i32>) {
+        let ui = self.ui;
+
+        let error = limits::range_error(value, &range);
+        let edited = {
+            // Rouge dès le chargement, avant toute modification
+            let _style =
+                error.as_ref().map(|_| ui.push_style_color(StyleColor::FrameBg, INVALID_BG));
+            self.input_i32(ident, value)
+        };
+        self.draw_range_error(error);
+
+        if edited && limits::clamp(value, &range) {
+            self.notify_clamped();
+        }
+    }
+
+    pub fn draw_edit_f32_clamped(&self, ident: &str, value: &mut f32, range: RangeInclusive<f32>) {
+        let ui = self.ui;
+
+        let error = limits::range_error(value, &range);
+        let edited = {
+            let _style =
+                error.as_ref().map(|_| ui.push_style_color(StyleColor::FrameBg, INVALID_BG));
+            let width = ui.push_item_width(self.scaled(120.0));
+            let edited = InputFloat::new(ui, &ImString::new(ident), value).build();
+            width.pop(ui);
+            edited
+        };
+        self.draw_range_error(error);
+
+        if edited && limits::clamp(value, &range) {
+            self.notify_clamped();
+        }
+    }
+
+    fn draw_range_error(&self, error: Option<String>) {
+        let ui = self.ui;
+        if let Some(error) = error {
+            if ui.is_item_hovered() {
+                ui.tooltip_text(error);
+            }
+        }
+    }
+
+    fn notify_clamped(&self) {
+        let _ = self.ui_addr.send(UiEvent::Notification("Value clamped to the game's limit"));
+    }
+
+    // Déménager ne change ni la position ni les quêtes, d'où l'avertissement
+    pub fn draw_edit_location(&self, game: GameVersion, map: &mut ImguiString) {
+        let ui = self.ui;
+
+        let name = location::location_name(game, map.to_str()).unwrap_or("Unknown location");
+        ui.text(format!("Location : {} ({})", name, map));
+
+        let relocations = location::relocations(game);
+        if relocations.is_empty() {
+            return;
+        }
+        if self.safe_mode {
+            ui.text_disabled("Relocation is disabled in safe mode");
+            ui.same_line();
+            self.draw_help_marker(Guarded::Relocation.reason());
+            return;
+        }
+
+        let mut names = vec![ImString::new("Relocate to...")];
+        names.extend(relocations.iter().map(|(_, name)| ImString::new(*name)));
+        let names: Vec<&ImStr> = names.iter().map(ImString::as_ref).collect();
+        let mut index = 0;
+
+        let width = ui.push_item_width(self.scaled(200.0));
+        if ComboBox::new(im_str!("##relocate")).build_simple_string(ui, &mut index, &names) {
+            if let Some((target, _)) = index.checked_sub(1).map(|index| relocations[index]) {
+                *map = ImString::new(target).into();
+            }
+        }
+        width.pop(ui);
+        ui.same_line();
+        self.draw_help_marker(
+            "The position and quest state aren't updated,\nthe game may load you out of bounds or break quests.",
+        );
+    }
+
     pub fn draw_edit_enum(&self, ident: &str, current_item: &mut usize, items: &[&ImStr]) -> bool {
         let ui = self.ui;
 
-        let width = ui.push_item_width(200.0);
+        let width = ui.push_item_width(self.scaled(200.0));
         let edited =
             ComboBox::new(&ImString::new(ident)).build_simple_string(ui, current_item, items);
         width.pop(ui);
         edited
     }
 
+    // Liste des tenues, une valeur inconnue reste éditable en numérique
+    pub fn draw_edit_outfit(&self, ident: &str, value: &mut i32, squad_outfits: &SquadOutfits) {
+        if squad_outfits.label(*value).is_none() {
+            self.draw_edit_i32(ident, value);
+            return;
+        }
+
+        let items: Vec<ImString> =
+            squad_outfits.outfits.iter().map(|outfit| ImString::new(*outfit)).collect();
+        let items: Vec<&ImStr> = items.iter().map(ImString::as_ref).collect();
+        let mut current_item = *value as usize;
+        if self.draw_edit_enum(ident, &mut current_item, &items) {
+            *value = current_item as i32;
+        }
+    }
+
     pub fn draw_edit_color(&self, ident: &str, color: &mut [f32; 4]) {
         let ui = self.ui;
 
-        let width = ui.push_item_width(200.0);
+        let width = ui.push_item_width(self.scaled(200.0));
         ColorEdit::new(&ImString::new(ident), color).build(ui);
         width.pop(ui);
     }
@@ -64,7 +205,7 @@ impl<'ui> Gui<'ui> {
     pub fn draw_edit_guid(&self, ident: &str, guid: &mut Guid) {
         let ui = self.ui;
         {
-            let width = ui.push_item_width(65.0);
+            let width = ui.push_item_width(self.scaled(65.0));
             ui.input_text(&im_str!("##{}-part1", ident), &mut guid.part1)
                 .chars_hexadecimal(true)
                 .build();
@@ -74,7 +215,7 @@ impl<'ui> Gui<'ui> {
         ui.text("-");
         {
             ui.same_line();
-            let width = ui.push_item_width(36.0);
+            let width = ui.push_item_width(self.scaled(36.0));
             ui.input_text(&im_str!("##{}-part2", ident), &mut guid.part2)
                 .chars_hexadecimal(true)
                 .build();
@@ -84,7 +225,7 @@ impl<'ui> Gui<'ui> {
         ui.text("-");
         {
             ui.same_line();
-            let width = ui.push_item_width(36.0);
+            let width = ui.push_item_width(self.scaled(36.0));
             ui.input_text(&im_str!("##{}-part3", ident), &mut guid.part3)
                 .chars_hexadecimal(true)
                 .build();
@@ -94,7 +235,7 @@ impl<'ui> Gui<'ui> {
         ui.text("-");
         {
             ui.same_line();
-            let width = ui.push_item_width(36.0);
+            let width = ui.push_item_width(self.scaled(36.0));
             ui.input_text(&im_str!("##{}-part4", ident), &mut guid.part4)
                 .chars_hexadecimal(true)
                 .build();
@@ -104,7 +245,7 @@ impl<'ui> Gui<'ui> {
         ui.text("-");
         {
             ui.same_line();
-            let width = ui.push_item_width(93.0);
+            let width = ui.push_item_width(self.scaled(93.0));
             ui.input_text(&im_str!("{}##{}-part5", ident, ident), &mut guid.part5)
                 .chars_hexadecimal(true)
                 .build();
@@ -153,6 +294,20 @@ impl<'ui> Gui<'ui> {
     }
 
     pub fn draw_vec<T>(&self, ident: &str, list: &mut Vec<T>)
+    where
+        T: RawUi + Default,
+    {
+        self.draw_list(ident, list, false);
+    }
+
+    pub fn draw_reorderable_vec<T>(&self, ident: &str, list: &mut Vec<T>)
+    where
+        T: RawUi + Default,
+    {
+        self.draw_list(ident, list, true);
+    }
+
+    fn draw_list<T>(&self, ident: &str, list: &mut Vec<T>, reorderable: bool)
     where
         T: RawUi + Default,
     {
@@ -171,11 +326,37 @@ impl<'ui> Gui<'ui> {
         };
 
         if !list.is_empty() {
+            // La liste est identifiée par son adresse pour ne pas déposer dans une autre
+            let list_id = list.as_ptr() as usize;
+
             // Item
             let mut remove = None;
+            let mut moved = None;
             for (i, item) in list.iter_mut().enumerate() {
                 self.table_next_row();
                 ui.align_text_to_frame_padding();
+                if reorderable {
+                    ui.small_button(&im_str!("move##move-{}", i));
+                    if let Some(tooltip) =
+                        DragDropSource::new(im_str!("vec-item")).begin_payload(ui, (list_id, i))
+                    {
+                        ui.text(format!("Move {}", i));
+                        tooltip.end();
+                    }
+                    if let Some(target) = DragDropTarget::new(ui) {
+                        if let Some(Ok(payload)) = target.accept_payload::<(usize, usize)>(
+                            im_str!("vec-item"),
+                            DragDropFlags::empty(),
+                        ) {
+                            let (from_list, from) = payload.data;
+                            if from_list == list_id {
+                                moved = Some((from, i));
+                            }
+                        }
+                        target.pop();
+                    }
+                    ui.same_line();
+                }
                 if ui.small_button(&im_str!("remove##remove-{}", i)) {
                     remove = Some(i);
                 }
@@ -186,6 +367,8 @@ impl<'ui> Gui<'ui> {
             // Remove
             if let Some(i) = remove {
                 list.remove(i);
+            } else if let Some((from, to)) = moved {
+                save_data::move_item(list, from, to);
             }
         } else {
             self.table_next_row();
@@ -0,0 +1,111 @@
+use imgui::{im_str, ChildWindow, PopupModal, Selectable, TreeNode};
+use std::path::Path;
+
+use crate::event_handler::{GameVersion, MainEvent, SaveEntry, SaveGroup};
+
+use super::Gui;
+
+pub struct SaveBrowserState {
+    pub dir: String,
+    pub groups: Vec<SaveGroup>,
+}
+
+impl<'ui> Gui<'ui> {
+    pub fn draw_save_browser(&self, option_browser: &mut Option<SaveBrowserState>) {
+        let ui = self.ui;
+
+        if let Some(SaveBrowserState { dir, groups }) = option_browser {
+            ui.open_popup(im_str!("Saves###save-browser"));
+
+            if let Some(_t) = PopupModal::new(im_str!("Saves###save-browser"))
+                .always_auto_resize(true)
+                .begin_popup(ui)
+            {
+                ui.text(dir.as_str());
+                ui.separator();
+
+                let mut opened = false;
+                if let Some(_t) = ChildWindow::new("saves").size([500.0, 300.0]).begin(ui) {
+                    match groups.as_slice() {
+                        [] => ui.text("No save in this folder"),
+                        // Dossier d'un seul personnage, pas besoin d'arborescence
+                        [SaveGroup { characters, .. }] if characters.len() == 1 => {
+                            opened = self.draw_save_entries(&characters[0].1);
+                        }
+                        _ => {
+                            for (i, SaveGroup { game, characters }) in groups.iter().enumerate() {
+                                let label = game.map_or("Unknown game", game_name);
+                                if let Some(_t) = TreeNode::new(&im_str!("{}##game-{}", label, i))
+                                    .default_open(true)
+                                    .push(ui)
+                                {
+                                    for (j, (character, entries)) in characters.iter().enumerate() {
+                                        if character.is_empty() {
+                                            opened |= self.draw_save_entries(entries);
+                                            continue;
+                                        }
+                                        let label = im_str!("{}##character-{}", character, j);
+                                        if let Some(_t) = TreeNode::new(&label).push(ui) {
+                                            opened |= self.draw_save_entries(entries);
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                ui.separator();
+
+                if ui.button_with_size(im_str!("Close"), [70.0, 0.0]) || opened {
+                    *option_browser = None;
+                    ui.close_current_popup();
+                }
+            }
+        }
+    }
+
+    // Renvoie `true` si une sauvegarde a été ouverte
+    fn draw_save_entries(&self, entries: &[SaveEntry]) -> bool {
+        let ui = self.ui;
+
+        let mut opened = false;
+        for SaveEntry { file_path, integrity } in entries {
+            // Badge
+            match integrity {
+                Ok(game) => {
+                    ui.text_colored([0.3, 1.0, 0.3, 1.0], "OK     ");
+                    if ui.is_item_hovered() {
+                        ui.tooltip_text(format!("{:?}", game));
+                    }
+                }
+                Err(err) => {
+                    ui.text_colored([1.0, 0.3, 0.3, 1.0], "CORRUPT");
+                    if ui.is_item_hovered() {
+                        ui.tooltip_text(err);
+                    }
+                }
+            }
+            ui.same_line();
+
+            let file_name = Path::new(file_path)
+                .file_name()
+                .map(|file_name| file_name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            if Selectable::new(&im_str!("{}##{}", file_name, file_path)).build(ui) {
+                let _ = self.event_addr.send(MainEvent::OpenSave(file_path.clone()));
+                opened = true;
+            }
+        }
+        opened
+    }
+}
+
+fn game_name(game: GameVersion) -> &'static str {
+    match game {
+        GameVersion::MassEffect1 => "Mass Effect 1",
+        GameVersion::MassEffect1Leg => "Mass Effect 1 Legendary",
+        GameVersion::MassEffect2 => "Mass Effect 2",
+        GameVersion::MassEffect2Leg => "Mass Effect 2 Legendary",
+        GameVersion::MassEffect3 => "Mass Effect 3",
+    }
+}
@@ -0,0 +1,51 @@
+use imgui::{im_str, ImString, ProgressBar};
+
+use crate::save_data::summary::CharacterSummary;
+
+use super::Gui;
+
+impl<'ui> Gui<'ui> {
+    pub fn draw_character_summary(&self, summary: &CharacterSummary) -> Option<()> {
+        let ui = self.ui;
+        let CharacterSummary {
+            name, level, class_name, paragon, renegade, credits, romances, ..
+        } = summary;
+
+        let _t = self.begin_table(im_str!("summary-table"), 1)?;
+        self.table_next_row();
+        self.set_next_item_open(true);
+        let _t = self.push_tree_node("Summary")?;
+
+        self.table_next_row();
+        match class_name {
+            Some(class_name) => ui.text(format!("{}, level {} {}", name, level, class_name)),
+            None => ui.text(format!("{}, level {}", name, level)),
+        }
+
+        self.table_next_row();
+        ui.text(format!("Playtime : {}", summary.playtime()));
+
+        self.table_next_row();
+        ui.text(format!("Credits : {}", credits));
+
+        // Paragon / Renegade
+        let total = (*paragon + *renegade).max(1) as f32;
+        self.table_next_row();
+        ProgressBar::new(*paragon as f32 / total)
+            .overlay_text(&ImString::new(format!("Paragon : {}", paragon)))
+            .build(ui);
+        self.table_next_row();
+        ProgressBar::new(*renegade as f32 / total)
+            .overlay_text(&ImString::new(format!("Renegade : {}", renegade)))
+            .build(ui);
+
+        self.table_next_row();
+        if romances.is_empty() {
+            ui.text("Romance : None");
+        } else {
+            ui.text(format!("Romance : {}", romances.join(", ")));
+        }
+
+        Some(())
+    }
+}
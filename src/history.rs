@@ -0,0 +1,162 @@
+use anyhow::Result;
+use std::mem;
+
+// Une copie complète de la sauvegarde par étape, les plus anciennes sont oubliées
+const MAX_STEPS: usize = 20;
+
+// Annulation par copies, une opération groupée ne compte que pour une étape
+pub struct History<T> {
+    undo: Vec<T>,
+    redo: Vec<T>,
+    // État d'avant le groupe en cours et profondeur d'imbrication
+    group: Option<(T, usize)>,
+}
+
+impl<T> Default for History<T> {
+    fn default() -> Self {
+        History { undo: Vec::new(), redo: Vec::new(), group: None }
+    }
+}
+
+impl<T: Clone> History<T> {
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    // Dans un groupe, l'état d'avant le groupe suffit
+    pub fn record(&mut self, before: T) {
+        if self.group.is_none() {
+            self.push(before);
+        }
+    }
+
+    pub fn begin(&mut self, current: &T) {
+        match &mut self.group {
+            Some((_, depth)) => *depth += 1,
+            None => self.group = Some((current.clone(), 1)),
+        }
+    }
+
+    // Seul le groupe le plus externe devient une étape
+    pub fn commit(&mut self) {
+        match self.group.take() {
+            Some((before, depth)) if depth > 1 => self.group = Some((before, depth - 1)),
+            Some((before, _)) => self.push(before),
+            None => (),
+        }
+    }
+
+    // Tout ou rien : on revient à l'état d'avant le groupe le plus externe
+    pub fn rollback(&mut self, current: &mut T) {
+        if let Some((before, _)) = self.group.take() {
+            *current = before;
+        }
+    }
+
+    pub fn transaction(
+        &mut self, current: &mut T, edit: impl FnOnce(&mut T) -> Result<()>,
+    ) -> Result<()> {
+        self.begin(current);
+        match edit(current) {
+            Ok(()) => {
+                self.commit();
+                Ok(())
+            }
+            Err(err) => {
+                self.rollback(current);
+                Err(err)
+            }
+        }
+    }
+
+    pub fn undo(&mut self, current: &mut T) -> bool {
+        match self.undo.pop() {
+            Some(before) => {
+                self.redo.push(mem::replace(current, before));
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn redo(&mut self, current: &mut T) -> bool {
+        match self.redo.pop() {
+            Some(after) => {
+                self.undo.push(mem::replace(current, after));
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn push(&mut self, before: T) {
+        if self.undo.len() == MAX_STEPS {
+            self.undo.remove(0);
+        }
+        self.undo.push(before);
+        self.redo.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::bail;
+    use std::fs;
+
+    use crate::event_handler::SaveGame;
+
+    use super::*;
+
+    #[test]
+    fn grouped_undo() -> Result<()> {
+        let input = fs::read("test/ME2Save.pcsav")?;
+        let mut save_game = SaveGame::load(String::new(), &input)?;
+        let original = save_game.to_json_value()?;
+        let mut history = History::default();
+
+        // Plusieurs champs, une seule étape
+        history.transaction(&mut save_game, |save_game| {
+            save_game.max_character()?;
+            if let SaveGame::MassEffect2 { save_game, .. } = save_game {
+                save_game.plot.int_variables[10] += 3;
+            }
+            Ok(())
+        })?;
+        let edited = save_game.to_json_value()?;
+        assert!(crate::edit_log::diff(&original, &edited).len() > 1);
+
+        assert!(history.undo(&mut save_game));
+        assert_eq!(save_game.to_json_value()?, original);
+        assert!(!history.can_undo());
+        assert!(history.redo(&mut save_game));
+        assert_eq!(save_game.to_json_value()?, edited);
+
+        // Groupes imbriqués
+        history.begin(&save_game);
+        history.record(save_game.clone());
+        history.begin(&save_game);
+        if let SaveGame::MassEffect2 { save_game, .. } = &mut save_game {
+            save_game.player.level = 1;
+        }
+        history.commit();
+        history.commit();
+        assert!(history.undo(&mut save_game));
+        assert_eq!(save_game.to_json_value()?, edited);
+        assert!(history.undo(&mut save_game));
+        assert_eq!(save_game.to_json_value()?, original);
+
+        // Une erreur au milieu ne laisse rien à moitié modifié
+        let failed = history.transaction(&mut save_game, |save_game| {
+            save_game.max_character()?;
+            bail!("Oops")
+        });
+        assert!(failed.is_err());
+        assert_eq!(save_game.to_json_value()?, original);
+        assert!(!history.can_undo());
+        Ok(())
+    }
+}
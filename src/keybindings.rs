@@ -0,0 +1,160 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+pub const ACTIONS: [Action; 5] =
+    [Action::Save, Action::Undo, Action::Redo, Action::Search, Action::Open];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Action {
+    Save,
+    Undo,
+    Redo,
+    Search,
+    Open,
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Action::Save => "Save",
+            Action::Undo => "Undo",
+            Action::Redo => "Redo",
+            Action::Search => "Search",
+            Action::Open => "Open",
+        };
+        f.pad(name)
+    }
+}
+
+// Touches acceptées : lettres et chiffres
+pub const KEYS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct KeyBinding {
+    #[serde(default)]
+    pub ctrl: bool,
+    #[serde(default)]
+    pub shift: bool,
+    #[serde(default)]
+    pub alt: bool,
+    pub key: char,
+}
+
+impl KeyBinding {
+    fn ctrl(key: char) -> Self {
+        KeyBinding { ctrl: true, shift: false, alt: false, key }
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.ctrl {
+            f.write_str("Ctrl+")?;
+        }
+        if self.shift {
+            f.write_str("Shift+")?;
+        }
+        if self.alt {
+            f.write_str("Alt+")?;
+        }
+        write!(f, "{}", self.key.to_ascii_uppercase())
+    }
+}
+
+// Les actions absentes du fichier de config gardent leur raccourci par défaut
+#[derive(Deserialize, Serialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct KeyBindings {
+    pub save: KeyBinding,
+    pub undo: KeyBinding,
+    pub redo: KeyBinding,
+    pub search: KeyBinding,
+    pub open: KeyBinding,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        KeyBindings {
+            save: KeyBinding::ctrl('S'),
+            undo: KeyBinding::ctrl('Z'),
+            redo: KeyBinding::ctrl('Y'),
+            search: KeyBinding::ctrl('F'),
+            open: KeyBinding::ctrl('O'),
+        }
+    }
+}
+
+impl KeyBindings {
+    pub fn binding(&self, action: Action) -> &KeyBinding {
+        match action {
+            Action::Save => &self.save,
+            Action::Undo => &self.undo,
+            Action::Redo => &self.redo,
+            Action::Search => &self.search,
+            Action::Open => &self.open,
+        }
+    }
+
+    pub fn binding_mut(&mut self, action: Action) -> &mut KeyBinding {
+        match action {
+            Action::Save => &mut self.save,
+            Action::Undo => &mut self.undo,
+            Action::Redo => &mut self.redo,
+            Action::Search => &mut self.search,
+            Action::Open => &mut self.open,
+        }
+    }
+
+    // En cas de conflit, la première action de la liste l'emporte
+    pub fn action_for(&self, pressed: &KeyBinding) -> Option<Action> {
+        ACTIONS.iter().copied().find(|action| self.binding(*action) == pressed)
+    }
+
+    pub fn conflicts(&self) -> Vec<(Action, Action)> {
+        let mut conflicts = Vec::new();
+        for (i, action) in ACTIONS.iter().enumerate() {
+            for other in &ACTIONS[i + 1..] {
+                if self.binding(*action) == self.binding(*other) {
+                    conflicts.push((*action, *other));
+                }
+            }
+        }
+        conflicts
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use crate::config::Config;
+
+    use super::*;
+
+    #[test]
+    fn remapped_key_from_config() -> anyhow::Result<()> {
+        let path = std::env::temp_dir().join("trilogy_save_editor_keybindings.ron");
+        fs::write(&path, "(keybindings: (save: (ctrl: true, shift: true, key: 'W')))")?;
+        let config = Config::load(&path)?;
+        fs::remove_file(&path)?;
+
+        let pressed = KeyBinding { ctrl: true, shift: true, alt: false, key: 'W' };
+        assert_eq!(config.keybindings.action_for(&pressed), Some(Action::Save));
+        assert_eq!(config.keybindings.action_for(&KeyBinding::ctrl('S')), None);
+
+        // Les autres gardent leur raccourci par défaut
+        assert_eq!(config.keybindings.action_for(&KeyBinding::ctrl('Z')), Some(Action::Undo));
+        assert_eq!(config.keybindings.save.to_string(), "Ctrl+Shift+W");
+        Ok(())
+    }
+
+    #[test]
+    fn conflicts() {
+        let mut keybindings = KeyBindings::default();
+        assert!(keybindings.conflicts().is_empty());
+
+        *keybindings.binding_mut(Action::Search) = KeyBinding::ctrl('S');
+        assert_eq!(keybindings.conflicts(), vec![(Action::Save, Action::Search)]);
+        assert_eq!(keybindings.action_for(&KeyBinding::ctrl('S')), Some(Action::Save));
+    }
+}
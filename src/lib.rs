@@ -0,0 +1,25 @@
+#![warn(clippy::all)]
+
+extern crate derive_more;
+
+#[macro_use]
+extern crate raw_ui_derive;
+
+pub mod autosave;
+pub mod cli;
+pub mod config;
+pub mod edit_log;
+pub mod event_handler;
+pub mod gui;
+pub mod history;
+pub mod keybindings;
+pub mod overview;
+pub mod patch;
+pub mod plot_db;
+pub mod preset;
+pub mod revive;
+pub mod safe_mode;
+pub mod save_archive;
+pub mod save_data;
+pub mod save_slot;
+pub mod unreal;
@@ -2,18 +2,18 @@
 #![cfg_attr(test, windows_subsystem = "console")]
 #![warn(clippy::all)]
 
-extern crate derive_more;
-
-use std::panic::{self, PanicInfo};
+use std::{
+    env,
+    panic::{self, PanicInfo},
+    process,
+};
 use tokio::task;
 
-#[macro_use]
-extern crate raw_ui_derive;
-
-mod event_handler;
-mod gui;
-mod save_data;
-mod unreal;
+use trilogy_save_editor::{
+    cli::{self, Launch},
+    event_handler::{self, MainEvent},
+    gui,
+};
 
 #[tokio::main]
 async fn main() {
@@ -26,12 +26,32 @@ async fn main() {
         panic_hook(e);
     }));
 
+    let args: Vec<String> = env::args().skip(1).collect();
+    let file = match cli::parse_launch(&args) {
+        Launch::Gui(file) => file,
+        Launch::Cli => {
+            #[cfg(target_os = "windows")]
+            console::attach();
+            if let Err(err) = cli::run(&args) {
+                eprintln!("{:#}", err);
+                process::exit(1);
+            }
+            return;
+        }
+    };
+
     let (event_addr, event_rx) = flume::unbounded();
     let (ui_addr, ui_rx) = flume::unbounded();
 
-    let event_loop = tokio::spawn(event_handler::event_loop(event_rx, ui_addr));
+    let event_loop =
+        tokio::spawn(event_handler::event_loop(event_rx, flume::Sender::clone(&ui_addr)));
+
+    // Association de fichiers, une erreur s'affichera dans l'éditeur
+    if let Some(file) = file {
+        let _ = event_addr.send(MainEvent::OpenSave(file));
+    }
 
-    task::block_in_place(move || gui::run(event_addr, ui_rx));
+    task::block_in_place(move || gui::run(event_addr, ui_addr, ui_rx));
     event_loop.await.unwrap();
 }
 
@@ -0,0 +1,223 @@
+use anyhow::Result;
+use indexmap::IndexMap;
+use std::path::Path;
+
+use crate::{
+    event_handler::SaveGame,
+    plot_db::{self, PlotDb},
+    save_data::shared::plot::{BoolSlice, PlotCategory, QuestState},
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum OverviewFormat {
+    Text,
+    Markdown,
+}
+
+impl OverviewFormat {
+    // `.md` pour le Markdown, le reste en texte
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension() {
+            Some(ext) if unicase::eq(ext.to_string_lossy().as_ref(), "md") => {
+                OverviewFormat::Markdown
+            }
+            _ => OverviewFormat::Text,
+        }
+    }
+}
+
+// Vue d'ensemble en lecture seule, à partager pour savoir si une sauvegarde est complète
+pub struct Overview {
+    pub title: String,
+    pub sections: Vec<(&'static str, Vec<String>)>,
+}
+
+impl Overview {
+    pub fn new(save_game: &SaveGame) -> Result<Self> {
+        let file_name = Path::new(save_game.file_path())
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let plot_db = plot_db::for_game(save_game.game_version())?;
+        let (booleans, ints) = plot_tables(save_game);
+
+        let sections = vec![
+            ("Character", character(save_game)),
+            ("Decisions", decisions(&plot_db, booleans)),
+            ("DLC", dlcs(save_game)),
+            ("Quests", quests(&plot_db, booleans, ints)),
+        ];
+        Ok(Overview { title: format!("{} ({:?})", file_name, save_game.game_version()), sections })
+    }
+
+    pub fn render(&self, format: OverviewFormat) -> String {
+        let mut output = String::new();
+        match format {
+            OverviewFormat::Text => {
+                output.push_str(&format!("{}\n{}\n", self.title, "=".repeat(self.title.len())));
+                for (title, lines) in &self.sections {
+                    output.push_str(&format!("\n{}\n{}\n", title, "-".repeat(title.len())));
+                    for line in lines {
+                        output.push_str(&format!("{}\n", line));
+                    }
+                }
+            }
+            OverviewFormat::Markdown => {
+                output.push_str(&format!("# {}\n", self.title));
+                for (title, lines) in &self.sections {
+                    output.push_str(&format!("\n## {}\n\n", title));
+                    for line in lines {
+                        output.push_str(&format!("- {}\n", line));
+                    }
+                }
+            }
+        }
+        output
+    }
+}
+
+// Les entiers de ME3 sont dans une map, l'état des missions n'utilise que les booléens
+fn plot_tables(save_game: &SaveGame) -> (&BoolSlice, &[i32]) {
+    match save_game {
+        SaveGame::MassEffect1 { save_game, .. } => {
+            let plot = &save_game.state.plot;
+            (&plot.bool_variables, &plot.int_variables)
+        }
+        SaveGame::MassEffect1Leg { save_game, .. } => {
+            let plot = &save_game.save_data.plot;
+            (&plot.bool_variables, &plot.int_variables)
+        }
+        SaveGame::MassEffect2 { save_game, .. } => {
+            (&save_game.plot.bool_variables, &save_game.plot.int_variables)
+        }
+        SaveGame::MassEffect2Leg { save_game, .. } => {
+            (&save_game.plot.bool_variables, &save_game.plot.int_variables)
+        }
+        SaveGame::MassEffect3 { save_game, .. } => (&save_game.plot.bool_variables, &[]),
+    }
+}
+
+fn character(save_game: &SaveGame) -> Vec<String> {
+    let summary = match save_game.summary() {
+        Some(summary) => summary,
+        None => return vec!["Not available, the Mass Effect 1 player isn't mapped".to_owned()],
+    };
+    let mut lines = vec![format!("Name: {}", summary.name)];
+    if let Some(class_name) = &summary.class_name {
+        lines.push(format!("Class: {}", class_name));
+    }
+    lines.extend(vec![
+        format!("Level: {}", summary.level),
+        format!("Playtime: {}", summary.playtime()),
+        format!("Paragon: {} / Renegade: {}", summary.paragon, summary.renegade),
+        format!("Credits: {}", summary.credits),
+    ]);
+    if !summary.romances.is_empty() {
+        lines.push(format!("Romance: {}", summary.romances.join(", ")));
+    }
+    lines
+}
+
+// Booléens de l'équipage et des romances, seuls ceux qui sont vrais
+fn decisions(plot_db: &PlotDb, booleans: &BoolSlice) -> Vec<String> {
+    let categories: Vec<&IndexMap<String, PlotCategory>> = match plot_db {
+        PlotDb::Me1(me1_known_plot) => vec![&me1_known_plot.player_crew],
+        PlotDb::Me2(me2_known_plot) => vec![&me2_known_plot.crew, &me2_known_plot.romance],
+        PlotDb::Me3(me3_known_plot) => vec![&me3_known_plot.crew, &me3_known_plot.romance],
+    };
+
+    let mut lines = Vec::new();
+    for (name, category) in categories.into_iter().flatten() {
+        for (plot_id, desc) in &category.booleans {
+            if booleans.get(*plot_id).map_or(false, |value| *value) {
+                lines.push(format!("{}: {}", name, desc));
+            }
+        }
+    }
+    if lines.is_empty() {
+        lines.push("None".to_owned());
+    }
+    lines
+}
+
+fn dlcs(save_game: &SaveGame) -> Vec<String> {
+    let names: Vec<String> = match save_game {
+        SaveGame::MassEffect1 { .. } | SaveGame::MassEffect1Leg { .. } => {
+            return vec!["Not recorded by Mass Effect 1 saves".to_owned()]
+        }
+        SaveGame::MassEffect2 { save_game, .. } => {
+            save_game.dependant_dlcs.iter().map(|dlc| dlc.name.to_string()).collect()
+        }
+        SaveGame::MassEffect2Leg { save_game, .. } => {
+            save_game.dependant_dlcs.iter().map(|dlc| dlc.name.to_string()).collect()
+        }
+        SaveGame::MassEffect3 { save_game, .. } => {
+            save_game.dependant_dlcs.iter().map(|dlc| dlc.name.to_string()).collect()
+        }
+    };
+    if names.is_empty() {
+        vec!["None".to_owned()]
+    } else {
+        names
+    }
+}
+
+// Quêtes avec un plot de fin, celles pas encore commencées sont omises
+fn quests(plot_db: &PlotDb, booleans: &BoolSlice, ints: &[i32]) -> Vec<String> {
+    let categories: Vec<&IndexMap<String, PlotCategory>> = match plot_db {
+        PlotDb::Me1(me1_known_plot) => vec![&me1_known_plot.missions],
+        PlotDb::Me2(me2_known_plot) => {
+            vec![&me2_known_plot.missions, &me2_known_plot.loyalty_missions]
+        }
+        PlotDb::Me3(me3_known_plot) => vec![&me3_known_plot.missions],
+    };
+
+    let mut lines = Vec::new();
+    for category in categories.into_iter().flat_map(IndexMap::values) {
+        for (quest, side_quest) in category.side_quests() {
+            let state = match side_quest.state(booleans, ints) {
+                Some(QuestState::Complete) => "complete",
+                Some(QuestState::InProgress) => "in progress",
+                Some(QuestState::NotStarted) | None => continue,
+            };
+            lines.push(format!("{}: {}", quest, state));
+        }
+    }
+    if lines.is_empty() {
+        lines.push("None started".to_owned());
+    }
+    lines
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn overview_sections() -> Result<()> {
+        let input = fs::read("test/ME2Save.pcsav")?;
+        let save_game = SaveGame::load("ME2Save.pcsav".to_owned(), &input)?;
+        let overview = Overview::new(&save_game)?;
+
+        let titles: Vec<_> = overview.sections.iter().map(|(title, _)| *title).collect();
+        assert_eq!(titles, ["Character", "Decisions", "DLC", "Quests"]);
+
+        // Le résumé du personnage est repris tel quel
+        let summary = save_game.summary().unwrap();
+        let text = overview.render(OverviewFormat::Text);
+        assert!(text.starts_with("ME2Save.pcsav (MassEffect2)\n==="));
+        assert!(text.contains(&format!("Name: {}\n", summary.name)));
+        assert!(text.contains("\nDLC\n---\n"));
+
+        let markdown = overview.render(OverviewFormat::Markdown);
+        assert!(markdown.starts_with("# ME2Save.pcsav"));
+        assert!(markdown.contains("\n## Decisions\n"));
+        assert!(markdown.contains(&format!("- Level: {}\n", summary.level)));
+
+        assert_eq!(OverviewFormat::from_path(Path::new("a.MD")), OverviewFormat::Markdown);
+        assert_eq!(OverviewFormat::from_path(Path::new("a.txt")), OverviewFormat::Text);
+        Ok(())
+    }
+}
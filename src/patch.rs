@@ -0,0 +1,418 @@
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::mem;
+
+use crate::{
+    edit_log::{self, bool_variable, to_pointer, unescape},
+    event_handler::{GameVersion, SaveGame},
+};
+
+// Valeurs finales des champs modifiés de A vers B, adressés par leur chemin pour rester
+// applicables à une autre sauvegarde du même jeu
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct SavePatch {
+    pub game: GameVersion,
+    pub fields: Vec<PatchField>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct PatchField {
+    pub path: String,
+    pub value: Value,
+}
+
+impl SavePatch {
+    pub fn between(from: &SaveGame, to: &SaveGame) -> Result<Self> {
+        let game = from.game_version();
+        if to.game_version() != game {
+            bail!("Can't compare a {:?} save with a {:?} save", game, to.game_version());
+        }
+
+        let before = from.to_json_value()?;
+        let after = to.to_json_value()?;
+        let fields = edit_log::diff(&before, &after)
+            .iter()
+            .map(|change| {
+                let path = change.path().to_owned();
+                let value = field_value(&after, &path).unwrap_or(Value::Null);
+                PatchField { path, value }
+            })
+            .collect();
+        Ok(SavePatch { game, fields })
+    }
+
+    pub fn from_json(input: &str) -> Result<Self> {
+        serde_json::from_str(input).context("Not a save patch")
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    // Tout ou rien, la sauvegarde n'est pas touchée si un champ manque
+    pub fn apply(&self, save_game: &mut SaveGame) -> Result<()> {
+        if save_game.game_version() != self.game {
+            bail!("This patch is for {:?}, not {:?}", self.game, save_game.game_version());
+        }
+
+        let mut current = save_game.to_json_value()?;
+        for PatchField { path, value } in &self.fields {
+            set_field(&mut current, path, value)
+                .with_context(|| format!("Can't apply `{}`", path))?;
+        }
+        save_game.set_json_value(current)
+    }
+}
+
+// Les booléens de plot sont des bits, le patch garde l'état voulu et pas un basculement
+fn field_value(json: &Value, path: &str) -> Option<Value> {
+    match bool_variable(path) {
+        Some((array_path, id)) => {
+            let bits = json.pointer(&to_pointer(array_path))?.get(id / 32)?.as_u64()?;
+            Some(Value::Bool(bits & (1 << (id % 32)) != 0))
+        }
+        None => json.pointer(&to_pointer(path)).cloned(),
+    }
+}
+
+fn set_field(json: &mut Value, path: &str, value: &Value) -> Result<()> {
+    if let Some((array_path, id)) = bool_variable(path) {
+        let set = value.as_bool().context("Expected a boolean")?;
+        let (idx, mask) = (id / 32, 1 << (id % 32));
+        let bits = json
+            .pointer_mut(&to_pointer(array_path))
+            .and_then(|bits| bits.get_mut(idx))
+            .context("Plot flag out of range")?;
+        let old = bits.as_u64().unwrap_or(0);
+        *bits = Value::from(if set { old | mask } else { old & !mask });
+        return Ok(());
+    }
+
+    let pointer = to_pointer(path);
+    if let Some(field) = json.pointer_mut(&pointer) {
+        *field = value.clone();
+        return Ok(());
+    }
+
+    // Clé absente de cette sauvegarde, ex. un int de plot de ME3
+    let (parent, key) = pointer.rsplit_once('/').unwrap_or_default();
+    match json.pointer_mut(parent).and_then(Value::as_object_mut) {
+        Some(map) => {
+            map.insert(unescape(key), value.clone());
+            Ok(())
+        }
+        None => bail!("Missing field"),
+    }
+}
+
+// Document JSON Patch (RFC 6902) sur la représentation de `SaveGame::to_json`,
+// les booléens de plot y sont des mots de 32 bits
+#[derive(Deserialize, PartialEq, Debug)]
+#[serde(transparent)]
+pub struct JsonPatch(Vec<JsonPatchOp>);
+
+#[derive(Deserialize, PartialEq, Debug)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum JsonPatchOp {
+    Add { path: String, value: Value },
+    Remove { path: String },
+    Replace { path: String, value: Value },
+    Move { from: String, path: String },
+    Copy { from: String, path: String },
+    Test { path: String, value: Value },
+}
+
+impl JsonPatch {
+    pub fn from_json(input: &str) -> Result<Self> {
+        serde_json::from_str(input).context("Not a JSON patch")
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    // Tout ou rien, la sauvegarde n'est pas touchée si une opération échoue
+    pub fn apply(&self, save_game: &mut SaveGame) -> Result<()> {
+        let mut current = save_game.to_json_value()?;
+        for (idx, op) in self.0.iter().enumerate() {
+            op.apply(&mut current).with_context(|| format!("Operation {} failed", idx))?;
+        }
+
+        let mut patched = save_game.clone();
+        patched.set_json_value(current.clone()).context("The patched save isn't valid")?;
+
+        // Serde ignore les clés inconnues d'une structure, elles ne doivent pas disparaître
+        // en silence
+        if let Some(path) = lost_field(&current, &patched.to_json_value()?, String::new()) {
+            bail!("The patched save isn't valid: `{}` isn't part of the save", path);
+        }
+        *save_game = patched;
+        Ok(())
+    }
+}
+
+impl JsonPatchOp {
+    fn apply(&self, json: &mut Value) -> Result<()> {
+        match self {
+            JsonPatchOp::Add { path, value } => add(json, path, value.clone()),
+            JsonPatchOp::Remove { path } => remove(json, path).map(drop),
+            JsonPatchOp::Replace { path, value } => {
+                let field = get_mut(json, path)?;
+                check_type(field, value)?;
+                *field = value.clone();
+                Ok(())
+            }
+            JsonPatchOp::Move { from, path } => {
+                if path.starts_with(&format!("{}/", from)) {
+                    bail!("Can't move `{}` into itself", from);
+                }
+                let value = remove(json, from)?;
+                add(json, path, value)
+            }
+            JsonPatchOp::Copy { from, path } => {
+                let value = get_mut(json, from)?.clone();
+                add(json, path, value)
+            }
+            JsonPatchOp::Test { path, value } => {
+                if get_mut(json, path)? != value {
+                    bail!("`{}` isn't {}", path, value);
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+// Premier champ du JSON patché absent de la sauvegarde relue, ou tableau dont la longueur
+// n'a pas été gardée
+fn lost_field(patched: &Value, reloaded: &Value, path: String) -> Option<String> {
+    match (patched, reloaded) {
+        (Value::Object(patched), Value::Object(reloaded)) => {
+            patched.iter().find_map(|(key, value)| {
+                let path = format!("{}/{}", path, key.replace('~', "~0").replace('/', "~1"));
+                match reloaded.get(key) {
+                    Some(reloaded) => lost_field(value, reloaded, path),
+                    None => Some(path),
+                }
+            })
+        }
+        (Value::Array(patched), Value::Array(reloaded)) => {
+            if patched.len() != reloaded.len() {
+                return Some(path);
+            }
+            patched
+                .iter()
+                .zip(reloaded)
+                .enumerate()
+                .find_map(|(idx, (a, b))| lost_field(a, b, format!("{}/{}", path, idx)))
+        }
+        _ => None,
+    }
+}
+
+fn get_mut<'a>(json: &'a mut Value, path: &str) -> Result<&'a mut Value> {
+    json.pointer_mut(path).with_context(|| format!("`{}` doesn't exist", path))
+}
+
+// `/player/powers/2` -> (`/player/powers`, `2`)
+fn split_pointer(path: &str) -> Result<(&str, String)> {
+    path.rsplit_once('/')
+        .map(|(parent, key)| (parent, unescape(key)))
+        .with_context(|| format!("`{}` isn't a JSON pointer", path))
+}
+
+// Inséré dans un tableau, créé ou remplacé dans un objet
+fn add(json: &mut Value, path: &str, value: Value) -> Result<()> {
+    let (parent, key) = split_pointer(path)?;
+    match get_mut(json, parent)? {
+        Value::Array(array) => {
+            let len = array.len();
+            let idx = match key.as_str() {
+                "-" => len,
+                _ => key
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|idx| *idx <= len)
+                    .with_context(|| format!("`{}` is out of range", path))?,
+            };
+            if let Some(first) = array.first() {
+                check_type(first, &value)?;
+            }
+            array.insert(idx, value);
+        }
+        Value::Object(map) => {
+            if let Some(field) = map.get(&key) {
+                check_type(field, &value)?;
+            }
+            map.insert(key, value);
+        }
+        _ => bail!("`{}` isn't an array or an object", parent),
+    }
+    Ok(())
+}
+
+fn remove(json: &mut Value, path: &str) -> Result<Value> {
+    let (parent, key) = split_pointer(path)?;
+    let removed = match json.pointer_mut(parent) {
+        Some(Value::Array(array)) => {
+            let len = array.len();
+            key.parse::<usize>().ok().filter(|idx| *idx < len).map(|idx| array.remove(idx))
+        }
+        Some(Value::Object(map)) => map.remove(&key),
+        _ => None,
+    };
+    removed.with_context(|| format!("`{}` doesn't exist", path))
+}
+
+// Le reste est vérifié en relisant la sauvegarde, mais un entier ne doit pas devenir
+// un flottant ou une chaîne
+fn check_type(old: &Value, new: &Value) -> Result<()> {
+    let same = match (old, new) {
+        (Value::Null, _) => true,
+        (Value::Number(old), Value::Number(new)) => old.is_f64() || !new.is_f64(),
+        _ => mem::discriminant(old) == mem::discriminant(new),
+    };
+    if !same {
+        bail!("Expected {}, got {}", type_name(old), type_name(new));
+    }
+    Ok(())
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(number) if number.is_f64() => "a float",
+        Value::Number(_) => "an integer",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use super::*;
+
+    fn edit(save_game: &mut SaveGame) {
+        if let SaveGame::MassEffect2 { save_game, .. } = save_game {
+            save_game.player.level = 30;
+            save_game.player.credits += 5000;
+            let value = save_game.plot.bool_variables[183];
+            save_game.plot.bool_variables.set(183, !value);
+            save_game.plot.int_variables[10] += 3;
+        }
+    }
+
+    #[test]
+    fn apply_patch() -> Result<()> {
+        let input = fs::read("test/ME2Save.pcsav")?;
+        let save_a = SaveGame::load(String::new(), &input)?;
+        let mut save_b = SaveGame::load(String::new(), &input)?;
+        edit(&mut save_b);
+
+        let patch = SavePatch::between(&save_a, &save_b)?;
+        assert_eq!(patch.fields.len(), 4);
+        assert!(patch
+            .fields
+            .iter()
+            .any(|field| field.path == "plot.bool_variables[183]" && field.value.is_boolean()));
+
+        // Le patch relu depuis le JSON donne les valeurs de B
+        let patch = SavePatch::from_json(&patch.to_json()?)?;
+        let mut save_game = SaveGame::load(String::new(), &input)?;
+        patch.apply(&mut save_game)?;
+        assert_eq!(save_game.to_json_value()?, save_b.to_json_value()?);
+
+        // Appliqué deux fois, rien ne bascule de nouveau
+        patch.apply(&mut save_game)?;
+        assert_eq!(save_game.to_json_value()?, save_b.to_json_value()?);
+
+        // Pas le même jeu
+        let input = fs::read("test/ME3Save.pcsav")?;
+        let mut me3_save_game = SaveGame::load(String::new(), &input)?;
+        assert!(patch.apply(&mut me3_save_game).is_err());
+        assert!(SavePatch::between(&save_a, &me3_save_game).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn apply_json_patch() -> Result<()> {
+        let input = fs::read("test/ME2Save.pcsav")?;
+        let mut save_game = SaveGame::load(String::new(), &input)?;
+        let json = save_game.to_json_value()?;
+        let level = json["player"]["level"].clone();
+        let word = json["plot"]["bool_variables"][183 / 32].as_u64().unwrap();
+
+        // Niveau et plot `Garrus is loyal`
+        let patch = JsonPatch::from_json(&format!(
+            r#"[
+                {{ "op": "test", "path": "/player/level", "value": {} }},
+                {{ "op": "replace", "path": "/player/level", "value": 30 }},
+                {{ "op": "replace", "path": "/plot/bool_variables/5", "value": {} }}
+            ]"#,
+            level,
+            word | (1 << (183 % 32))
+        ))?;
+        patch.apply(&mut save_game)?;
+
+        let save_game = SaveGame::load(String::new(), &save_game.to_bytes()?)?;
+        match &save_game {
+            SaveGame::MassEffect2 { save_game, .. } => {
+                assert_eq!(save_game.player.level, 30);
+                assert!(save_game.plot.bool_variables[183]);
+            }
+            _ => panic!("not a Mass Effect 2 save"),
+        }
+
+        // Rien n'est appliqué si une opération échoue
+        let original = save_game.to_json_value()?;
+        let failing = [
+            r#"[{ "op": "replace", "path": "/player/level", "value": 1 },
+                { "op": "replace", "path": "/player/nope", "value": 1 }]"#,
+            r#"[{ "op": "replace", "path": "/player/level", "value": "30" }]"#,
+            r#"[{ "op": "replace", "path": "/player/level", "value": 1.5 }]"#,
+            r#"[{ "op": "test", "path": "/player/level", "value": 1 }]"#,
+            r#"[{ "op": "add", "path": "/player/powers/9999", "value": {} }]"#,
+            r#"[{ "op": "remove", "path": "/player/level" }]"#,
+            r#"[{ "op": "add", "path": "/player/nope", "value": 1 }]"#,
+        ];
+        for patch in &failing {
+            let mut save_game = save_game.clone();
+            assert!(JsonPatch::from_json(patch)?.apply(&mut save_game).is_err(), "{}", patch);
+            assert_eq!(save_game.to_json_value()?, original);
+        }
+        assert!(JsonPatch::from_json(r#"[{ "op": "flip", "path": "/player/level" }]"#).is_err());
+
+        // Champs de taille fixe de ME2 LE et ME3
+        let fixed_size = [
+            ("test/ME2LegSave.pcsav", "/_unknown"),
+            ("test/ME3Save.pcsav", "/player/_character_guid"),
+        ];
+        for (file, path) in &fixed_size {
+            let mut save_game = SaveGame::load(String::new(), &fs::read(file)?)?;
+            let original = save_game.to_json_value()?;
+            for op in &[
+                format!(r#"[{{ "op": "add", "path": "{}/-", "value": 0 }}]"#, path),
+                format!(r#"[{{ "op": "remove", "path": "{}/0" }}]"#, path),
+            ] {
+                assert!(JsonPatch::from_json(op)?.apply(&mut save_game).is_err(), "{}", op);
+                assert_eq!(save_game.to_json_value()?, original);
+            }
+        }
+
+        // Une clé nouvelle reste possible dans une map, ex. un int de plot de ME3
+        let mut save_game = SaveGame::load(String::new(), &fs::read("test/ME3Save.pcsav")?)?;
+        let patch = r#"[{ "op": "add", "path": "/plot/int_variables/999999", "value": 7 }]"#;
+        JsonPatch::from_json(patch)?.apply(&mut save_game)?;
+        assert_eq!(save_game.to_json_value()?["plot"]["int_variables"]["999999"], 7);
+        Ok(())
+    }
+}
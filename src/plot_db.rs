@@ -0,0 +1,234 @@
+use anyhow::{anyhow, Context, Result};
+use lazy_static::lazy_static;
+use serde::{de::DeserializeOwned, Deserialize};
+use std::{
+    fs,
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    event_handler::GameVersion,
+    save_data::{
+        mass_effect_1::known_plot::Me1KnownPlot,
+        mass_effect_2::known_plot::Me2KnownPlot,
+        mass_effect_3::known_plot::Me3KnownPlot,
+        shared::plot::{PlotId, PlotNames},
+    },
+};
+
+lazy_static! {
+    static ref PLOT_DB: PlotDbCache = PlotDbCache::default();
+}
+
+// Base de plots d'un jeu, partagée entre l'UI et la ligne de commande
+#[derive(Clone)]
+pub enum PlotDb {
+    Me1(Arc<Me1KnownPlot>),
+    Me2(Arc<Me2KnownPlot>),
+    Me3(Arc<Me3KnownPlot>),
+}
+
+impl PlotDb {
+    // Copie traduite pour l'UI, la base en cache reste en anglais pour la ligne de commande
+    pub fn localized(&self, locale: &PlotLocale) -> PlotDb {
+        match self {
+            PlotDb::Me1(me1_known_plot) => {
+                let mut me1_known_plot = Me1KnownPlot::clone(me1_known_plot);
+                me1_known_plot.localize(&locale.me1);
+                PlotDb::Me1(Arc::new(me1_known_plot))
+            }
+            PlotDb::Me2(me2_known_plot) => {
+                let mut me2_known_plot = Me2KnownPlot::clone(me2_known_plot);
+                me2_known_plot.localize(&locale.me2);
+                PlotDb::Me2(Arc::new(me2_known_plot))
+            }
+            PlotDb::Me3(me3_known_plot) => {
+                let mut me3_known_plot = Me3KnownPlot::clone(me3_known_plot);
+                me3_known_plot.localize(&locale.me3);
+                PlotDb::Me3(Arc::new(me3_known_plot))
+            }
+        }
+    }
+
+    // Description de chaque plot connu, par id
+    pub fn names(&self) -> PlotNames {
+        match self {
+            PlotDb::Me1(me1_known_plot) => me1_known_plot.names(),
+            PlotDb::Me2(me2_known_plot) => me2_known_plot.names(),
+            PlotDb::Me3(me3_known_plot) => me3_known_plot.names(),
+        }
+    }
+}
+
+// Langues proposées en plus de l'anglais, `(code, nom)`
+pub const LOCALES: &[(&str, &str)] = &[("fr", "Français"), ("de", "Deutsch")];
+
+// Traductions d'une langue, seules les descriptions changent
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct PlotLocale {
+    pub me1: PlotNames,
+    pub me2: PlotNames,
+    pub me3: PlotNames,
+}
+
+impl PlotLocale {
+    pub fn load(locale: &str) -> Result<Self> {
+        let path = format!("plot/locales/{}.ron", locale);
+        let input =
+            fs::read_to_string(&path).with_context(|| format!("Failed to open {}", path))?;
+        ron::from_str(&input).with_context(|| format!("Failed to parse {}", path))
+    }
+}
+
+// Les LE partagent la base du jeu d'origine
+pub fn for_game(game: GameVersion) -> Result<PlotDb> {
+    PLOT_DB.for_game(game)
+}
+
+// Recherche insensible à la casse dans la base du jeu
+pub fn find_plot(game: GameVersion, name: &str) -> Result<PlotId> {
+    let plot_id = match for_game(game)? {
+        PlotDb::Me1(me1_known_plot) => me1_known_plot.find(name)?,
+        PlotDb::Me2(me2_known_plot) => me2_known_plot.find(name)?,
+        PlotDb::Me3(me3_known_plot) => me3_known_plot.find(name)?,
+    };
+    plot_id.ok_or_else(|| anyhow!("Unknown plot `{}` for {:?}", name, game))
+}
+
+// Bases nécessaires pour afficher un jeu, ME2 et ME3 affichent aussi les plots importés
+pub fn dependencies(game: GameVersion) -> &'static [GameVersion] {
+    match game {
+        GameVersion::MassEffect1 | GameVersion::MassEffect1Leg => &[GameVersion::MassEffect1],
+        GameVersion::MassEffect2 | GameVersion::MassEffect2Leg => {
+            &[GameVersion::MassEffect2, GameVersion::MassEffect1]
+        }
+        GameVersion::MassEffect3 => &[GameVersion::MassEffect3, GameVersion::MassEffect2],
+    }
+}
+
+// Chaque base n'est lue qu'au premier accès
+#[derive(Default)]
+pub struct PlotDbCache {
+    me1: Mutex<Option<Arc<Me1KnownPlot>>>,
+    me2: Mutex<Option<Arc<Me2KnownPlot>>>,
+    me3: Mutex<Option<Arc<Me3KnownPlot>>>,
+}
+
+impl PlotDbCache {
+    pub fn for_game(&self, game: GameVersion) -> Result<PlotDb> {
+        let plot_db = match game {
+            GameVersion::MassEffect1 | GameVersion::MassEffect1Leg => {
+                PlotDb::Me1(load_cached(&self.me1, "plot/me1_known_plot.ron")?)
+            }
+            GameVersion::MassEffect2 | GameVersion::MassEffect2Leg => {
+                PlotDb::Me2(load_cached(&self.me2, "plot/me2_known_plot.ron")?)
+            }
+            GameVersion::MassEffect3 => {
+                PlotDb::Me3(load_cached(&self.me3, "plot/me3_known_plot.ron")?)
+            }
+        };
+        Ok(plot_db)
+    }
+
+    pub fn is_loaded(&self, game: GameVersion) -> bool {
+        match game {
+            GameVersion::MassEffect1 | GameVersion::MassEffect1Leg => {
+                self.me1.lock().unwrap().is_some()
+            }
+            GameVersion::MassEffect2 | GameVersion::MassEffect2Leg => {
+                self.me2.lock().unwrap().is_some()
+            }
+            GameVersion::MassEffect3 => self.me3.lock().unwrap().is_some(),
+        }
+    }
+}
+
+fn load_cached<T: DeserializeOwned>(cache: &Mutex<Option<Arc<T>>>, path: &str) -> Result<Arc<T>> {
+    let mut cache = cache.lock().unwrap();
+    if let Some(plot_db) = cache.as_ref() {
+        return Ok(Arc::clone(plot_db));
+    }
+
+    let input = fs::read_to_string(path).with_context(|| format!("Failed to open {}", path))?;
+    let plot_db: Arc<T> =
+        Arc::new(ron::from_str(&input).with_context(|| format!("Failed to parse {}", path))?);
+    *cache = Some(Arc::clone(&plot_db));
+    Ok(plot_db)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn lazy_loading() -> Result<()> {
+        let cache = PlotDbCache::default();
+
+        cache.for_game(GameVersion::MassEffect1Leg)?;
+        assert!(cache.is_loaded(GameVersion::MassEffect1));
+        assert!(!cache.is_loaded(GameVersion::MassEffect2));
+        assert!(!cache.is_loaded(GameVersion::MassEffect3));
+
+        // Même instance au deuxième accès
+        let (first, second) = match (
+            cache.for_game(GameVersion::MassEffect1)?,
+            cache.for_game(GameVersion::MassEffect1)?,
+        ) {
+            (PlotDb::Me1(first), PlotDb::Me1(second)) => (first, second),
+            _ => panic!("not the Mass Effect 1 plot db"),
+        };
+        assert!(Arc::ptr_eq(&first, &second));
+
+        cache.for_game(GameVersion::MassEffect3)?;
+        assert!(cache.is_loaded(GameVersion::MassEffect3));
+        assert!(!cache.is_loaded(GameVersion::MassEffect2));
+        Ok(())
+    }
+
+    #[test]
+    fn locale_fallback() -> Result<()> {
+        let locale: PlotLocale = ron::from_str(
+            r#"(
+                me2: (
+                    booleans: { 183: "Garrus est loyal" },
+                ),
+            )"#,
+        )?;
+
+        let me2_known_plot = match for_game(GameVersion::MassEffect2)?.localized(&locale) {
+            PlotDb::Me2(me2_known_plot) => me2_known_plot,
+            _ => panic!("not the Mass Effect 2 plot db"),
+        };
+        let garrus = &me2_known_plot.crew["Garrus"];
+        assert_eq!(garrus.booleans[&183], "Garrus est loyal");
+        // Pas de traduction, on garde l'anglais
+        assert_eq!(garrus.booleans[&38], "Garrus recruited");
+
+        // La base en cache n'est pas modifiée
+        assert_eq!(find_plot(GameVersion::MassEffect2, "Garrus is loyal")?, PlotId::Boolean(183));
+
+        // Les fichiers fournis sont valides
+        for (locale, _) in LOCALES {
+            PlotLocale::load(locale)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn ambiguous_plot() -> Result<()> {
+        // Un nom par amélioration de recherche, aucun ne l'emporte
+        let err = find_plot(GameVersion::MassEffect2, "Damage discovered").unwrap_err();
+        assert!(err.to_string().contains("Ambiguous plot"));
+
+        // Plots importés de ME1, dans la table de ME2
+        let me2_known_plot = match for_game(GameVersion::MassEffect2)? {
+            PlotDb::Me2(me2_known_plot) => me2_known_plot,
+            _ => panic!("not the Mass Effect 2 plot db"),
+        };
+        let (_, player) = me2_known_plot.imported_me1.get_index(0).unwrap();
+        let (plot_id, desc) = player.booleans.get_index(0).unwrap();
+        assert_eq!(find_plot(GameVersion::MassEffect2, desc)?, PlotId::Boolean(*plot_id));
+        Ok(())
+    }
+}
@@ -0,0 +1,61 @@
+use anyhow::{Context, Result};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+use crate::{
+    event_handler::{GameVersion, SaveGame},
+    plot_db,
+};
+
+// Plots à appliquer par nom, les valeurs sont celles de `tse set`
+#[derive(Deserialize, Serialize, Clone, PartialEq, Debug)]
+pub struct Preset {
+    pub game: GameVersion,
+    pub plots: IndexMap<String, String>,
+}
+
+impl Preset {
+    pub fn load(path: &Path) -> Result<Self> {
+        let input = fs::read_to_string(path)
+            .with_context(|| format!("Failed to open `{}`", path.display()))?;
+        ron::from_str(&input).with_context(|| format!("Failed to parse `{}`", path.display()))
+    }
+
+    // Une LE accepte le preset du jeu d'origine, la base de plots est la même
+    pub fn is_compatible(&self, game: GameVersion) -> bool {
+        plot_db::dependencies(self.game)[0] == plot_db::dependencies(game)[0]
+    }
+
+    pub fn apply(&self, save_game: &mut SaveGame) -> Result<()> {
+        for (name, value) in &self.plots {
+            let plot_id = plot_db::find_plot(save_game.game_version(), name)?;
+            save_game.set_plot(plot_id, value).with_context(|| format!("Plot `{}`", name))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn compatibility() -> Result<()> {
+        let preset: Preset = ron::from_str(
+            r#"Preset(
+                game: MassEffect2,
+                plots: {
+                    "Garrus is loyal": "true",
+                },
+            )"#,
+        )?;
+        assert_eq!(preset.plots.len(), 1);
+
+        assert!(preset.is_compatible(GameVersion::MassEffect2));
+        assert!(preset.is_compatible(GameVersion::MassEffect2Leg));
+        assert!(!preset.is_compatible(GameVersion::MassEffect1));
+        assert!(!preset.is_compatible(GameVersion::MassEffect3));
+        Ok(())
+    }
+}
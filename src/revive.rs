@@ -0,0 +1,192 @@
+use anyhow::{bail, Context, Result};
+use indexmap::IndexMap;
+
+use crate::{
+    event_handler::{GameVersion, SaveGame},
+    plot_db,
+    preset::Preset,
+};
+
+// Les dialogues du jeu lisent plusieurs plots, les changer un par un laisse le compagnon
+// à moitié mort
+const ME1_WREX: [(&str, &str); 5] = [
+    ("Wrex recruited", "true"),
+    ("Wrex is dead", "false"),
+    ("Wrex killed by Shepard", "false"),
+    ("Wrex killed by Ashley on Shepard's orders", "false"),
+    ("Wrex killed by Ashley, without permission", "false"),
+];
+
+const ME2_SQUAD: [&str; 12] = [
+    "Garrus", "Grunt", "Jack", "Jacob", "Kasumi", "Legion", "Miranda", "Mordin", "Samara", "Tali",
+    "Thane", "Zaeed",
+];
+
+// Équipage du Normandy enlevé par les Récolteurs
+const ME2_NORMANDY_CREW: [&str; 5] = [
+    "Kelly Chambers",
+    "Dr. Karin Chakwas",
+    "Kenneth Donnelly",
+    "Gabriella Daniels",
+    "Mess Sgt. Rupert Gardner",
+];
+
+pub fn companions(game: GameVersion) -> &'static [&'static str] {
+    match plot_db::dependencies(game)[0] {
+        GameVersion::MassEffect1 => &["Wrex"],
+        GameVersion::MassEffect2 => &ME2_SQUAD,
+        _ => &[],
+    }
+}
+
+// Plots à appliquer, dans le format des presets
+pub fn revival(game: GameVersion, companion: &str) -> Result<Preset> {
+    let base_game = plot_db::dependencies(game)[0];
+    let plots: IndexMap<String, String> = match base_game {
+        GameVersion::MassEffect1 if companion == "Wrex" => {
+            ME1_WREX.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect()
+        }
+        GameVersion::MassEffect2 if ME2_SQUAD.contains(&companion) => [
+            (format!("{} recruited", companion), "true"),
+            (format!("{} died in Suicide Mission", companion), "false"),
+            (format!("{} is selectable", companion), "true"),
+        ]
+        .iter()
+        .map(|(name, value)| (name.clone(), value.to_string()))
+        .collect(),
+        _ => bail!("{} can't be revived in {:?}", companion, game),
+    };
+    Ok(Preset { game: base_game, plots })
+}
+
+// Tout ou rien, la sauvegarde n'est pas touchée si un plot manque
+pub fn revive(save_game: &mut SaveGame, companion: &str) -> Result<()> {
+    let revival = revival(save_game.game_version(), companion)?;
+
+    let mut revived = save_game.clone();
+    revival.apply(&mut revived).with_context(|| format!("Failed to revive {}", companion))?;
+    *save_game = revived;
+    Ok(())
+}
+
+// Issue de la Suicide Mission reprise par ME3, l'équipe recrutée puis l'équipage : (nom, vivant)
+pub fn survivors(save_game: &SaveGame) -> Result<Vec<(&'static str, bool)>> {
+    let mut squad = Vec::new();
+    for name in &ME2_SQUAD {
+        if save_game.get_plot_bool(&format!("{} recruited", name))? {
+            squad.push((*name, format!("{} died in Suicide Mission", name)));
+        }
+    }
+    let crew = ME2_NORMANDY_CREW.iter().map(|name| (*name, format!("{} died", name)));
+
+    squad
+        .into_iter()
+        .chain(crew)
+        .map(|(name, died)| Ok((name, !save_game.get_plot_bool(&died)?)))
+        .collect()
+}
+
+// Tout ou rien, les plots liés suivent : le squadmate mort n'est plus sélectionnable, le
+// bilan de l'équipage est recalculé
+pub fn set_survival(save_game: &mut SaveGame, name: &str, alive: bool) -> Result<()> {
+    let mut edited = save_game.clone();
+
+    if ME2_SQUAD.contains(&name) {
+        if alive {
+            return revive(save_game, name);
+        }
+        if !edited.get_plot_bool(&format!("{} recruited", name))? {
+            bail!("{} was never recruited", name);
+        }
+        edited.set_plot_bool(&format!("{} died in Suicide Mission", name), true)?;
+        edited.set_plot_bool(&format!("{} is selectable", name), false)?;
+        edited.set_plot_bool(&format!("{} is in Squad", name), false)?;
+    } else if ME2_NORMANDY_CREW.contains(&name) {
+        edited.set_plot_bool(&format!("{} died", name), !alive)?;
+
+        let mut casualties = false;
+        for name in &ME2_NORMANDY_CREW {
+            casualties |= edited.get_plot_bool(&format!("{} died", name))?;
+        }
+        edited.set_plot_bool("All Crew Survived", !casualties)?;
+        edited.set_plot_bool("Had casualties", casualties)?;
+    } else {
+        bail!("{} isn't part of the Suicide Mission", name);
+    }
+
+    *save_game = edited;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn revive_cluster() -> Result<()> {
+        let input = fs::read("test/ME2Save.pcsav")?;
+        let mut save_game = SaveGame::load(String::new(), &input)?;
+
+        // Mort au lieu d'être seulement absent de l'équipe
+        save_game.set_plot_bool("Garrus died in Suicide Mission", true)?;
+        save_game.set_plot_bool("Garrus is selectable", false)?;
+
+        revive(&mut save_game, "Garrus")?;
+        assert!(save_game.get_plot_bool("Garrus recruited")?);
+        assert!(!save_game.get_plot_bool("Garrus died in Suicide Mission")?);
+        assert!(save_game.get_plot_bool("Garrus is selectable")?);
+
+        // Chaque compagnon a tous ses plots dans la base
+        for game in &[GameVersion::MassEffect1, GameVersion::MassEffect2] {
+            for companion in companions(*game) {
+                let revival = revival(*game, companion)?;
+                for name in revival.plots.keys() {
+                    plot_db::find_plot(*game, name)?;
+                }
+            }
+        }
+
+        let err = revive(&mut save_game, "Wrex").unwrap_err();
+        assert_eq!(err.to_string(), "Wrex can't be revived in MassEffect2");
+        Ok(())
+    }
+
+    #[test]
+    fn suicide_mission_survival() -> Result<()> {
+        let input = fs::read("test/ME2Save.pcsav")?;
+        let mut save_game = SaveGame::load(String::new(), &input)?;
+        save_game.set_plot_bool("Garrus recruited", true)?;
+
+        // Un squadmate jamais recruté n'est ni mort ni vivant
+        save_game.set_plot_bool("Zaeed recruited", false)?;
+        assert!(survivors(&save_game)?.iter().all(|(name, _)| *name != "Zaeed"));
+
+        set_survival(&mut save_game, "Garrus", false)?;
+        set_survival(&mut save_game, "Kelly Chambers", false)?;
+        let mut save_game = SaveGame::load(String::new(), &save_game.to_bytes()?)?;
+
+        let survivors = survivors(&save_game)?;
+        assert!(survivors.contains(&("Garrus", false)));
+        assert!(survivors.contains(&("Kelly Chambers", false)));
+        assert!(!save_game.get_plot_bool("Garrus is selectable")?);
+        assert!(!save_game.get_plot_bool("Garrus is in Squad")?);
+        assert!(!save_game.get_plot_bool("All Crew Survived")?);
+        assert!(save_game.get_plot_bool("Had casualties")?);
+
+        // Retour en arrière, la paire mort / vivant reste cohérente
+        set_survival(&mut save_game, "Garrus", true)?;
+        for name in &ME2_NORMANDY_CREW {
+            set_survival(&mut save_game, name, true)?;
+        }
+        let mut save_game = SaveGame::load(String::new(), &save_game.to_bytes()?)?;
+        assert!(survivors(&save_game)?.iter().all(|(_, alive)| *alive));
+        assert!(save_game.get_plot_bool("Garrus is selectable")?);
+        assert!(save_game.get_plot_bool("All Crew Survived")?);
+        assert!(!save_game.get_plot_bool("Had casualties")?);
+
+        assert!(set_survival(&mut save_game, "Wrex", false).is_err());
+        Ok(())
+    }
+}
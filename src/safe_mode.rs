@@ -0,0 +1,72 @@
+use anyhow::{bail, Result};
+
+use crate::{event_handler::SaveGame, save_data::build::Build};
+
+// Modifications connues pour casser une sauvegarde, refusées tant que le mode sans échec
+// est actif. Toute nouvelle opération risquée s'ajoute ici
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Guarded {
+    ClassChange,
+    Relocation,
+    OpaqueMemberReplace,
+}
+
+impl Guarded {
+    pub fn reason(self) -> &'static str {
+        match self {
+            Guarded::ClassChange => {
+                "Changing the class leaves the talents of the old class, the game may crash on load."
+            }
+            Guarded::Relocation => {
+                "Relocating doesn't move the player nor update the quests, you may load out of bounds."
+            }
+            Guarded::OpaqueMemberReplace => {
+                "This archive file isn't understood by the editor, a wrong replacement can't be detected."
+            }
+        }
+    }
+}
+
+pub fn check(safe_mode: bool, edit: Guarded) -> Result<()> {
+    if safe_mode {
+        bail!("{}\nDisable the safe mode in the settings to do it anyway.", edit.reason());
+    }
+    Ok(())
+}
+
+// Une build de la même classe ne change que les pouvoirs
+pub fn check_build(safe_mode: bool, save_game: &SaveGame, build: &Build) -> Result<()> {
+    let same_class = save_game.build().map_or(true, |current| {
+        unicase::eq(current.class_name.as_str(), build.class_name.as_str())
+    });
+    if !same_class {
+        check(safe_mode, Guarded::ClassChange)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn guarded_class_change() -> Result<()> {
+        let input = fs::read("test/ME3Save.pcsav")?;
+        let save_game = SaveGame::load("test/ME3Save.pcsav".to_owned(), &input)?;
+        let mut build = save_game.build().unwrap();
+
+        // Même classe, rien à bloquer
+        check_build(true, &save_game, &build)?;
+
+        build.class_name = "SFXGame.SFXPawn_PlayerVanguard".to_owned();
+        let err = check_build(true, &save_game, &build).err().expect("blocked in safe mode");
+        assert!(err.to_string().starts_with(Guarded::ClassChange.reason()));
+        check_build(false, &save_game, &build)?;
+
+        assert!(check(true, Guarded::Relocation).is_err());
+        assert!(check(false, Guarded::Relocation).is_ok());
+        Ok(())
+    }
+}
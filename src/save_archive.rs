@@ -0,0 +1,190 @@
+use anyhow::{Context, Result};
+use std::{
+    error, fmt,
+    io::{self, Cursor, ErrorKind, Read},
+    path::Path,
+};
+use zip::ZipArchive;
+
+use crate::event_handler::{self, GameVersion};
+
+// Une sauvegarde dans une archive : `Backup.zip/ME2/Save_0001.pcsav`
+const SEPARATOR: &str = ".zip/";
+
+pub fn is_archive(path: &Path) -> bool {
+    path.extension().map(|ext| unicase::eq(ext.to_string_lossy().as_ref(), "zip")).unwrap_or(false)
+}
+
+pub fn join_path(archive_path: &str, member: &str) -> String {
+    format!("{}/{}", archive_path, member)
+}
+
+// (archive, fichier dans l'archive), un dossier `Saves.zip/` au-dessus de l'archive reste dans
+// son chemin
+pub fn split_path(path: &str) -> Option<(&str, &str)> {
+    let idx = path.to_ascii_lowercase().rfind(SEPARATOR)?;
+    let end = idx + SEPARATOR.len();
+    Some((&path[..end - 1], &path[end..]))
+}
+
+// À côté de l'archive, pour que l'enregistrement écrive un vrai fichier
+pub fn extracted_path(archive_path: &str, member: &str) -> String {
+    let file_name = Path::new(member).file_name().unwrap_or_default();
+    Path::new(archive_path).with_file_name(file_name).to_string_lossy().into_owned()
+}
+
+// Les fichiers sont reconnus par leur en-tête, pas par leur extension
+pub fn list_saves(input: &[u8]) -> Result<Vec<(String, Result<GameVersion, String>)>> {
+    let mut zip = ZipArchive::new(Cursor::new(input)).context("Invalid zip archive")?;
+
+    let mut saves = Vec::new();
+    for i in 0..zip.len() {
+        let mut file = zip.by_index(i)?;
+        if file.is_dir() {
+            continue;
+        }
+        let name = file.name().to_owned();
+
+        let bytes = read_zip_member(&mut file, &name)?;
+        if event_handler::detect_content(&bytes).is_some() {
+            let integrity = event_handler::quick_check(Path::new(&name), &bytes)
+                .map_err(|err| format!("{:#}", err));
+            saves.push((name, integrity));
+        }
+    }
+    saves.sort_by(|(a, _), (b, _)| a.cmp(b));
+    Ok(saves)
+}
+
+pub fn read_member(input: &[u8], member: &str) -> Result<Vec<u8>> {
+    let mut zip = ZipArchive::new(Cursor::new(input)).context("Invalid zip archive")?;
+
+    let file =
+        zip.by_name(member).with_context(|| format!("Missing `{}` in zip archive", member))?;
+    read_zip_member(file, member)
+}
+
+// Flux deflate coupé ou corrompu, `offset` est le nombre d'octets décompressés avant l'erreur
+#[derive(Debug)]
+pub struct TruncatedMember {
+    pub member: String,
+    pub offset: usize,
+    source: io::Error,
+}
+
+impl fmt::Display for TruncatedMember {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "`{}` is damaged after {} bytes: {}", self.member, self.offset, self.source)
+    }
+}
+
+impl error::Error for TruncatedMember {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+// `read_to_end` sans perdre la position de l'erreur
+pub fn read_zip_member<R: Read>(mut file: R, member: &str) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    let mut buf = [0; 8192];
+    loop {
+        match file.read(&mut buf) {
+            Ok(0) => return Ok(bytes),
+            Ok(len) => bytes.extend_from_slice(&buf[..len]),
+            Err(err) if err.kind() == ErrorKind::Interrupted => (),
+            Err(source) => {
+                let offset = bytes.len();
+                return Err(TruncatedMember { member: member.to_owned(), offset, source }.into());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fs, io::Write};
+    use zip::{write::FileOptions, ZipWriter};
+
+    use crate::event_handler::SaveGame;
+
+    use super::*;
+
+    #[test]
+    fn open_zip_with_two_saves() -> Result<()> {
+        let dir = std::env::temp_dir().join("trilogy_save_editor_save_archive");
+        if dir.exists() {
+            fs::remove_dir_all(&dir)?;
+        }
+        fs::create_dir_all(&dir)?;
+
+        let archive_path = dir.join("Backup.zip");
+        {
+            let mut zipper = ZipWriter::new(fs::File::create(&archive_path)?);
+            let options = FileOptions::default();
+            zipper.start_file("ME2/Save_0001.pcsav", options)?;
+            zipper.write_all(&fs::read("test/ME2Save.pcsav")?)?;
+            zipper.start_file("ME3/Save_0002.pcsav", options)?;
+            zipper.write_all(&fs::read("test/ME3Save.pcsav")?)?;
+            zipper.start_file("readme.txt", options)?;
+            zipper.write_all(b"not a save")?;
+            zipper.finish()?;
+        }
+        let archive_path = archive_path.to_string_lossy().into_owned();
+
+        let archive = fs::read(&archive_path)?;
+        let saves = list_saves(&archive)?;
+        assert_eq!(
+            saves,
+            vec![
+                ("ME2/Save_0001.pcsav".to_owned(), Ok(GameVersion::MassEffect2)),
+                ("ME3/Save_0002.pcsav".to_owned(), Ok(GameVersion::MassEffect3)),
+            ]
+        );
+
+        // Chargement par le chemin habituel
+        let path = join_path(&archive_path, "ME3/Save_0002.pcsav");
+        assert_eq!(split_path(&path), Some((archive_path.as_str(), "ME3/Save_0002.pcsav")));
+        assert_eq!(
+            split_path("Old.zip/Backup.ZIP/ME2/Save_0001.pcsav"),
+            Some(("Old.zip/Backup.ZIP", "ME2/Save_0001.pcsav"))
+        );
+
+        let (archive_path, member) = split_path(&path).unwrap();
+        let save_game =
+            SaveGame::load(extracted_path(archive_path, member), &read_member(&archive, member)?)?;
+        assert_eq!(save_game.game_version(), GameVersion::MassEffect3);
+        assert_eq!(save_game.file_path(), dir.join("Save_0002.pcsav").to_string_lossy());
+        assert_eq!(save_game.to_bytes()?, fs::read("test/ME3Save.pcsav")?);
+
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn damaged_member() -> Result<()> {
+        let mut archive = Vec::new();
+        {
+            let mut zipper = ZipWriter::new(Cursor::new(&mut archive));
+            zipper.start_file("ME2/Save_0001.pcsav", FileOptions::default())?;
+            zipper.write_all(&fs::read("test/ME2Save.pcsav")?)?;
+            zipper.finish()?;
+        }
+
+        // Le répertoire central reste lisible, seul le flux deflate est abîmé
+        let start = 30 + "ME2/Save_0001.pcsav".len();
+        for byte in &mut archive[start + 100..start + 200] {
+            *byte = !*byte;
+        }
+
+        let err = read_member(&archive, "ME2/Save_0001.pcsav").unwrap_err();
+        let truncated = err.downcast_ref::<TruncatedMember>().expect("a damaged member");
+        assert_eq!(truncated.member, "ME2/Save_0001.pcsav");
+        assert!(err.to_string().starts_with("`ME2/Save_0001.pcsav` is damaged after "));
+
+        let hint = event_handler::error_hint(&err).expect("a recovery hint");
+        assert!(hint.contains("`.bak`"));
+        assert!(list_saves(&archive).unwrap_err().is::<TruncatedMember>());
+        Ok(())
+    }
+}
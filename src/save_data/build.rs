@@ -0,0 +1,274 @@
+use anyhow::{anyhow, bail, Context, Result};
+use imgui::ImString;
+use std::{fmt, str::FromStr};
+
+use super::{
+    mass_effect_2::player::Player as Me2Player, mass_effect_3::player::Player as Me3Player,
+};
+
+// `TSE<version>;<jeu>;<classe>;<niveau>;<points de talent>;<pouvoir>;...`
+// Une nouvelle version peut ajouter des champs, les anciens codes restent lisibles
+const HEADER: &str = "TSE";
+const VERSION: u32 = 1;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BuildGame {
+    // ME2 et ME2 LE partagent le même joueur
+    MassEffect2,
+    MassEffect3,
+}
+
+impl BuildGame {
+    fn tag(self) -> &'static str {
+        match self {
+            BuildGame::MassEffect2 => "ME2",
+            BuildGame::MassEffect3 => "ME3",
+        }
+    }
+}
+
+// Classe, niveau et répartition des talents, le reste du personnage n'est pas partagé
+#[derive(Clone, PartialEq, Debug)]
+pub struct Build {
+    pub game: BuildGame,
+    pub class_name: String,
+    pub level: i32,
+    pub talent_points: i32,
+    pub powers: Vec<BuildPower>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct BuildPower {
+    pub name: String,
+    pub class_name: String,
+    pub rank: f32,
+    // ME3 seulement, toujours à 0 pour ME2
+    pub evolved_choices: [i32; 6],
+}
+
+impl Build {
+    pub fn me2(player: &Me2Player) -> Self {
+        let powers = player
+            .powers
+            .iter()
+            .map(|power| BuildPower {
+                name: power.name.to_string(),
+                class_name: power.power_class_name.to_string(),
+                rank: power.rank,
+                evolved_choices: [0; 6],
+            })
+            .collect();
+        Build {
+            game: BuildGame::MassEffect2,
+            class_name: player.class_name.to_string(),
+            level: player.level,
+            talent_points: player.talent_points,
+            powers,
+        }
+    }
+
+    pub fn me3(player: &Me3Player) -> Self {
+        let powers = player
+            .powers
+            .iter()
+            .map(|power| BuildPower {
+                name: power.name.to_string(),
+                class_name: power.power_class_name.to_string(),
+                rank: power.rank,
+                evolved_choices: [
+                    power.evolved_choice_0,
+                    power.evolved_choice_1,
+                    power.evolved_choice_2,
+                    power.evolved_choice_3,
+                    power.evolved_choice_4,
+                    power.evolved_choice_5,
+                ],
+            })
+            .collect();
+        Build {
+            game: BuildGame::MassEffect3,
+            class_name: player.class_name.to_string(),
+            level: player.level,
+            talent_points: player.talent_points,
+            powers,
+        }
+    }
+
+    // Les pouvoirs déjà présents gardent leur place dans la roue
+    pub fn apply_me2(&self, player: &mut Me2Player) -> Result<()> {
+        self.check_game(BuildGame::MassEffect2)?;
+        player.class_name = ImString::new(&self.class_name).into();
+        player.level = self.level;
+        player.talent_points = self.talent_points;
+        player.powers = self
+            .powers
+            .iter()
+            .map(|build_power| {
+                let mut power = find_power(&player.powers, &build_power.class_name, |power| {
+                    power.power_class_name.to_str()
+                })
+                .unwrap_or_default();
+                power.name = ImString::new(&build_power.name).into();
+                power.power_class_name = ImString::new(&build_power.class_name).into();
+                power.rank = build_power.rank;
+                power
+            })
+            .collect();
+        Ok(())
+    }
+
+    pub fn apply_me3(&self, player: &mut Me3Player) -> Result<()> {
+        self.check_game(BuildGame::MassEffect3)?;
+        player.class_name = ImString::new(&self.class_name).into();
+        player.level = self.level;
+        player.talent_points = self.talent_points;
+        player.powers = self
+            .powers
+            .iter()
+            .map(|build_power| {
+                let mut power = find_power(&player.powers, &build_power.class_name, |power| {
+                    power.power_class_name.to_str()
+                })
+                .unwrap_or_default();
+                power.name = ImString::new(&build_power.name).into();
+                power.power_class_name = ImString::new(&build_power.class_name).into();
+                power.rank = build_power.rank;
+                let [e0, e1, e2, e3, e4, e5] = build_power.evolved_choices;
+                power.evolved_choice_0 = e0;
+                power.evolved_choice_1 = e1;
+                power.evolved_choice_2 = e2;
+                power.evolved_choice_3 = e3;
+                power.evolved_choice_4 = e4;
+                power.evolved_choice_5 = e5;
+                power
+            })
+            .collect();
+        Ok(())
+    }
+
+    fn check_game(&self, game: BuildGame) -> Result<()> {
+        if self.game != game {
+            bail!("This build is for {}, not {}", self.game.tag(), game.tag());
+        }
+        Ok(())
+    }
+}
+
+// Les pouvoirs des deux jeux n'ont pas les mêmes champs
+fn find_power<T: Clone>(
+    powers: &[T], class_name: &str, power_class_name: impl Fn(&T) -> &str,
+) -> Option<T> {
+    powers.iter().find(|power| unicase::eq(power_class_name(power), class_name)).cloned()
+}
+
+impl fmt::Display for Build {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{};{};{};{};{}",
+            HEADER,
+            VERSION,
+            self.game.tag(),
+            self.class_name,
+            self.level,
+            self.talent_points
+        )?;
+        for power in &self.powers {
+            write!(f, ";{},{},{}", power.name, power.class_name, power.rank)?;
+            if self.game == BuildGame::MassEffect3 {
+                for choice in &power.evolved_choices {
+                    write!(f, ",{}", choice)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Build {
+    type Err = anyhow::Error;
+
+    fn from_str(code: &str) -> Result<Self> {
+        let mut fields = code.trim().split(';');
+
+        let version = fields
+            .next()
+            .and_then(|header| header.strip_prefix(HEADER))
+            .ok_or_else(|| anyhow!("Not a build code"))?;
+        match version.parse::<u32>() {
+            Ok(VERSION) => (),
+            Ok(version) => {
+                bail!("Build code version {} isn't supported, update the editor", version)
+            }
+            Err(_) => bail!("Not a build code"),
+        }
+
+        let mut next = |name: &str| fields.next().ok_or_else(|| anyhow!("Missing {}", name));
+        let game = match next("game")? {
+            "ME2" => BuildGame::MassEffect2,
+            "ME3" => BuildGame::MassEffect3,
+            game => bail!("Unknown game `{}`", game),
+        };
+        let class_name = next("class")?.to_owned();
+        let level = next("level")?.parse().context("Invalid level")?;
+        let talent_points = next("talent points")?.parse().context("Invalid talent points")?;
+
+        let powers = fields.map(|power| parse_power(game, power)).collect::<Result<_>>()?;
+        Ok(Build { game, class_name, level, talent_points, powers })
+    }
+}
+
+fn parse_power(game: BuildGame, power: &str) -> Result<BuildPower> {
+    let context = || format!("Invalid power `{}`", power);
+    let mut fields = power.split(',');
+    let mut next = || fields.next().ok_or_else(|| anyhow!("Missing field")).with_context(context);
+
+    let name = next()?.to_owned();
+    let class_name = next()?.to_owned();
+    let rank = next()?.parse().with_context(context)?;
+    let mut evolved_choices = [0; 6];
+    if game == BuildGame::MassEffect3 {
+        for choice in &mut evolved_choices {
+            *choice = next()?.parse().with_context(context)?;
+        }
+    }
+    if fields.next().is_some() {
+        return Err(anyhow!("Too many fields")).with_context(context);
+    }
+    Ok(BuildPower { name, class_name, rank, evolved_choices })
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use crate::{save_data::mass_effect_3::Me3SaveGame, unreal};
+
+    use super::*;
+
+    #[test]
+    fn build_code() -> Result<()> {
+        let input = fs::read("test/ME3Save.pcsav")?;
+        let me3_save_game: Me3SaveGame = unreal::Deserializer::from_bytes(&input)?;
+        let build = Build::me3(&me3_save_game.player);
+        assert!(!build.powers.is_empty());
+
+        let code = build.to_string();
+        assert!(code.starts_with("TSE1;ME3;"));
+        assert_eq!(code.parse::<Build>()?, build);
+
+        // Sur un personnage sans pouvoirs
+        let mut fresh = me3_save_game.player.clone();
+        fresh.powers.clear();
+        fresh.level = 1;
+        build.apply_me3(&mut fresh)?;
+        assert_eq!(Build::me3(&fresh), build);
+
+        let me2_build = Build { game: BuildGame::MassEffect2, ..build };
+        assert!(me2_build.apply_me3(&mut fresh).is_err());
+
+        assert!("TSE2;ME3;Adept;1;0".parse::<Build>().is_err());
+        assert!("hello".parse::<Build>().is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,284 @@
+use super::{
+    mass_effect_2::plot::PlotTable as Me2PlotTable,
+    mass_effect_3::plot::PlotTable,
+    shared::plot::{BoolSlice, Me1PlotTable},
+};
+
+// Personnage importé du jeu précédent ou nouvelle partie, (libellé, booléen)
+pub const ME2_IMPORT_FLAGS: [(&str, usize); 1] = [("Imported from ME1", 2279)];
+pub const ME3_IMPORT_FLAGS: [(&str, usize); 2] =
+    [("Imported from ME2", 21554), ("Played ME1", 22226)];
+
+// Comme une nouvelle partie de ME2, les décisions de ME1 reprennent leurs valeurs par défaut
+pub fn clear_me1_import(me1_plot: &mut Me1PlotTable) {
+    me1_plot.bool_variables.clear();
+    me1_plot.int_variables.clear();
+    me1_plot.float_variables.clear();
+}
+
+// Tables de correspondance des décisions importées d'un jeu à l'autre
+// ME2 et ME3 partagent les mêmes ids, ME3 relit directement ceux de ME2
+
+// Morts de l'équipe (Suicide Mission), un flag levé par membre mort
+const ME2_TO_ME3_SQUAD_DEATHS: [usize; 12] = [
+    195, // Miranda
+    196, // Jacob
+    197, // Jack
+    198, // Legion
+    199, // Kasumi
+    200, // Garrus
+    201, // Thane
+    202, // Tali
+    203, // Mordin
+    204, // Grunt
+    205, // Samara
+    206, // Zaeed
+];
+
+// Base des Récolteurs
+const ME2_TO_ME3_COLLECTOR_BASE: [usize; 4] = [
+    1831, // Détruite
+    1832, // Sauvée
+    4388, // Shepard l'a traitée d'abomination
+    4390, // Utilisée pour étudier les Moissonneurs
+];
+
+// Romances
+pub const ME2_TO_ME3_ROMANCE_BOOLEANS: [usize; 8] = [
+    5206, // Thane
+    5207, // Garrus
+    5208, // Jack
+    5209, // Miranda
+    3892, // Jacob
+    3926, // Tali
+    6999, // Romance refusée (Hagalaz)
+    7333, // Romance ravivée dans la cabine
+];
+const ME2_TO_ME3_ROMANCE_INTS: [usize; 7] = [
+    213, // Jack
+    266, // Miranda
+    267, // Jacob
+    270, // Garrus
+    271, // Thane
+    272, // Tali
+    275, // Samara
+];
+
+// Romances de ME1 relues par ME3 : Ashley, Kaidan, Liara
+const ME1_ROMANCES_IN_ME3: [usize; 3] = [14281, 13960, 14169];
+
+// Incohérences de l'historique qui empêchent ME3 de reprendre une romance
+pub fn me3_romance_warnings(booleans: &BoolSlice) -> Vec<&'static str> {
+    let is_set = |plot_id: &usize| booleans.get(*plot_id).map_or(false, |value| *value);
+    let [(_, imported_me2), (_, played_me1)] = ME3_IMPORT_FLAGS;
+    let (me2_romances, hagalaz) = ME2_TO_ME3_ROMANCE_BOOLEANS.split_at(6);
+    let me2_count = me2_romances.iter().filter(|&plot_id| is_set(plot_id)).count();
+    let me1_count = ME1_ROMANCES_IN_ME3.iter().filter(|&plot_id| is_set(plot_id)).count();
+
+    let mut warnings = Vec::new();
+    if me2_count > 0 && !is_set(&imported_me2) {
+        warnings
+            .push("A Mass Effect 2 romance is set but the save isn't imported from Mass Effect 2");
+    }
+    if me2_count > 1 {
+        warnings.push("Several Mass Effect 2 romances are set, only one can carry over");
+    }
+    if hagalaz.iter().all(is_set) {
+        warnings.push("Liara's romance is both rejected on Hagalaz and rekindled");
+    }
+    if me1_count > 0 && !is_set(&played_me1) {
+        warnings.push("A Mass Effect 1 romance is set but `Played ME1` isn't");
+    }
+    if me1_count > 1 {
+        warnings.push("Several Mass Effect 1 romances are set, only one can carry over");
+    }
+    warnings
+}
+
+// Choix qui s'excluent, (décision, [(choix, booléen)])
+pub type Decision = (&'static str, &'static [(&'static str, usize)]);
+
+const COLLECTOR_BASE_DECISION: Decision =
+    ("Collector Base", &[("Destroyed", 1831), ("Saved", 1832)]);
+const ME2_ROMANCE_DECISION: Decision = (
+    "Mass Effect 2 romance",
+    &[
+        ("Thane", 5206),
+        ("Garrus", 5207),
+        ("Jack", 5208),
+        ("Miranda", 5209),
+        ("Jacob", 3892),
+        ("Tali", 3926),
+    ],
+);
+const ME1_ROMANCE_DECISION: Decision =
+    ("Mass Effect 1 romance", &[("Ashley", 14281), ("Kaidan", 13960), ("Liara", 14169)]);
+
+pub const ME2_DECISIONS: [Decision; 2] = [COLLECTOR_BASE_DECISION, ME2_ROMANCE_DECISION];
+pub const ME3_DECISIONS: [Decision; 3] =
+    [COLLECTOR_BASE_DECISION, ME2_ROMANCE_DECISION, ME1_ROMANCE_DECISION];
+
+// Décisions dont plusieurs choix sont faits, `Décision : a, b`
+pub fn decision_conflicts(booleans: &BoolSlice, decisions: &[Decision]) -> Vec<String> {
+    let is_set = |plot_id: usize| booleans.get(plot_id).map_or(false, |value| *value);
+    decisions
+        .iter()
+        .filter_map(|(decision, choices)| {
+            let set: Vec<_> = choices
+                .iter()
+                .filter(|(_, plot_id)| is_set(*plot_id))
+                .map(|(choice, _)| *choice)
+                .collect();
+            (set.len() > 1).then(|| format!("{} : {}", decision, set.join(", ")))
+        })
+        .collect()
+}
+
+pub fn me2_to_me3(me2_plot: &Me2PlotTable, me3_plot: &mut PlotTable) {
+    let booleans = ME2_TO_ME3_SQUAD_DEATHS
+        .iter()
+        .chain(&ME2_TO_ME3_COLLECTOR_BASE)
+        .chain(&ME2_TO_ME3_ROMANCE_BOOLEANS);
+
+    for &plot_id in booleans {
+        let value = plot_id < me2_plot.bool_variables.len() && me2_plot.bool_variables[plot_id];
+
+        if plot_id >= me3_plot.bool_variables.len() {
+            me3_plot.bool_variables.resize(plot_id + 1, false);
+        }
+        me3_plot.bool_variables.set(plot_id, value);
+    }
+
+    for &plot_id in &ME2_TO_ME3_ROMANCE_INTS {
+        let value = me2_plot.int_variables.get(plot_id).copied().unwrap_or_default();
+        me3_plot.int_variables.insert(plot_id as i32, value);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::Result;
+    use std::{fs::File, io::Read};
+
+    use crate::{
+        save_data::{mass_effect_2::Me2SaveGame, mass_effect_3::Me3SaveGame},
+        unreal,
+    };
+
+    use super::*;
+
+    fn read(path: &str) -> Result<Vec<u8>> {
+        let mut input = Vec::new();
+        let mut file = File::open(path)?;
+        file.read_to_end(&mut input)?;
+        Ok(input)
+    }
+
+    #[test]
+    fn import_flags() -> Result<()> {
+        let mut me2_save_game: Me2SaveGame =
+            unreal::Deserializer::from_bytes(&read("test/ME2Save.pcsav")?)?;
+        let (_, played_me1) = ME2_IMPORT_FLAGS[0];
+        let imported = me2_save_game.plot.bool_variables[played_me1];
+        me2_save_game.plot.bool_variables.set(played_me1, !imported);
+        clear_me1_import(&mut me2_save_game.me1_plot);
+
+        let output = unreal::Serializer::to_byte_buf(&me2_save_game)?;
+        let me2_save_game: Me2SaveGame = unreal::Deserializer::from_bytes(&output)?;
+        assert_eq!(me2_save_game.plot.bool_variables[played_me1], !imported);
+        assert!(me2_save_game.me1_plot.bool_variables.is_empty());
+
+        let mut me3_save_game: Me3SaveGame =
+            unreal::Deserializer::from_bytes(&read("test/ME3Save.pcsav")?)?;
+        let mut expected = Vec::new();
+        for &(_, plot_id) in &ME3_IMPORT_FLAGS {
+            let value = me3_save_game.plot.bool_variables[plot_id];
+            me3_save_game.plot.bool_variables.set(plot_id, !value);
+            expected.push(!value);
+        }
+
+        let output = unreal::Serializer::to_byte_buf(&me3_save_game)?;
+        let me3_save_game: Me3SaveGame = unreal::Deserializer::from_bytes(&output)?;
+        let values: Vec<_> = ME3_IMPORT_FLAGS
+            .iter()
+            .map(|&(_, plot_id)| me3_save_game.plot.bool_variables[plot_id])
+            .collect();
+        assert_eq!(values, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn me2_to_me3_decisions() -> Result<()> {
+        let me2_save_game: Me2SaveGame =
+            unreal::Deserializer::from_bytes(&read("test/ME2Save.pcsav")?)?;
+        let mut me3_save_game: Me3SaveGame =
+            unreal::Deserializer::from_bytes(&read("test/ME3Save.pcsav")?)?;
+
+        // On part d'un ME3 vierge
+        for &plot_id in ME2_TO_ME3_SQUAD_DEATHS.iter().chain(&ME2_TO_ME3_COLLECTOR_BASE) {
+            if plot_id < me3_save_game.plot.bool_variables.len() {
+                me3_save_game.plot.bool_variables.set(plot_id, false);
+            }
+        }
+
+        me2_to_me3(&me2_save_game.plot, &mut me3_save_game.plot);
+
+        let me2_plot = &me2_save_game.plot;
+        let me3_plot = &me3_save_game.plot;
+        let booleans = ME2_TO_ME3_SQUAD_DEATHS
+            .iter()
+            .chain(&ME2_TO_ME3_COLLECTOR_BASE)
+            .chain(&ME2_TO_ME3_ROMANCE_BOOLEANS);
+        for &plot_id in booleans {
+            let expected =
+                plot_id < me2_plot.bool_variables.len() && me2_plot.bool_variables[plot_id];
+            assert_eq!(me3_plot.bool_variables[plot_id], expected, "bool {}", plot_id);
+        }
+        for &plot_id in &ME2_TO_ME3_ROMANCE_INTS {
+            let expected = me2_plot.int_variables.get(plot_id).copied().unwrap_or_default();
+            assert_eq!(me3_plot.int_variables[&(plot_id as i32)], expected, "int {}", plot_id);
+        }
+
+        // Le reste de la sauvegarde ME3 se sérialise toujours
+        unreal::Serializer::to_byte_buf(&me3_save_game)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn romance_history() -> Result<()> {
+        let mut me3_save_game: Me3SaveGame =
+            unreal::Deserializer::from_bytes(&read("test/ME3Save.pcsav")?)?;
+        let booleans = &mut me3_save_game.plot.bool_variables;
+        let history = ME2_TO_ME3_ROMANCE_BOOLEANS
+            .iter()
+            .chain(&ME1_ROMANCES_IN_ME3)
+            .chain(ME3_IMPORT_FLAGS.iter().map(|(_, plot_id)| plot_id));
+        for &plot_id in history {
+            if plot_id >= booleans.len() {
+                booleans.resize(plot_id + 1, false);
+            }
+            booleans.set(plot_id, false);
+        }
+        assert!(me3_romance_warnings(booleans).is_empty());
+
+        // Garrus dans ME2, sans l'import
+        let garrus = ME2_TO_ME3_ROMANCE_BOOLEANS[1];
+        booleans.set(garrus, true);
+        assert_eq!(me3_romance_warnings(booleans).len(), 1);
+        booleans.set(ME3_IMPORT_FLAGS[0].1, true);
+        assert!(me3_romance_warnings(booleans).is_empty());
+        booleans.set(ME2_TO_ME3_ROMANCE_BOOLEANS[2], true);
+        assert_eq!(
+            me3_romance_warnings(booleans),
+            ["Several Mass Effect 2 romances are set, only one can carry over"]
+        );
+        booleans.set(ME2_TO_ME3_ROMANCE_BOOLEANS[2], false);
+
+        let output = unreal::Serializer::to_byte_buf(&me3_save_game)?;
+        let me3_save_game: Me3SaveGame = unreal::Deserializer::from_bytes(&output)?;
+        assert!(me3_save_game.plot.bool_variables[garrus]);
+        assert!(me3_romance_warnings(&me3_save_game.plot.bool_variables).is_empty());
+        Ok(())
+    }
+}
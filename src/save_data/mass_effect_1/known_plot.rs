@@ -1,14 +1,34 @@
+use anyhow::Result;
 use indexmap::IndexMap;
 use serde::Deserialize;
 
-use crate::save_data::shared::plot::PlotCategory;
+use crate::save_data::shared::plot::{self, PlotCategory, PlotId, PlotNames};
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Clone)]
 pub struct Me1KnownPlot {
     pub player_crew: IndexMap<String, PlotCategory>,
     pub missions: IndexMap<String, PlotCategory>,
 }
 
+impl Me1KnownPlot {
+    pub fn find(&self, name: &str) -> Result<Option<PlotId>> {
+        plot::find_unique(self.player_crew.values().chain(self.missions.values()), name)
+    }
+
+    pub fn localize(&mut self, names: &PlotNames) {
+        self.player_crew
+            .values_mut()
+            .chain(self.missions.values_mut())
+            .for_each(|plots| plots.localize(names));
+    }
+
+    pub fn names(&self) -> PlotNames {
+        let mut names = PlotNames::default();
+        self.player_crew.values().chain(self.missions.values()).for_each(|plots| names.add(plots));
+        names
+    }
+}
+
 #[cfg(test)]
 mod test {
     use anyhow::Result;
@@ -1,12 +1,17 @@
-use anyhow::Result;
-use serde::{de, ser::SerializeStruct, Deserialize, Serialize};
+use anyhow::{bail, Context, Result};
+use indexmap::IndexMap;
+use serde::{de, de::DeserializeOwned, ser::SerializeStruct, Deserialize, Serialize};
 use std::{
+    convert::TryInto,
     fmt,
-    io::{Cursor, Read, Write},
+    io::{Cursor, Read, Seek, SeekFrom, Write},
 };
 use zip::{write::FileOptions, CompressionMethod, ZipArchive, ZipWriter};
 
-use crate::unreal;
+use crate::{
+    save_archive,
+    unreal::{self, Coverage, OpaqueRegion},
+};
 
 use super::{Dummy, List};
 
@@ -19,6 +24,32 @@ use self::state::*;
 pub mod data;
 pub mod known_plot;
 
+// _begin + zip_offset
+const HEADER_LEN: u32 = 12;
+
+const KNOWN_MEMBERS: [&str; 3] = ["player.sav", "state.sav", "WorldSavePackage.sav"];
+
+// Taille de chaque partie du fichier une fois sérialisé
+pub struct SizeBreakdown {
+    pub header: usize,
+    pub no_mans_land: usize,
+    pub members: Vec<MemberSize>,
+    pub zip_overhead: usize,
+}
+
+pub struct MemberSize {
+    pub name: String,
+    pub size: usize,
+    pub compressed_size: usize,
+}
+
+impl SizeBreakdown {
+    pub fn total(&self) -> usize {
+        let members: usize = self.members.iter().map(|member| member.compressed_size).sum();
+        self.header + self.no_mans_land + members + self.zip_overhead
+    }
+}
+
 #[derive(Clone)]
 pub struct Me1SaveGame {
     _begin: Dummy<8>,
@@ -27,36 +58,221 @@ pub struct Me1SaveGame {
     pub player: Player,
     pub state: State,
     _world_save_package: Option<WorldSavePackage>,
+    // Fichiers ajoutés par des mods, réécrits tels quels
+    _extra_members: IndexMap<String, Vec<u8>>,
 }
 
 impl Me1SaveGame {
-    fn unzip(input: &[u8]) -> Result<(Player, State, Option<WorldSavePackage>)> {
-        let mut zip = ZipArchive::new(Cursor::new(input))?;
+    // Comme la désérialisation, mais les erreurs du zip gardent leur type (`TruncatedMember`)
+    // au lieu de devenir un message de serde
+    pub fn load(input: &[u8]) -> Result<Self> {
+        if input.len() < HEADER_LEN as usize {
+            bail!("The header is truncated");
+        }
+        let _begin = Dummy(input[..8].try_into()?);
+        let zip_offset = u32::from_le_bytes(input[8..12].try_into()?);
+        let zip_start = zip_offset as usize;
+        if zip_offset < HEADER_LEN || zip_start > input.len() {
+            bail!("Invalid zip offset ({})", zip_offset);
+        }
+
+        let (player, state, _world_save_package, _extra_members) =
+            Self::unzip(&input[zip_start..])?;
+        Ok(Me1SaveGame {
+            _begin,
+            zip_offset,
+            _no_mans_land: input[HEADER_LEN as usize..zip_start].to_vec().into(),
+            player,
+            state,
+            _world_save_package,
+            _extra_members,
+        })
+    }
+
+    pub fn no_mans_land(&self) -> &[u8] {
+        &self._no_mans_land
+    }
+
+    // Le zip commence juste après, son offset suit la longueur
+    pub fn set_no_mans_land(&mut self, no_mans_land: Vec<u8>) {
+        self.zip_offset = HEADER_LEN + no_mans_land.len() as u32;
+        self._no_mans_land = no_mans_land.into();
+    }
+
+    // Chargement partiel, seul le fichier demandé du zip est désérialisé
+    pub fn load_player_only<R: Read + Seek>(input: R) -> Result<Player> {
+        let mut zip = Self::open_zip(input)?;
+        Self::unzip_file(&mut zip, "player.sav")
+    }
+
+    pub fn load_state_only<R: Read + Seek>(input: R) -> Result<State> {
+        let mut zip = Self::open_zip(input)?;
+        Self::unzip_file(&mut zip, "state.sav")
+    }
+
+    // En-tête et répertoire du zip lisibles, sans rien décompresser
+    pub fn check_zip<R: Read + Seek>(input: R) -> Result<()> {
+        Self::open_zip(input).map(|_| ())
+    }
+
+    // Fichiers du zip, pour les modifier à la main
+    pub fn zip_members(&self) -> Vec<&str> {
+        let mut members = vec!["player.sav", "state.sav"];
+        if self._world_save_package.is_some() {
+            members.push("WorldSavePackage.sav");
+        }
+        members.extend(self._extra_members.keys().map(String::as_str));
+        members
+    }
 
-        let player: Player = {
-            let mut bytes = Vec::new();
-            zip.by_name("player.sav")?.read_to_end(&mut bytes)?;
-            unreal::Deserializer::from_bytes(&bytes)?
+    // Inconnu de l'éditeur, ni lu ni vérifié
+    pub fn is_extra_member(&self, member: &str) -> bool {
+        self._extra_members.contains_key(member)
+    }
+
+    pub fn export_member(&self, member: &str) -> Result<Vec<u8>> {
+        let output = match member {
+            "player.sav" => unreal::Serializer::to_byte_buf(&self.player)?,
+            "state.sav" => unreal::Serializer::to_byte_buf(&self.state)?,
+            "WorldSavePackage.sav" => match &self._world_save_package {
+                Some(world_save_package) => unreal::Serializer::to_byte_buf(world_save_package)?,
+                None => bail!("Missing `{}` in zip archive", member),
+            },
+            _ => match self._extra_members.get(member) {
+                Some(data) => data.clone(),
+                None => bail!("Unknown zip member `{}`", member),
+            },
         };
+        Ok(output)
+    }
+
+    // Le fichier n'est accepté que s'il se désérialise
+    pub fn replace_member(&mut self, member: &str, input: &[u8]) -> Result<()> {
+        let context = || format!("Invalid `{}`", member);
+        match member {
+            "player.sav" => {
+                self.player = unreal::Deserializer::from_bytes(input).with_context(context)?
+            }
+            "state.sav" => {
+                self.state = unreal::Deserializer::from_bytes(input).with_context(context)?
+            }
+            "WorldSavePackage.sav" => {
+                self._world_save_package =
+                    Some(unreal::Deserializer::from_bytes(input).with_context(context)?)
+            }
+            _ => match self._extra_members.get_mut(member) {
+                Some(data) => *data = input.to_vec(),
+                None => bail!("Unknown zip member `{}`", member),
+            },
+        }
+        Ok(())
+    }
+
+    // Contenu décompressé du zip, l'en-tête et le conteneur ne sont pas comptés
+    pub fn coverage(&self) -> Result<Coverage> {
+        let mut coverage = unreal::to_coverage(&self.player)?
+            + unreal::to_coverage(&self.state)?
+            + Coverage::opaque(self._no_mans_land.len());
+        if let Some(world_save_package) = &self._world_save_package {
+            coverage = coverage + Coverage::opaque(world_save_package.data.len());
+        }
+        for data in self._extra_members.values() {
+            coverage = coverage + Coverage::opaque(data.len());
+        }
+        Ok(coverage)
+    }
+
+    // Membres du zip sous leur nom, `_no_mans_land` avant le zip
+    pub fn opaque_regions(&self) -> Result<Vec<OpaqueRegion>> {
+        let mut regions = vec![OpaqueRegion {
+            field: "_no_mans_land".to_owned(),
+            bytes: self._no_mans_land.to_vec(),
+        }];
+        regions.extend(unreal::to_opaque_regions(&self.player)?);
+        regions.extend(unreal::to_opaque_regions(&self.state)?);
+        if let Some(world_save_package) = &self._world_save_package {
+            let bytes = world_save_package.data.to_vec();
+            regions.push(OpaqueRegion { field: "WorldSavePackage.sav".to_owned(), bytes });
+        }
+        for (member, data) in &self._extra_members {
+            regions.push(OpaqueRegion { field: member.clone(), bytes: data.clone() });
+        }
+        Ok(regions)
+    }
+
+    // Le zip est reconstruit, les tailles sont celles du prochain enregistrement
+    pub fn size_breakdown(&self) -> Result<SizeBreakdown> {
+        let zip_data = self.zip()?;
+        let mut zip = ZipArchive::new(Cursor::new(&zip_data[..])).context("Invalid zip archive")?;
+
+        let mut members = Vec::new();
+        for i in 0..zip.len() {
+            let file = zip.by_index(i)?;
+            members.push(MemberSize {
+                name: file.name().to_owned(),
+                size: file.size() as usize,
+                compressed_size: file.compressed_size() as usize,
+            });
+        }
+
+        let compressed: usize = members.iter().map(|member| member.compressed_size).sum();
+        Ok(SizeBreakdown {
+            header: HEADER_LEN as usize,
+            no_mans_land: self._no_mans_land.len(),
+            members,
+            zip_overhead: zip_data.len() - compressed,
+        })
+    }
+
+    fn open_zip<R: Read + Seek>(mut input: R) -> Result<ZipArchive<Cursor<Vec<u8>>>> {
+        let mut header = [0; HEADER_LEN as usize];
+        input.read_exact(&mut header)?;
+        let zip_offset = u32::from_le_bytes(header[8..12].try_into()?);
+
+        let mut zip_data = Vec::new();
+        input.seek(SeekFrom::Start(zip_offset as u64))?;
+        input.read_to_end(&mut zip_data)?;
+
+        ZipArchive::new(Cursor::new(zip_data)).context("Invalid zip archive")
+    }
+
+    fn unzip_file<R: Read + Seek, T: DeserializeOwned>(
+        zip: &mut ZipArchive<R>, file_name: &str,
+    ) -> Result<T> {
+        let file = zip
+            .by_name(file_name)
+            .with_context(|| format!("Missing `{}` in zip archive", file_name))?;
+        let bytes = save_archive::read_zip_member(file, file_name)?;
+        Ok(unreal::Deserializer::from_bytes(&bytes)?)
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn unzip(
+        input: &[u8],
+    ) -> Result<(Player, State, Option<WorldSavePackage>, IndexMap<String, Vec<u8>>)> {
+        let mut zip = ZipArchive::new(Cursor::new(input)).context("Invalid zip archive")?;
+
+        let player = Self::unzip_file(&mut zip, "player.sav")?;
+        let state = Self::unzip_file(&mut zip, "state.sav")?;
 
-        let state: State = {
-            let mut bytes = Vec::new();
-            zip.by_name("state.sav")?.read_to_end(&mut bytes)?;
-            unreal::Deserializer::from_bytes(&bytes)?
+        let _world_save_package = if zip.file_names().any(|f| f == "WorldSavePackage.sav") {
+            Some(Self::unzip_file(&mut zip, "WorldSavePackage.sav")?)
+        } else {
+            None
         };
 
-        let _world_save_package: Option<WorldSavePackage> =
-            if zip.file_names().any(|f| f == "WorldSavePackage.sav") {
-                Some({
-                    let mut bytes = Vec::new();
-                    zip.by_name("WorldSavePackage.sav")?.read_to_end(&mut bytes)?;
-                    unreal::Deserializer::from_bytes(&bytes)?
-                })
-            } else {
-                None
-            };
+        // Dans l'ordre de l'archive, `file_names` suit celui d'une HashMap
+        let mut extra_members = IndexMap::new();
+        for index in 0..zip.len() {
+            let file = zip.by_index(index)?;
+            let name = file.name().to_owned();
+            if !KNOWN_MEMBERS.contains(&name.as_str()) {
+                let data = save_archive::read_zip_member(file, &name)?;
+                extra_members.insert(name, data);
+            }
+        }
 
-        Ok((player, state, _world_save_package))
+        Ok((player, state, _world_save_package, extra_members))
     }
 
     fn zip(&self) -> Result<List<u8>> {
@@ -83,6 +299,11 @@ impl Me1SaveGame {
                 zipper.start_file("WorldSavePackage.sav", options)?;
                 zipper.write_all(&world_save_package_data)?;
             }
+            // Mods
+            for (name, data) in &self._extra_members {
+                zipper.start_file(name.as_str(), options)?;
+                zipper.write_all(data)?;
+            }
         }
         Ok(zip.into())
     }
@@ -106,17 +327,21 @@ impl<'de> serde::Deserialize<'de> for Me1SaveGame {
                 A: de::SeqAccess<'de>,
             {
                 let _begin = seq.next_element()?.unwrap();
-                let zip_offset = seq.next_element()?.unwrap();
+                let zip_offset: u32 = seq.next_element()?.unwrap();
+                let no_mans_land_len = zip_offset.checked_sub(HEADER_LEN).ok_or_else(|| {
+                    de::Error::custom(format!("Invalid zip offset ({})", zip_offset))
+                })?;
 
                 // No man's land
                 let mut _no_mans_land = Vec::new();
-                for _ in 0..(zip_offset - 12) {
+                for _ in 0..no_mans_land_len {
                     _no_mans_land.push(seq.next_element()?.unwrap());
                 }
 
                 let zip_data: List<u8> = seq.next_element()?.unwrap();
-                let (player, state, _world_save_package) =
-                    Me1SaveGame::unzip(&zip_data).map_err(de::Error::custom)?;
+                let (player, state, _world_save_package, _extra_members) =
+                    Me1SaveGame::unzip(&zip_data)
+                        .map_err(|err| de::Error::custom(format!("{:#}", err)))?;
 
                 Ok(Me1SaveGame {
                     _begin,
@@ -125,6 +350,7 @@ impl<'de> serde::Deserialize<'de> for Me1SaveGame {
                     player,
                     state,
                     _world_save_package,
+                    _extra_members,
                 })
             }
         }
@@ -145,8 +371,13 @@ impl serde::Serialize for Me1SaveGame {
             player: _,
             state: _,
             _world_save_package,
+            _extra_members: _,
         } = self;
 
+        if *zip_offset != HEADER_LEN + _no_mans_land.len() as u32 {
+            return Err(Error::custom("zip_offset doesn't match the no man's land length"));
+        }
+
         let mut s = serializer.serialize_struct("Me1SaveGame", 4)?;
         s.serialize_field("_begin", _begin)?;
         s.serialize_field("zip_offset", zip_offset)?;
@@ -163,7 +394,7 @@ pub(super) struct WorldSavePackage {
 
 #[cfg(test)]
 mod test {
-    use anyhow::Result;
+    use anyhow::{Context, Result};
     use std::{
         time::Instant,
         {fs::File, io::Read},
@@ -225,4 +456,172 @@ mod test {
         }
         Ok(())
     }
+
+    #[test]
+    fn partial_load() -> Result<()> {
+        let mut input = Vec::new();
+        {
+            let mut file = File::open("test/Clare00_AutoSave.MassEffectSave")?;
+            file.read_to_end(&mut input)?;
+        }
+
+        let me1_save_game: Me1SaveGame = unreal::Deserializer::from_bytes(&input)?;
+
+        // Player seul
+        let player = Me1SaveGame::load_player_only(Cursor::new(&input))?;
+        assert_eq!(
+            unreal::Serializer::to_byte_buf(&player)?,
+            unreal::Serializer::to_byte_buf(&me1_save_game.player)?
+        );
+
+        // State seul
+        let state = Me1SaveGame::load_state_only(Cursor::new(&input))?;
+        assert_eq!(
+            unreal::Serializer::to_byte_buf(&state)?,
+            unreal::Serializer::to_byte_buf(&me1_save_game.state)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn export_replace_member() -> Result<()> {
+        let mut input = Vec::new();
+        {
+            let mut file = File::open("test/Clare00_AutoSave.MassEffectSave")?;
+            file.read_to_end(&mut input)?;
+        }
+
+        let mut me1_save_game: Me1SaveGame = unreal::Deserializer::from_bytes(&input)?;
+        assert_eq!(
+            me1_save_game.zip_members(),
+            vec!["player.sav", "state.sav", "WorldSavePackage.sav"]
+        );
+
+        // Export d'un state modifié puis remplacement dans la sauvegarde d'origine
+        let mut edited = me1_save_game.clone();
+        let value = edited.state.plot.bool_variables[0];
+        edited.state.plot.bool_variables.set(0, !value);
+        let state = edited.export_member("state.sav")?;
+
+        me1_save_game.replace_member("state.sav", &state)?;
+        assert_eq!(me1_save_game.export_member("state.sav")?, state);
+        assert_eq!(
+            unreal::Serializer::to_byte_buf(&me1_save_game)?,
+            unreal::Serializer::to_byte_buf(&edited)?
+        );
+
+        // Fichier invalide refusé, rien n'est modifié
+        assert!(me1_save_game.replace_member("state.sav", b"not a state").is_err());
+        assert_eq!(me1_save_game.export_member("state.sav")?, state);
+        assert!(me1_save_game.export_member("BioGame.sav").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn zip_offset_follows_no_mans_land() -> Result<()> {
+        let mut input = Vec::new();
+        {
+            let mut file = File::open("test/Clare00_AutoSave.MassEffectSave")?;
+            file.read_to_end(&mut input)?;
+        }
+
+        let mut me1_save_game: Me1SaveGame = unreal::Deserializer::from_bytes(&input)?;
+        let old_offset = me1_save_game.zip_offset;
+
+        let mut no_mans_land = me1_save_game.no_mans_land().to_vec();
+        no_mans_land.extend(&[0; 16]);
+        me1_save_game.set_no_mans_land(no_mans_land);
+        assert_eq!(me1_save_game.zip_offset, old_offset + 16);
+
+        let output = unreal::Serializer::to_byte_buf(&me1_save_game)?;
+        assert_eq!(u32::from_le_bytes((&output[8..12]).try_into()?), old_offset + 16);
+
+        // Le zip est toujours lisible
+        let reloaded: Me1SaveGame = unreal::Deserializer::from_bytes(&output)?;
+        assert_eq!(reloaded.no_mans_land().len(), me1_save_game.no_mans_land().len());
+        assert_eq!(
+            unreal::Serializer::to_byte_buf(&reloaded.state)?,
+            unreal::Serializer::to_byte_buf(&me1_save_game.state)?
+        );
+
+        // Incohérence refusée
+        me1_save_game.zip_offset += 1;
+        assert!(unreal::Serializer::to_byte_buf(&me1_save_game).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_zip_offset() {
+        // zip_offset avant la fin de l'en-tête
+        let mut input = vec![0; 40];
+        input[8..12].copy_from_slice(&4u32.to_le_bytes());
+        let result: Result<Me1SaveGame, _> = unreal::Deserializer::from_bytes(&input);
+        assert_eq!(
+            result.err().map(|err| err.to_string()),
+            Some("Invalid zip offset (4)".to_owned())
+        );
+    }
+
+    #[test]
+    fn size_breakdown() -> Result<()> {
+        let files = [
+            "test/Clare00_AutoSave.MassEffectSave",
+            "test/Char_01-60-3-2-2-26-6-2018-57-26.MassEffectSave",
+        ];
+
+        for file in &files {
+            let mut input = Vec::new();
+            {
+                let mut file = File::open(file)?;
+                file.read_to_end(&mut input)?;
+            }
+
+            let me1_save_game: Me1SaveGame = unreal::Deserializer::from_bytes(&input)?;
+            let breakdown = me1_save_game.size_breakdown()?;
+            let output = unreal::Serializer::to_byte_buf(&me1_save_game)?;
+
+            assert_eq!(breakdown.total(), output.len());
+            assert_eq!(breakdown.members.len(), me1_save_game.zip_members().len());
+
+            let player = breakdown.members.iter().find(|member| member.name == "player.sav");
+            assert_eq!(
+                player.map(|member| member.size),
+                Some(me1_save_game.export_member("player.sav")?.len())
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn extra_member() -> Result<()> {
+        let mut input = Vec::new();
+        {
+            let mut file = File::open("test/Clare00_AutoSave.MassEffectSave")?;
+            file.read_to_end(&mut input)?;
+        }
+
+        // Sauvegarde modée, avec un fichier en plus dans le zip
+        let mut me1_save_game: Me1SaveGame = unreal::Deserializer::from_bytes(&input)?;
+        assert!(me1_save_game._extra_members.is_empty());
+        me1_save_game._extra_members.insert("ModData.sav".to_owned(), b"mod data".to_vec());
+        me1_save_game._extra_members.insert("Aaa.sav".to_owned(), b"more data".to_vec());
+        let modded = unreal::Serializer::to_byte_buf(&me1_save_game)?;
+
+        let mut me1_save_game: Me1SaveGame = unreal::Deserializer::from_bytes(&modded)?;
+        assert_eq!(
+            me1_save_game.zip_members(),
+            vec!["player.sav", "state.sav", "WorldSavePackage.sav", "ModData.sav", "Aaa.sav"]
+        );
+        assert!(me1_save_game.is_extra_member("ModData.sav"));
+        assert_eq!(me1_save_game.export_member("ModData.sav")?, b"mod data");
+
+        // Réécrit à l'identique, dans l'ordre de l'archive
+        assert_eq!(unreal::Serializer::to_byte_buf(&me1_save_game)?, modded);
+
+        me1_save_game.replace_member("ModData.sav", b"other data")?;
+        let output = unreal::Serializer::to_byte_buf(&me1_save_game)?;
+        let reloaded: Me1SaveGame = unreal::Deserializer::from_bytes(&output)?;
+        assert_eq!(reloaded.export_member("ModData.sav")?, b"other data");
+        Ok(())
+    }
 }
@@ -1,14 +1,33 @@
 use anyhow::Result;
 use derive_more::{Deref, DerefMut};
 use serde::{de, ser::SerializeStruct, Deserialize, Serialize};
-use std::{cell::RefCell, fmt};
+use std::{cell::RefCell, fmt, ops::RangeInclusive};
 
 use crate::{
-    save_data::{Dummy, ImguiString},
+    save_data::{
+        shared::limits::{self, range_error},
+        Dummy, ImguiString,
+    },
     unreal,
 };
 
-use super::{data::Data, List};
+use super::{
+    data::{Data, Property},
+    List,
+};
+
+// Propriétés bornées du joueur, (champ, propriété, limites). Le joueur de ME1 n'est qu'une
+// liste de propriétés, pas de `#[range]` possible
+pub const INT_RANGES: [(&str, &str, RangeInclusive<i32>); 4] = [
+    ("level", "m_XPLevel", limits::ME1_LEVEL),
+    ("current_xp", "m_nSquadExperience", limits::POSITIVE),
+    ("credits", "m_nResourceCredits", limits::ME1_CREDITS),
+    ("grenades", "m_nResourceGrenades", limits::POSITIVE),
+];
+pub const FLOAT_RANGES: [(&str, &str, RangeInclusive<f32>); 2] = [
+    ("medigel", "m_fResourceMedigel", limits::POSITIVE_F32),
+    ("omnigel", "m_fResourceSalvage", limits::POSITIVE_F32),
+];
 
 #[derive(Clone)]
 pub struct Player {
@@ -21,10 +40,17 @@ pub struct Player {
     pub objects: List<Object>,
     _no_mans_land2: List<u8>,
     datas: List<RefCell<Data>>,
+    _unknown_tail: List<u8>,
     pub duplicate: RefCell<Option<Name>>, // Spécial n'est pas (dé)sérialisé
 }
 
 impl Player {
+    // Version du package UE3 et version licenciée, celles du moteur qui a écrit la sauvegarde
+    pub fn package_version(&self) -> (u16, u16) {
+        let [low_1, low_2, high_1, high_2] = self.header._version.0;
+        (u16::from_le_bytes([low_1, low_2]), u16::from_le_bytes([high_1, high_2]))
+    }
+
     pub fn get_name(&self, id: u32) -> &RefCell<Name> {
         &self.names[id as usize]
     }
@@ -40,6 +66,38 @@ impl Player {
     pub fn get_data(&self, i: i32) -> &RefCell<Data> {
         &self.datas[i as usize - 1]
     }
+
+    // Comme `RawUi::range_errors`, les propriétés sont cherchées dans tous les objets
+    pub fn range_errors(&self) -> Vec<(&'static str, String)> {
+        let mut errors = Vec::new();
+        for data in self.datas.iter() {
+            for property in data.borrow().properties.iter() {
+                let error = match property {
+                    Property::Int { name_id, value, .. } => {
+                        let name = self.get_name(*name_id).borrow();
+                        INT_RANGES
+                            .iter()
+                            .find(|(_, property_name, _)| name.to_str() == *property_name)
+                            .and_then(|(field, _, range)| {
+                                Some((*field, range_error(value, range)?))
+                            })
+                    }
+                    Property::Float { name_id, value, .. } => {
+                        let name = self.get_name(*name_id).borrow();
+                        FLOAT_RANGES
+                            .iter()
+                            .find(|(_, property_name, _)| name.to_str() == *property_name)
+                            .and_then(|(field, _, range)| {
+                                Some((*field, range_error(value, range)?))
+                            })
+                    }
+                    _ => None,
+                };
+                errors.extend(error);
+            }
+        }
+        errors
+    }
 }
 
 impl<'de> serde::Deserialize<'de> for Player {
@@ -101,6 +159,9 @@ impl<'de> serde::Deserialize<'de> for Player {
                     datas.push(RefCell::new(data));
                 }
 
+                // Octets restants après les données, conservés tels quels
+                let _unknown_tail = seq.next_element()?.unwrap();
+
                 Ok(Player {
                     _begin,
                     header_offset,
@@ -111,6 +172,7 @@ impl<'de> serde::Deserialize<'de> for Player {
                     objects: objects.into(),
                     _no_mans_land2: _no_mans_land2.into(),
                     datas: datas.into(),
+                    _unknown_tail,
                     duplicate: RefCell::new(None),
                 })
             }
@@ -135,6 +197,7 @@ impl serde::Serialize for Player {
             objects,
             _no_mans_land2,
             datas,
+            _unknown_tail,
             duplicate: _,
         } = self;
 
@@ -163,7 +226,7 @@ impl serde::Serialize for Player {
         }
 
         // Serialize
-        let mut s = serializer.serialize_struct("Player", 9)?;
+        let mut s = serializer.serialize_struct("Player", 10)?;
         s.serialize_field("_begin", _begin)?;
         s.serialize_field("header_offset", header_offset)?;
         s.serialize_field("_no_mans_land1", _no_mans_land1)?;
@@ -173,6 +236,7 @@ impl serde::Serialize for Player {
         s.serialize_field("objects", &objects)?;
         s.serialize_field("_no_mans_land2", _no_mans_land2)?;
         s.serialize_field("objects", datas)?;
+        s.serialize_field("_unknown_tail", _unknown_tail)?;
         s.end()
     }
 }
@@ -252,22 +316,24 @@ mod test {
 
     use super::*;
 
-    #[test]
-    fn deserialize_serialize() -> Result<()> {
+    fn player_data() -> Result<Vec<u8>> {
         let mut input = Vec::new();
         {
             let mut file = File::open("test/Clare00_AutoSave.MassEffectSave")?;
             file.read_to_end(&mut input)?;
         }
 
-        let player_data = {
-            let zip_offset = <u32>::from_le_bytes((&input[8..12]).try_into()?);
-            let mut zip = ZipArchive::new(Cursor::new(&input[zip_offset as usize..]))?;
+        let zip_offset = <u32>::from_le_bytes((&input[8..12]).try_into()?);
+        let mut zip = ZipArchive::new(Cursor::new(&input[zip_offset as usize..]))?;
 
-            let mut bytes = Vec::new();
-            zip.by_name("player.sav")?.read_to_end(&mut bytes)?;
-            bytes
-        };
+        let mut bytes = Vec::new();
+        zip.by_name("player.sav")?.read_to_end(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    #[test]
+    fn deserialize_serialize() -> Result<()> {
+        let player_data = player_data()?;
 
         // Deserialize
         let player: Player = unreal::Deserializer::from_bytes(&player_data.clone())?;
@@ -288,4 +354,16 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn trailing_bytes() -> Result<()> {
+        let mut player_data = player_data()?;
+        player_data.extend(&[0xde, 0xad, 0xbe, 0xef, 0x01]);
+
+        let player: Player = unreal::Deserializer::from_bytes(&player_data)?;
+        let output = unreal::Serializer::to_byte_buf(&player)?;
+
+        assert_eq!(player_data, output);
+        Ok(())
+    }
 }
@@ -1,5 +1,5 @@
 use anyhow::Result;
-use serde::{de, Serialize};
+use serde::{de, ser::SerializeStruct};
 use std::fmt;
 
 use crate::{
@@ -7,13 +7,13 @@ use crate::{
     save_data::{shared::plot::Me1PlotTable, Dummy, ImguiString, List, RawUi},
 };
 
-#[derive(Serialize, Clone)]
+#[derive(Clone)]
 pub struct State {
     _begin: Dummy<12>,
-    base_level_name: ImguiString,
+    pub base_level_name: ImguiString,
     _osef1: Dummy<24>,
     pub plot: Me1PlotTable,
-    _osef2: List<u8>,
+    _osef2: List<u8>, // Jusqu'à la fin, octets inconnus compris
 }
 
 impl RawUi for State {
@@ -42,7 +42,8 @@ impl<'de> serde::Deserialize<'de> for State {
                 let _begin = seq.next_element()?.unwrap();
                 let base_level_name = seq.next_element()?.unwrap();
                 let _osef1 = seq.next_element()?.unwrap();
-                let plot = seq.next_element()?.unwrap();
+                let mut plot: Me1PlotTable = seq.next_element()?.unwrap();
+                plot.normalize();
                 let _osef2 = seq.next_element()?.unwrap();
                 Ok(State { _begin, base_level_name, _osef1, plot, _osef2 })
             }
@@ -51,6 +52,24 @@ impl<'de> serde::Deserialize<'de> for State {
     }
 }
 
+impl serde::Serialize for State {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        // Copie normalisée, les booléens doivent remplir des u32 entiers
+        let mut plot = self.plot.clone();
+        plot.normalize();
+        let mut s = serializer.serialize_struct("State", 5)?;
+        s.serialize_field("_begin", &self._begin)?;
+        s.serialize_field("base_level_name", &self.base_level_name)?;
+        s.serialize_field("_osef1", &self._osef1)?;
+        s.serialize_field("plot", &plot)?;
+        s.serialize_field("_osef2", &self._osef2)?;
+        s.end()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use anyhow::Result;
@@ -65,22 +84,24 @@ mod test {
 
     use super::*;
 
-    #[test]
-    fn deserialize_serialize() -> Result<()> {
+    fn state_data() -> Result<Vec<u8>> {
         let mut input = Vec::new();
         {
             let mut file = File::open("test/Clare00_AutoSave.MassEffectSave")?;
             file.read_to_end(&mut input)?;
         }
 
-        let state_data = {
-            let zip_offset = <u32>::from_le_bytes((&input[8..12]).try_into()?);
-            let mut zip = ZipArchive::new(Cursor::new(&input[zip_offset as usize..]))?;
+        let zip_offset = <u32>::from_le_bytes((&input[8..12]).try_into()?);
+        let mut zip = ZipArchive::new(Cursor::new(&input[zip_offset as usize..]))?;
 
-            let mut bytes = Vec::new();
-            zip.by_name("state.sav")?.read_to_end(&mut bytes)?;
-            bytes
-        };
+        let mut bytes = Vec::new();
+        zip.by_name("state.sav")?.read_to_end(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    #[test]
+    fn deserialize_serialize() -> Result<()> {
+        let state_data = state_data()?;
 
         // Deserialize
         let state: State = unreal::Deserializer::from_bytes(&state_data.clone())?;
@@ -100,4 +121,45 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn plot_lengths_follow_vectors() -> Result<()> {
+        let state_data = state_data()?;
+        let mut state: State = unreal::Deserializer::from_bytes(&state_data)?;
+
+        // Ajoute des entrées sans se soucier des longueurs
+        let bool_len = state.plot.bool_variables.len();
+        let int_len = state.plot.int_variables.len();
+        state.plot.bool_variables.push(true);
+        state.plot.int_variables.push(42);
+        state.plot.int_variables.push(43);
+        state.plot.float_variables.clear();
+
+        let output = unreal::Serializer::to_byte_buf(&state)?;
+        let state: State = unreal::Deserializer::from_bytes(&output)?;
+
+        // Les booléens sont complétés jusqu'au prochain u32
+        assert_eq!(state.plot.bool_variables.len(), bool_len + 32);
+        assert!(state.plot.bool_variables[bool_len]);
+        assert_eq!(state.plot.int_variables.len(), int_len + 2);
+        assert_eq!(state.plot.int_variables[int_len + 1], 43);
+        assert!(state.plot.float_variables.is_empty());
+
+        // Le reste n'a pas bougé
+        assert_eq!(unreal::Serializer::to_byte_buf(&state)?, output);
+
+        Ok(())
+    }
+
+    #[test]
+    fn trailing_bytes() -> Result<()> {
+        let mut state_data = state_data()?;
+        state_data.extend(&[0xde, 0xad, 0xbe, 0xef, 0x01]);
+
+        let state: State = unreal::Deserializer::from_bytes(&state_data)?;
+        let output = unreal::Serializer::to_byte_buf(&state)?;
+
+        assert_eq!(state_data, output);
+        Ok(())
+    }
 }
@@ -186,13 +186,13 @@ pub struct Me1LegSaveData {
     _unknown5: Vec<Vec<Dummy<8>>>,
     _unknown6: Vec<Dummy<4>>,
     timestamp: SaveTimeStamp,
-    seconds_played: i32,
+    pub seconds_played: i32,
     pub player: Player,
     _unknown7: Dummy<16>,
     pub difficulty: Difficulty,
     _unknown8: Dummy<177>,
     player_controller: PlayerController,
-    map_name: ImguiString,
+    pub map_name: ImguiString,
     maybe_sub_map_name: ImguiString,
     _unknown9: Dummy<4>,
     location: Vector,
@@ -202,6 +202,12 @@ pub struct Me1LegSaveData {
     _remaining_bytes: List<u8>,
 }
 
+impl Me1LegSaveData {
+    pub fn version(&self) -> i32 {
+        self._version.0
+    }
+}
+
 #[derive(Serialize, Clone)]
 pub struct Me1LegVersion(i32);
 
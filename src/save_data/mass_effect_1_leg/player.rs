@@ -1,11 +1,17 @@
+use anyhow::{Context, Result};
+use derive_more::{Deref, DerefMut};
 use serde::{Deserialize, Serialize};
 
-use crate::save_data::{
-    shared::{
-        appearance::HasHeadMorph,
-        player::{Notoriety, Origin},
+use crate::{
+    gui::Gui,
+    save_data::{
+        shared::{
+            appearance::HasHeadMorph,
+            limits,
+            player::{Notoriety, Origin},
+        },
+        Dummy, ImguiString, RawUi,
     },
-    Dummy, ImguiString,
 };
 
 #[derive(Deserialize, Serialize, RawUi, Clone)]
@@ -13,7 +19,9 @@ pub struct Player {
     pub is_female: bool,
     localized_class_name: i32,
     _unknown1: Dummy<1>,
+    #[range(limits::ME1_LEVEL)]
     pub level: i32,
+    #[range(limits::POSITIVE_F32)]
     pub current_xp: f32,
     pub first_name: ImguiString,
     localized_last_name: i32,
@@ -21,16 +29,21 @@ pub struct Player {
     pub notoriety: Notoriety,
     specialization_bonus_id: i32,
     _unknown2: Dummy<1>,
+    #[range(limits::POSITIVE)]
     pub talent_points: i32,
     _unknown3: Dummy<4>,
     unknown_string: ImguiString,
     pub head_morph: HasHeadMorph,
     simple_talents: Vec<SimpleTalent>,
     complex_talents: Vec<ComplexTalent>,
-    inventory: Inventory,
+    pub inventory: Inventory,
+    #[range(limits::ME1_CREDITS)]
     pub credits: i32,
+    #[range(limits::POSITIVE)]
     pub medigel: i32,
+    #[range(limits::POSITIVE_F32)]
     pub grenades: f32,
+    #[range(limits::POSITIVE_F32)]
     pub omnigel: f32,
     pub face_code: ImguiString,
     _unknown4: Dummy<4>,
@@ -65,14 +78,45 @@ pub struct ComplexTalent {
 
 #[derive(Deserialize, Serialize, RawUi, Clone, Default)]
 pub struct Inventory {
-    equipped: Vec<Item>,
-    quick_slots: Vec<Item>,
-    inventory: Vec<Item>,
-    saved_backpack_items: Vec<Item>,
+    equipped: ItemList,
+    quick_slots: ItemList,
+    inventory: ItemList,
+    saved_backpack_items: ItemList,
+}
+
+impl Inventory {
+    pub fn lists_mut(&mut self) -> [(&'static str, &mut ItemList); 4] {
+        [
+            ("Equipped", &mut self.equipped),
+            ("Quick Slots", &mut self.quick_slots),
+            ("Inventory", &mut self.inventory),
+            ("Saved Backpack Items", &mut self.saved_backpack_items),
+        ]
+    }
+
+    // Uniquement l'inventaire, pour le passer d'un personnage à l'autre
+    pub fn export_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn import_json(&mut self, input: &str) -> Result<()> {
+        *self = serde_json::from_str(input).context("Not a ME1 LE inventory")?;
+        Ok(())
+    }
+}
+
+// L'ordre des objets compte pour les raccourcis rapides, il se change par glisser-déposer
+#[derive(Deserialize, Serialize, Deref, DerefMut, Clone, Default)]
+pub struct ItemList(Vec<Item>);
+
+impl RawUi for ItemList {
+    fn draw_raw_ui(&mut self, gui: &Gui, ident: &str) {
+        gui.draw_reorderable_vec(ident, &mut self.0);
+    }
 }
 
 #[allow(clippy::upper_case_acronyms)]
-#[derive(Deserialize, Serialize, RawUi, Clone)]
+#[derive(Deserialize, Serialize, RawUi, Clone, Copy, PartialEq, Debug)]
 pub enum ItemLevel {
     None,
     I,
@@ -87,6 +131,22 @@ pub enum ItemLevel {
     X,
 }
 
+impl ItemLevel {
+    pub const ALL: [ItemLevel; 11] = [
+        ItemLevel::None,
+        ItemLevel::I,
+        ItemLevel::II,
+        ItemLevel::III,
+        ItemLevel::IV,
+        ItemLevel::V,
+        ItemLevel::VI,
+        ItemLevel::VII,
+        ItemLevel::VIII,
+        ItemLevel::IX,
+        ItemLevel::X,
+    ];
+}
+
 impl Default for ItemLevel {
     fn default() -> Self {
         ItemLevel::None
@@ -104,6 +164,24 @@ pub struct Item {
     attached_mods: Vec<ItemMod>,
 }
 
+// Critères de l'éditeur d'inventaire, `None` accepte tous les objets
+#[derive(Default, Clone, PartialEq, Debug)]
+pub struct ItemFilter {
+    pub manufacturer_id: Option<i32>,
+    pub item_level: Option<ItemLevel>,
+}
+
+impl ItemFilter {
+    pub fn is_active(&self) -> bool {
+        *self != ItemFilter::default()
+    }
+
+    pub fn matches(&self, item: &Item) -> bool {
+        self.manufacturer_id.map_or(true, |id| item.manufacturer_id == id)
+            && self.item_level.map_or(true, |level| item.item_level == level)
+    }
+}
+
 #[derive(Deserialize, Serialize, RawUi, Clone, Default)]
 struct ItemMod {
     item_id: i32,
@@ -111,3 +189,123 @@ struct ItemMod {
     manufacturer_id: i32,
     _osef: Dummy<4>,
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        event_handler::SaveGame,
+        save_data::{move_item, shared::limits},
+        unreal,
+    };
+
+    use super::*;
+
+    #[test]
+    fn reorder_items() -> Result<()> {
+        let item = |item_id| Item { item_id, ..Default::default() };
+        let mut inventory = Inventory {
+            quick_slots: ItemList(vec![item(1), item(2), item(3)]),
+            ..Default::default()
+        };
+
+        move_item(&mut inventory.quick_slots, 0, 2);
+        let ids: Vec<i32> = inventory.quick_slots.iter().map(|item| item.item_id).collect();
+        assert_eq!(ids, [2, 3, 1]);
+
+        // Le nouvel ordre est celui de la sauvegarde
+        let output = unreal::Serializer::to_byte_buf(&inventory)?;
+        let expected = unreal::Serializer::to_byte_buf(&Inventory {
+            quick_slots: ItemList(vec![item(2), item(3), item(1)]),
+            ..Default::default()
+        })?;
+        assert_eq!(output, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn filter_items() {
+        let item = |item_id, manufacturer_id, item_level| Item {
+            item_id,
+            manufacturer_id,
+            item_level,
+            ..Default::default()
+        };
+        let items = vec![
+            item(1, 10, ItemLevel::I),
+            item(2, 10, ItemLevel::X),
+            item(3, 20, ItemLevel::X),
+            item(4, 10, ItemLevel::X),
+        ];
+        let filtered = |filter: &ItemFilter| -> Vec<i32> {
+            items.iter().filter(|item| filter.matches(item)).map(|item| item.item_id).collect()
+        };
+
+        let mut filter = ItemFilter::default();
+        assert!(!filter.is_active());
+        assert_eq!(filtered(&filter), [1, 2, 3, 4]);
+
+        filter.manufacturer_id = Some(10);
+        assert_eq!(filtered(&filter), [1, 2, 4]);
+        filter.item_level = Some(ItemLevel::X);
+        assert_eq!(filtered(&filter), [2, 4]);
+        filter.manufacturer_id = None;
+        assert_eq!(filtered(&filter), [2, 3, 4]);
+    }
+
+    #[test]
+    fn large_inventory() -> Result<()> {
+        let input = std::fs::read("test/ME1Leg00_QuickSave.pcsav")?;
+        let mut save_game = SaveGame::load(String::new(), &input)?;
+        save_game.self_check()?;
+
+        // Valeurs pseudo-aléatoires pour que la compression ne rattrape pas la taille
+        let mut seed = 0x2545_F491_u32;
+        let mut next = || {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            seed as i32
+        };
+        match &mut save_game {
+            SaveGame::MassEffect1Leg { save_game, .. } => {
+                let inventory = &mut save_game.save_data.player.inventory.inventory;
+                for _ in 0..300_000 {
+                    inventory.push(Item {
+                        item_id: next(),
+                        manufacturer_id: next(),
+                        plot_conditional_id: next(),
+                        ..Default::default()
+                    });
+                }
+            }
+            _ => panic!("not a Mass Effect 1 Legendary save"),
+        }
+
+        let err = save_game.self_check().unwrap_err();
+        assert!(err.is::<limits::SaveTooLarge>());
+        assert!(err.to_string().starts_with("The save would be"));
+        Ok(())
+    }
+
+    #[test]
+    fn inventory_json() -> Result<()> {
+        let input = std::fs::read("test/ME1Leg00_QuickSave.pcsav")?;
+        let save_game = SaveGame::load(String::new(), &input)?;
+        let inventory = match &save_game {
+            SaveGame::MassEffect1Leg { save_game, .. } => &save_game.save_data.player.inventory,
+            _ => panic!("not a Mass Effect 1 Legendary save"),
+        };
+
+        // Relu à l'identique
+        let json = inventory.export_json()?;
+        let mut imported = Inventory::default();
+        imported.import_json(&json)?;
+        assert_eq!(
+            unreal::Serializer::to_byte_buf(&imported)?,
+            unreal::Serializer::to_byte_buf(inventory)?
+        );
+
+        assert!(imported.import_json("{}").is_err());
+        Ok(())
+    }
+}
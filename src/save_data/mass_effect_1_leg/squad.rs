@@ -2,15 +2,15 @@ use serde::{Deserialize, Serialize};
 
 use crate::save_data::{Dummy, ImguiString};
 
-use super::player::{ComplexTalent, Item, SimpleTalent};
+use super::player::{ComplexTalent, ItemList, SimpleTalent};
 
 #[derive(Deserialize, Serialize, RawUi, Default, Clone)]
 pub struct Henchman {
     tag: ImguiString,
     simple_talents: Vec<SimpleTalent>,
     complex_talents: Vec<ComplexTalent>,
-    equipped: Vec<Item>,
-    quick_slots: Vec<Item>,
+    equipped: ItemList,
+    quick_slots: ItemList,
     talent_points: i32,
     _unknown1: Dummy<4>,
     auto_levelup_template_id: i32,
@@ -1,9 +1,14 @@
+use anyhow::Result;
 use indexmap::IndexMap;
 use serde::Deserialize;
+use std::iter;
 
-use crate::save_data::shared::plot::PlotCategory;
+use crate::save_data::shared::plot::{self, PlotCategory, PlotId, PlotNames};
 
-#[derive(Deserialize)]
+// Un par membre de l'équipe, ce sont eux qui comptent pour la mission suicide
+const LOYALTY_IDS: [usize; 12] = [177, 178, 179, 180, 182, 183, 185, 186, 188, 189, 191, 192];
+
+#[derive(Deserialize, Clone)]
 pub struct Me2KnownPlot {
     pub player: PlotCategory,
     pub crew: IndexMap<String, PlotCategory>,
@@ -16,10 +21,72 @@ pub struct Me2KnownPlot {
     pub imported_me1: IndexMap<String, PlotCategory>,
 }
 
+impl Me2KnownPlot {
+    // Les plots importés de ME1 sont dans la table de ME2, comme pour `names`
+    pub fn find(&self, name: &str) -> Result<Option<PlotId>> {
+        let categories = iter::once(&self.player)
+            .chain(self.crew.values())
+            .chain(self.romance.values())
+            .chain(self.missions.values())
+            .chain(self.loyalty_missions.values())
+            .chain(self.research_upgrades.values())
+            .chain(iter::once(&self.rewards))
+            .chain(iter::once(&self.captains_cabin))
+            .chain(self.imported_me1.values());
+        plot::find_unique(categories, name)
+    }
+
+    // (membre de l'équipe, plot de loyauté) dans l'ordre de la base de plots
+    pub fn loyalty(&self) -> Vec<(&str, usize)> {
+        self.crew
+            .iter()
+            .filter_map(|(name, plots)| {
+                let id = plots.booleans.keys().find(|id| LOYALTY_IDS.contains(id))?;
+                Some((name.as_str(), *id))
+            })
+            .collect()
+    }
+
+    // Les plots importés de ME1 sont aussi indexés dans la table de ME2
+    pub fn localize(&mut self, names: &PlotNames) {
+        iter::once(&mut self.player)
+            .chain(self.crew.values_mut())
+            .chain(self.romance.values_mut())
+            .chain(self.missions.values_mut())
+            .chain(self.loyalty_missions.values_mut())
+            .chain(self.research_upgrades.values_mut())
+            .chain(iter::once(&mut self.rewards))
+            .chain(iter::once(&mut self.captains_cabin))
+            .chain(self.imported_me1.values_mut())
+            .for_each(|plots| plots.localize(names));
+    }
+
+    // Toute la table de ME2, plots importés de ME1 compris
+    pub fn names(&self) -> PlotNames {
+        let mut names = PlotNames::default();
+        iter::once(&self.player)
+            .chain(self.crew.values())
+            .chain(self.romance.values())
+            .chain(self.missions.values())
+            .chain(self.loyalty_missions.values())
+            .chain(self.research_upgrades.values())
+            .chain(iter::once(&self.rewards))
+            .chain(iter::once(&self.captains_cabin))
+            .chain(self.imported_me1.values())
+            .for_each(|plots| names.add(plots));
+        names
+    }
+}
+
 #[cfg(test)]
 mod test {
     use anyhow::Result;
-    use std::{fs::File, io::Read};
+    use std::{
+        fs::{self, File},
+        io::Read,
+    };
+
+    use crate::{save_data::mass_effect_2::Me2SaveGame, unreal};
 
     use super::*;
 
@@ -31,8 +98,35 @@ mod test {
             file.read_to_string(&mut input)?;
         }
 
-        let _me2_known_plot: Me2KnownPlot = ron::from_str(&input)?;
+        let me2_known_plot: Me2KnownPlot = ron::from_str(&input)?;
+
+        // Les liens wiki sont optionnels
+        assert!(me2_known_plot.crew["Garrus"].wiki.booleans.contains_key(&200));
+        assert!(me2_known_plot.player.wiki.booleans.is_empty());
+
+        Ok(())
+    }
 
+    #[test]
+    fn squad_loyalty() -> Result<()> {
+        let input = fs::read_to_string("plot/me2_known_plot.ron")?;
+        let me2_known_plot: Me2KnownPlot = ron::from_str(&input)?;
+        let loyalty = me2_known_plot.loyalty();
+        assert_eq!(loyalty.len(), LOYALTY_IDS.len());
+        assert_eq!(loyalty[0], ("Garrus", 183));
+
+        // Seul Garrus change
+        let input = fs::read("test/ME2Save.pcsav")?;
+        let mut me2_save_game: Me2SaveGame = unreal::Deserializer::from_bytes(&input)?;
+        let loyal = me2_save_game.plot.bool_variables[183];
+        me2_save_game.plot.bool_variables.set(183, !loyal);
+
+        let output = unreal::Serializer::to_byte_buf(&me2_save_game)?;
+        let reloaded: Me2SaveGame = unreal::Deserializer::from_bytes(&output)?;
+        assert_eq!(reloaded.plot.bool_variables[183], !loyal);
+        for &(_, id) in &loyalty[1..] {
+            assert_eq!(reloaded.plot.bool_variables[id], me2_save_game.plot.bool_variables[id]);
+        }
         Ok(())
     }
 }
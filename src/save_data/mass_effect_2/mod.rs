@@ -28,9 +28,9 @@ use galaxy_map::*;
 pub struct Me2SaveGame {
     _version: Me2Version,
     debug_name: ImguiString,
-    seconds_played: f32,
+    pub seconds_played: f32,
     disc: i32,
-    base_level_name: ImguiString,
+    pub base_level_name: ImguiString,
     pub difficulty: Difficulty,
     pub end_game_state: EndGameState,
     timestamp: SaveTimeStamp,
@@ -47,7 +47,13 @@ pub struct Me2SaveGame {
     pub plot: PlotTable,
     pub me1_plot: Me1PlotTable,
     galaxy_map: GalaxyMap,
-    dependant_dlcs: Vec<DependentDlc>,
+    pub dependant_dlcs: Vec<DependentDlc>,
+}
+
+impl Me2SaveGame {
+    pub fn version(&self) -> i32 {
+        self._version.0
+    }
 }
 
 #[derive(Serialize, Clone)]
@@ -74,9 +80,9 @@ impl<'de> serde::Deserialize<'de> for Me2Version {
 pub struct Me2LegSaveGame {
     _version: Me2LegVersion,
     debug_name: ImguiString,
-    seconds_played: f32,
+    pub seconds_played: f32,
     disc: i32,
-    base_level_name: ImguiString,
+    pub base_level_name: ImguiString,
     pub difficulty: Difficulty,
     pub end_game_state: EndGameState,
     timestamp: SaveTimeStamp,
@@ -94,7 +100,13 @@ pub struct Me2LegSaveGame {
     pub plot: PlotTable,
     pub me1_plot: Me1PlotTable,
     galaxy_map: GalaxyMap,
-    dependant_dlcs: Vec<DependentDlc>,
+    pub dependant_dlcs: Vec<DependentDlc>,
+}
+
+impl Me2LegSaveGame {
+    pub fn version(&self) -> i32 {
+        self._version.0
+    }
 }
 
 #[derive(Serialize, Clone)]
@@ -127,9 +139,9 @@ pub enum Difficulty {
 }
 
 #[derive(Deserialize, Serialize, RawUi, Default, Clone)]
-struct DependentDlc {
+pub struct DependentDlc {
     id: i32,
-    name: ImguiString,
+    pub name: ImguiString,
 }
 
 #[derive(Deserialize, Serialize, RawUi, Default, Clone)]
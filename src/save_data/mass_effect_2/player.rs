@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use crate::save_data::{
     shared::{
         appearance::Appearance,
+        limits,
         player::{Notoriety, Origin, WeaponLoadout},
     },
     ImguiString,
@@ -12,12 +13,15 @@ use crate::save_data::{
 pub struct Player {
     pub is_female: bool,
     pub class_name: ImguiString,
+    #[range(limits::ME2_LEVEL)]
     pub level: i32,
+    #[range(limits::POSITIVE_F32)]
     pub current_xp: f32,
     pub first_name: ImguiString,
     localized_last_name: i32,
     pub origin: Origin,
     pub notoriety: Notoriety,
+    #[range(limits::POSITIVE)]
     pub talent_points: i32,
     mapped_power_1: ImguiString,
     mapped_power_2: ImguiString,
@@ -27,13 +31,21 @@ pub struct Player {
     weapons: Vec<Weapon>,
     weapons_loadout: WeaponLoadout,
     hotkeys: Vec<Hotkey>,
+    #[range(limits::ME2_CREDITS)]
     pub credits: i32,
+    #[range(limits::POSITIVE)]
     pub medigel: i32,
+    #[range(limits::POSITIVE)]
     pub eezo: i32,
+    #[range(limits::POSITIVE)]
     pub iridium: i32,
+    #[range(limits::POSITIVE)]
     pub palladium: i32,
+    #[range(limits::POSITIVE)]
     pub platinum: i32,
+    #[range(limits::POSITIVE)]
     pub probes: i32,
+    #[range(limits::POSITIVE_F32)]
     pub current_fuel: f32,
     pub face_code: ImguiString,
     class_friendly_name: i32,
@@ -41,8 +53,8 @@ pub struct Player {
 
 #[derive(Deserialize, Serialize, RawUi, Default, Clone)]
 pub struct Power {
-    name: ImguiString,
-    rank: f32,
+    pub name: ImguiString,
+    pub rank: f32,
     pub power_class_name: ImguiString,
     wheel_display_index: i32,
 }
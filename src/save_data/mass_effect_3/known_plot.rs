@@ -1,9 +1,14 @@
+use anyhow::Result;
 use indexmap::IndexMap;
 use serde::Deserialize;
+use std::iter;
 
-use crate::save_data::{mass_effect_1::known_plot::Me1KnownPlot, shared::plot::PlotCategory};
+use crate::save_data::{
+    mass_effect_1::known_plot::Me1KnownPlot,
+    shared::plot::{self, PlotCategory, PlotId, PlotNames},
+};
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Clone)]
 pub struct Me3KnownPlot {
     pub general: PlotCategory,
     pub appearances: IndexMap<String, PlotCategory>,
@@ -17,7 +22,59 @@ pub struct Me3KnownPlot {
     pub me1_imported: Me1KnownPlot,
 }
 
-#[derive(Deserialize)]
+impl Me3KnownPlot {
+    // Sans les plots de ME1 ni les armes / pouvoirs
+    pub fn find(&self, name: &str) -> Result<Option<PlotId>> {
+        let categories = iter::once(&self.general)
+            .chain(self.appearances.values())
+            .chain(self.crew.values())
+            .chain(self.romance.values())
+            .chain(self.missions.values())
+            .chain(self.citadel_dlc.values())
+            .chain(self.normandy.values())
+            .chain(iter::once(&self.intel));
+        plot::find_unique(categories, name)
+    }
+
+    pub fn localize(&mut self, names: &PlotNames) {
+        iter::once(&mut self.general)
+            .chain(self.appearances.values_mut())
+            .chain(self.crew.values_mut())
+            .chain(self.romance.values_mut())
+            .chain(self.missions.values_mut())
+            .chain(self.citadel_dlc.values_mut())
+            .chain(self.normandy.values_mut())
+            .chain(iter::once(&mut self.intel))
+            .for_each(|plots| plots.localize(names));
+        for weapon_power in self.weapons_powers.values_mut() {
+            plot::localize(&mut weapon_power.booleans, &names.booleans);
+        }
+        self.me1_imported.localize(names);
+    }
+
+    // Toute la table de ME3, armes / pouvoirs et plots importés de ME1 compris
+    pub fn names(&self) -> PlotNames {
+        let mut names = PlotNames::default();
+        iter::once(&self.general)
+            .chain(self.appearances.values())
+            .chain(self.crew.values())
+            .chain(self.romance.values())
+            .chain(self.missions.values())
+            .chain(self.citadel_dlc.values())
+            .chain(self.normandy.values())
+            .chain(iter::once(&self.intel))
+            .for_each(|plots| names.add(plots));
+        for weapon_power in self.weapons_powers.values() {
+            plot::add_names(&mut names.booleans, &weapon_power.booleans);
+        }
+        let me1_imported = self.me1_imported.names();
+        plot::add_names(&mut names.booleans, &me1_imported.booleans);
+        plot::add_names(&mut names.ints, &me1_imported.ints);
+        names
+    }
+}
+
+#[derive(Deserialize, Clone)]
 pub struct PlotVariable {
     pub booleans: IndexMap<usize, String>,
     pub variables: IndexMap<String, String>,
@@ -28,6 +85,8 @@ mod test {
     use anyhow::Result;
     use std::{fs::File, io::Read};
 
+    use crate::save_data::mass_effect_3::plot::NEW_GAME_PLUS_COUNT;
+
     use super::*;
 
     #[test]
@@ -38,7 +97,8 @@ mod test {
             file.read_to_string(&mut input)?;
         }
 
-        let _me3_known_plot: Me3KnownPlot = ron::from_str(&input)?;
+        let me3_known_plot: Me3KnownPlot = ron::from_str(&input)?;
+        assert!(me3_known_plot.general.ints.contains_key(&(NEW_GAME_PLUS_COUNT as usize)));
 
         Ok(())
     }
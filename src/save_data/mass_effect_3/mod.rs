@@ -25,9 +25,9 @@ use galaxy_map::*;
 pub struct Me3SaveGame {
     _version: Me3Version,
     debug_name: ImguiString,
-    seconds_played: f32,
+    pub seconds_played: f32,
     disc: i32,
-    base_level_name: ImguiString,
+    pub base_level_name: ImguiString,
     base_level_name_display_override_as_read: ImguiString,
     pub difficulty: Difficulty,
     pub end_game_state: EndGameState,
@@ -47,7 +47,7 @@ pub struct Me3SaveGame {
     _me1_plot: Me1PlotTable,
     pub player_variables: IndexMap<ImguiString, i32>,
     galaxy_map: GalaxyMap,
-    dependant_dlcs: Vec<DependentDlc>,
+    pub dependant_dlcs: Vec<DependentDlc>,
     treasures: Vec<LevelTreasure>,
     use_modules: Vec<Guid>,
     pub conversation_mode: AutoReplyModeOptions,
@@ -55,6 +55,12 @@ pub struct Me3SaveGame {
     saved_objective_text: i32,
 }
 
+impl Me3SaveGame {
+    pub fn version(&self) -> i32 {
+        self._version.0
+    }
+}
+
 #[derive(Serialize, Clone)]
 pub struct Me3Version(i32);
 
@@ -104,9 +110,9 @@ impl Default for PlaceableState {
 }
 
 #[derive(Deserialize, Serialize, RawUi, Default, Clone)]
-struct DependentDlc {
+pub struct DependentDlc {
     id: i32,
-    name: ImguiString,
+    pub name: ImguiString,
     canonical_name: ImguiString,
 }
 
@@ -153,12 +159,13 @@ impl Default for ObjectiveMarkerIconType {
 mod test {
     use anyhow::Result;
     use crc::{Crc, CRC_32_BZIP2};
+    use imgui::ImString;
     use std::{
         time::Instant,
         {fs::File, io::Read},
     };
 
-    use crate::unreal;
+    use crate::{event_handler::SaveGame, unreal};
 
     use super::*;
 
@@ -201,4 +208,133 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn new_game_plus_count() -> Result<()> {
+        let mut input = Vec::new();
+        {
+            let mut file = File::open("test/ME3Save.pcsav")?;
+            file.read_to_end(&mut input)?;
+        }
+
+        let mut me3_save_game: Me3SaveGame = unreal::Deserializer::from_bytes(&input)?;
+        let count = me3_save_game.plot.new_game_plus_count();
+        me3_save_game.plot.set_new_game_plus_count(count + 2);
+
+        let output = unreal::Serializer::to_byte_buf(&me3_save_game)?;
+        let mut me3_save_game: Me3SaveGame = unreal::Deserializer::from_bytes(&output)?;
+        assert_eq!(me3_save_game.plot.new_game_plus_count(), count + 2);
+
+        // Remise à zéro
+        me3_save_game.plot.set_new_game_plus_count(0);
+        let output = unreal::Serializer::to_byte_buf(&me3_save_game)?;
+        let me3_save_game: Me3SaveGame = unreal::Deserializer::from_bytes(&output)?;
+        assert_eq!(me3_save_game.plot.new_game_plus_count(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn plot_json_sorted() -> Result<()> {
+        let mut input = Vec::new();
+        {
+            let mut file = File::open("test/ME3Save.pcsav")?;
+            file.read_to_end(&mut input)?;
+        }
+
+        let mut save_game = SaveGame::load(String::new(), &input)?;
+        let json = save_game.to_json()?;
+
+        // Une clé réinsérée passe à la fin de la map
+        if let SaveGame::MassEffect3 { save_game, .. } = &mut save_game {
+            let int_variables = &mut save_game.plot.int_variables;
+            let (&plot_id, &value) = int_variables.get_index(0).unwrap();
+            int_variables.shift_remove(&plot_id);
+            int_variables.insert(plot_id, value);
+            assert_ne!(int_variables.get_index(0).map(|(id, _)| *id), Some(plot_id));
+        }
+        assert_eq!(save_game.to_json()?, json);
+
+        // Relu dans l'ordre du texte
+        #[derive(serde::Deserialize)]
+        struct Ints {
+            int_variables: IndexMap<i32, i32>,
+        }
+        #[derive(serde::Deserialize)]
+        struct Export {
+            plot: Ints,
+        }
+        let Export { plot: Ints { int_variables } } = serde_json::from_str(&json)?;
+        let ids: Vec<i32> = int_variables.keys().copied().collect();
+        assert!(ids.windows(2).all(|ids| ids[0] < ids[1]));
+
+        // Le binaire suit l'ordre de la map, y compris après un aller-retour JSON
+        let output = save_game.to_bytes()?;
+        assert_ne!(output, input);
+        let mut restored = save_game.clone();
+        restored.set_json_value(save_game.to_json_value()?)?;
+        assert_eq!(restored.to_bytes()?, output);
+        Ok(())
+    }
+
+    #[test]
+    fn first_name_round_trip() -> Result<()> {
+        let mut input = Vec::new();
+        {
+            let mut file = File::open("test/ME3Save.pcsav")?;
+            file.read_to_end(&mut input)?;
+        }
+
+        let mut me3_save_game: Me3SaveGame = unreal::Deserializer::from_bytes(&input)?;
+        let last_name = me3_save_game.player.localized_last_name();
+
+        me3_save_game.player.first_name = ImString::new("Jane").into();
+        let output = unreal::Serializer::to_byte_buf(&me3_save_game)?;
+        let mut me3_save_game: Me3SaveGame = unreal::Deserializer::from_bytes(&output)?;
+        assert_eq!(me3_save_game.player.first_name.to_str(), "Jane");
+        assert_eq!(me3_save_game.player.localized_last_name(), last_name);
+
+        // Latin-1 : 1 octet par caractère, `\0` compris
+        me3_save_game.player.first_name = ImString::new("Zoé").into();
+        let latin1 = unreal::Serializer::to_byte_buf(&me3_save_game)?;
+        assert_eq!(output.len() - latin1.len(), 1);
+        let mut me3_save_game: Me3SaveGame = unreal::Deserializer::from_bytes(&latin1)?;
+        assert_eq!(me3_save_game.player.first_name.to_str(), "Zoé");
+
+        // UTF-16 : longueur négative, 2 octets par caractère
+        me3_save_game.player.first_name = ImString::new("Шепард").into();
+        let utf16 = unreal::Serializer::to_byte_buf(&me3_save_game)?;
+        assert_eq!(utf16.len() - output.len(), (6 + 1) * 2 - (4 + 1));
+        let me3_save_game: Me3SaveGame = unreal::Deserializer::from_bytes(&utf16)?;
+        assert_eq!(me3_save_game.player.first_name.to_str(), "Шепард");
+        assert_eq!(me3_save_game.player.localized_last_name(), last_name);
+        Ok(())
+    }
+
+    #[test]
+    fn power_evolution() -> Result<()> {
+        let mut input = Vec::new();
+        {
+            let mut file = File::open("test/ME3Save.pcsav")?;
+            file.read_to_end(&mut input)?;
+        }
+
+        let mut me3_save_game: Me3SaveGame = unreal::Deserializer::from_bytes(&input)?;
+        let power = me3_save_game.player.powers.iter_mut().find(|power| power.rank >= 6.0).unwrap();
+        let name = power.name.clone();
+        let branch = power.evolution(5).unwrap();
+        power.set_evolution(5, 1 - branch)?;
+
+        // Rangs et branches invalides
+        assert!(power.set_evolution(3, 0).is_err());
+        assert!(power.set_evolution(4, 2).is_err());
+
+        let output = unreal::Serializer::to_byte_buf(&me3_save_game)?;
+        let me3_save_game: Me3SaveGame = unreal::Deserializer::from_bytes(&output)?;
+        let power = me3_save_game.player.powers.iter().find(|power| power.name == name).unwrap();
+        assert_eq!(power.evolution(5), Some(1 - branch));
+        let (left, right) = (power.evolved_choice_2, power.evolved_choice_3);
+        assert_eq!(if branch == 0 { (left, right) } else { (right, left) }, (0, 2));
+        Ok(())
+    }
 }
@@ -1,9 +1,11 @@
+use anyhow::{bail, Result};
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 
 use crate::save_data::{
     shared::{
         appearance::Appearance,
+        limits,
         player::{Notoriety, Origin, WeaponLoadout},
     },
     Dummy, ImguiString,
@@ -16,12 +18,15 @@ pub struct Player {
     is_combat_pawn: bool,
     is_injured_pawn: bool,
     use_casual_appearance: bool,
+    #[range(limits::ME3_LEVEL)]
     pub level: i32,
+    #[range(limits::POSITIVE_F32)]
     pub current_xp: f32,
     pub first_name: ImguiString,
     localized_last_name: i32,
     pub origin: Origin,
     pub notoriety: Notoriety,
+    #[range(limits::POSITIVE)]
     pub talent_points: i32,
     mapped_power_1: ImguiString,
     mapped_power_2: ImguiString,
@@ -38,34 +43,81 @@ pub struct Player {
     loadout_weapon_group: Vec<i32>,
     hotkeys: Vec<Hotkey>,
     current_health: f32,
+    #[range(limits::ME3_CREDITS)]
     pub credits: i32,
+    #[range(limits::POSITIVE)]
     pub medigel: i32,
     eezo: i32,
     iridium: i32,
     palladium: i32,
     platinum: i32,
     probes: i32,
+    #[range(limits::POSITIVE_F32)]
     pub current_fuel: f32,
+    #[range(limits::POSITIVE)]
     pub grenades: i32,
     pub face_code: ImguiString,
     class_friendly_name: i32,
     _character_guid: Dummy<16>,
 }
 
+impl Player {
+    // Id d'une chaîne localisée (TLK), seul le prénom est saisi par le joueur
+    pub fn localized_last_name(&self) -> i32 {
+        self.localized_last_name
+    }
+}
+
 #[derive(Deserialize, Serialize, RawUi, Default, Clone)]
 pub struct Power {
-    name: ImguiString,
-    rank: f32,
-    evolved_choice_0: i32,
-    evolved_choice_1: i32,
-    evolved_choice_2: i32,
-    evolved_choice_3: i32,
-    evolved_choice_4: i32,
-    evolved_choice_5: i32,
+    pub name: ImguiString,
+    pub rank: f32,
+    pub evolved_choice_0: i32,
+    pub evolved_choice_1: i32,
+    pub evolved_choice_2: i32,
+    pub evolved_choice_3: i32,
+    pub evolved_choice_4: i32,
+    pub evolved_choice_5: i32,
     pub power_class_name: ImguiString,
     wheel_display_index: i32,
 }
 
+impl Power {
+    // Rangs 4 à 6 : deux branches par rang, la branche choisie vaut `rang - 3` et l'autre 0
+    pub fn evolution(&self, rank: usize) -> Option<usize> {
+        let branches = match rank {
+            4 => (self.evolved_choice_0, self.evolved_choice_1),
+            5 => (self.evolved_choice_2, self.evolved_choice_3),
+            6 => (self.evolved_choice_4, self.evolved_choice_5),
+            _ => return None,
+        };
+        match branches {
+            (0, 0) => None,
+            (_, 0) => Some(0),
+            _ => Some(1),
+        }
+    }
+
+    pub fn set_evolution(&mut self, rank: usize, branch: usize) -> Result<()> {
+        if branch > 1 {
+            bail!("Rank {} has two evolutions, not {}", rank, branch + 1);
+        }
+        if self.rank < rank as f32 {
+            bail!("Rank {} of `{}` isn't unlocked", rank, self.name);
+        }
+        let (left, right) = match rank {
+            4 => (&mut self.evolved_choice_0, &mut self.evolved_choice_1),
+            5 => (&mut self.evolved_choice_2, &mut self.evolved_choice_3),
+            6 => (&mut self.evolved_choice_4, &mut self.evolved_choice_5),
+            _ => bail!("Only ranks 4 to 6 have evolutions"),
+        };
+        let choice = rank as i32 - 3;
+        *left = if branch == 0 { choice } else { 0 };
+        *right = if branch == 1 { choice } else { 0 };
+        Ok(())
+    }
+}
+
 #[derive(Deserialize, Serialize, RawUi, Default, Clone)]
 pub struct Weapon {
     class_name: ImguiString,
@@ -15,6 +15,19 @@ pub struct PlotTable {
     codex_ids: Vec<i32>,
 }
 
+// Compteur de parties terminées, débloque certains bonus
+pub const NEW_GAME_PLUS_COUNT: i32 = 10475;
+
+impl PlotTable {
+    pub fn new_game_plus_count(&self) -> i32 {
+        self.int_variables.get(&NEW_GAME_PLUS_COUNT).copied().unwrap_or_default()
+    }
+
+    pub fn set_new_game_plus_count(&mut self, count: i32) {
+        self.int_variables.insert(NEW_GAME_PLUS_COUNT, count);
+    }
+}
+
 #[derive(Deserialize, Serialize, RawUi, Clone)]
 pub struct Me1PlotTable {
     bool_variables: BoolVec,
@@ -0,0 +1,144 @@
+use std::ops::RangeInclusive;
+
+use super::{
+    mass_effect_1_leg::player::Player as Me1LegPlayer, mass_effect_2::player::Player as Me2Player,
+    mass_effect_3::player::Player as Me3Player, shared::limits,
+};
+
+// Chaque valeur repasse par les mêmes limites que les éditeurs pour rester valide
+// Les niveaux gagnés sont convertis en points de talent, les talents déjà pris sont conservés
+
+// Quantités visées par « Max character », choisies par l'éditeur et non tirées du jeu :
+// aucune limite connue pour ces champs, les éditeurs les bornent seulement à `limits::POSITIVE`
+const MAX_MEDIGEL: i32 = 10;
+const MAX_GRENADES: i32 = 10;
+const ME1_MAX_OMNIGEL: f32 = 999.0;
+const ME2_MAX_RESOURCES: i32 = 999_999;
+const ME2_MAX_PROBES: i32 = 30;
+const MAX_FUEL: f32 = 1_000.0;
+
+pub fn me1_leg(player: &mut Me1LegPlayer) {
+    level_up(
+        &mut player.level,
+        &mut player.talent_points,
+        limits::ME1_LEVEL,
+        limits::ME1_TALENT_POINTS_PER_LEVEL,
+    );
+    player.current_xp = 0.0;
+
+    player.credits = max_i32(player.credits, *limits::ME1_CREDITS.end(), limits::ME1_CREDITS);
+    player.medigel = max_i32(player.medigel, MAX_MEDIGEL, limits::POSITIVE);
+    player.grenades = max_f32(player.grenades, MAX_GRENADES as f32);
+    player.omnigel = max_f32(player.omnigel, ME1_MAX_OMNIGEL);
+}
+
+pub fn me2(player: &mut Me2Player) {
+    level_up(
+        &mut player.level,
+        &mut player.talent_points,
+        limits::ME2_LEVEL,
+        limits::ME2_TALENT_POINTS_PER_LEVEL,
+    );
+    player.current_xp = 0.0;
+
+    player.credits = max_i32(player.credits, *limits::ME2_CREDITS.end(), limits::ME2_CREDITS);
+    player.medigel = max_i32(player.medigel, MAX_MEDIGEL, limits::POSITIVE);
+    player.eezo = max_i32(player.eezo, ME2_MAX_RESOURCES, limits::POSITIVE);
+    player.iridium = max_i32(player.iridium, ME2_MAX_RESOURCES, limits::POSITIVE);
+    player.palladium = max_i32(player.palladium, ME2_MAX_RESOURCES, limits::POSITIVE);
+    player.platinum = max_i32(player.platinum, ME2_MAX_RESOURCES, limits::POSITIVE);
+    player.probes = max_i32(player.probes, ME2_MAX_PROBES, limits::POSITIVE);
+    player.current_fuel = max_f32(player.current_fuel, MAX_FUEL);
+}
+
+pub fn me3(player: &mut Me3Player) {
+    level_up(
+        &mut player.level,
+        &mut player.talent_points,
+        limits::ME3_LEVEL,
+        limits::ME3_TALENT_POINTS_PER_LEVEL,
+    );
+    player.current_xp = 0.0;
+
+    player.credits = max_i32(player.credits, *limits::ME3_CREDITS.end(), limits::ME3_CREDITS);
+    player.medigel = max_i32(player.medigel, MAX_MEDIGEL, limits::POSITIVE);
+    player.grenades = max_i32(player.grenades, MAX_GRENADES, limits::POSITIVE);
+    player.current_fuel = max_f32(player.current_fuel, MAX_FUEL);
+}
+
+fn level_up(
+    level: &mut i32, talent_points: &mut i32, range: RangeInclusive<i32>, points_per_level: i32,
+) {
+    limits::clamp(level, &range);
+    limits::clamp(talent_points, &limits::POSITIVE);
+
+    let gained = range.end() - *level;
+    *level = *range.end();
+    *talent_points = talent_points.saturating_add(gained * points_per_level);
+}
+
+// Ne diminue jamais une valeur déjà plus haute
+fn max_i32(value: i32, max: i32, range: RangeInclusive<i32>) -> i32 {
+    let mut value = value.max(max);
+    limits::clamp(&mut value, &range);
+    value
+}
+
+fn max_f32(value: f32, max: f32) -> f32 {
+    let mut value = value.max(max);
+    limits::clamp(&mut value, &limits::POSITIVE_F32);
+    value
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::Result;
+    use std::{fs::File, io::Read};
+
+    use crate::{save_data::mass_effect_2::Me2SaveGame, unreal};
+
+    use super::*;
+
+    #[test]
+    fn me2_max_character() -> Result<()> {
+        let mut input = Vec::new();
+        {
+            let mut file = File::open("test/ME2Save.pcsav")?;
+            file.read_to_end(&mut input)?;
+        }
+
+        let mut save_game: Me2SaveGame = unreal::Deserializer::from_bytes(&input)?;
+        let level = save_game.player.level;
+        let talent_points = save_game.player.talent_points;
+        me2(&mut save_game.player);
+
+        // Toujours lisible
+        let output = unreal::Serializer::to_byte_buf(&save_game)?;
+        let save_game: Me2SaveGame = unreal::Deserializer::from_bytes(&output)?;
+        let player = &save_game.player;
+
+        assert_eq!(player.level, 30);
+        assert_eq!(
+            player.talent_points,
+            talent_points.max(0) + (30 - level.max(1)) * limits::ME2_TALENT_POINTS_PER_LEVEL
+        );
+        assert_eq!(player.credits, *limits::ME2_CREDITS.end());
+        assert!(player.medigel >= MAX_MEDIGEL);
+        assert!(player.eezo >= ME2_MAX_RESOURCES);
+        assert!(player.probes >= ME2_MAX_PROBES);
+        assert!(player.current_fuel >= MAX_FUEL);
+        Ok(())
+    }
+
+    #[test]
+    fn never_lowers() {
+        assert_eq!(max_i32(5, 10, limits::POSITIVE), 10);
+        assert_eq!(max_i32(50, 10, limits::POSITIVE), 50);
+        assert_eq!(max_i32(-5, 10, limits::POSITIVE), 10);
+        assert_eq!(max_i32(i32::MAX, 10, limits::ME1_CREDITS), 9_999_999);
+
+        let (mut level, mut talent_points) = (0, -3);
+        level_up(&mut level, &mut talent_points, limits::ME2_LEVEL, 2);
+        assert_eq!((level, talent_points), (30, 58));
+    }
+}
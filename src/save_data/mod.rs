@@ -6,19 +6,30 @@ use serde::{de, ser::SerializeSeq, Serialize};
 use std::{
     fmt::{self, Display},
     hash::Hash,
+    ops::RangeInclusive,
 };
 
 use crate::gui::Gui;
 
+pub mod build;
+pub mod carry_forward;
 pub mod mass_effect_1;
 pub mod mass_effect_1_leg;
 pub mod mass_effect_2;
 pub mod mass_effect_3;
+pub mod max_character;
+pub mod new_character;
 pub mod shared;
+pub mod summary;
 
 // Raw Ui
 pub trait RawUi {
     fn draw_raw_ui(&mut self, gui: &Gui, ident: &str);
+
+    // Champs `#[range(...)]` hors limites de cette struct, (nom du champ, message)
+    fn range_errors(&self) -> Vec<(&'static str, String)> {
+        Vec::new()
+    }
 }
 
 // Nouveau string type pour pouvoir implémenter serde...
@@ -70,9 +81,10 @@ impl<'de, const LEN: usize> serde::Deserialize<'de> for Dummy<LEN> {
             type Value = Dummy<LEN>;
 
             fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-                formatter.write_str("a seq")
+                write!(formatter, "a seq of {} bytes", LEN)
             }
 
+            // Un JSON modifié peut avoir n'importe quelle longueur
             fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
             where
                 A: de::SeqAccess<'de>,
@@ -80,9 +92,15 @@ impl<'de, const LEN: usize> serde::Deserialize<'de> for Dummy<LEN> {
                 let mut result = [0u8; LEN];
                 let mut i = 0;
                 while let Some(element) = seq.next_element()? {
+                    if i == LEN {
+                        return Err(de::Error::invalid_length(i + 1, &self));
+                    }
                     result[i] = element;
                     i += 1;
                 }
+                if i < LEN {
+                    return Err(de::Error::invalid_length(i, &self));
+                }
                 Ok(Dummy(result))
             }
         }
@@ -133,6 +151,18 @@ impl<'de> serde::Deserialize<'de> for List<u8> {
             {
                 Ok(List(v))
             }
+
+            // Relu depuis du JSON
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::SeqAccess<'de>,
+            {
+                let mut bytes = Vec::new();
+                while let Some(byte) = seq.next_element()? {
+                    bytes.push(byte);
+                }
+                Ok(List(bytes))
+            }
         }
         deserializer.deserialize_byte_buf(ByteListVisitor)
     }
@@ -154,6 +184,29 @@ where
     }
 }
 
+// Déplace un élément d'une liste, les suivants se décalent
+pub fn move_item<T>(list: &mut Vec<T>, from: usize, to: usize) {
+    if from < list.len() && to < list.len() {
+        let item = list.remove(from);
+        list.insert(to, item);
+    }
+}
+
+// Champ dont le jeu n'accepte qu'une plage de valeurs, voir `#[range(...)]`
+pub struct Ranged<'a, T>(pub &'a mut T, pub RangeInclusive<T>);
+
+impl RawUi for Ranged<'_, i32> {
+    fn draw_raw_ui(&mut self, gui: &Gui, ident: &str) {
+        gui.draw_edit_i32_clamped(ident, self.0, self.1.clone());
+    }
+}
+
+impl RawUi for Ranged<'_, f32> {
+    fn draw_raw_ui(&mut self, gui: &Gui, ident: &str) {
+        gui.draw_edit_f32_clamped(ident, self.0, self.1.clone());
+    }
+}
+
 // Implémentation des types std
 impl RawUi for i32 {
     fn draw_raw_ui(&mut self, gui: &Gui, ident: &str) {
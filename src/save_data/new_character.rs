@@ -0,0 +1,109 @@
+use anyhow::{Context, Result};
+use imgui::ImString;
+
+use crate::event_handler::SaveGame;
+
+use super::{
+    mass_effect_1_leg::player::Inventory,
+    shared::{
+        limits,
+        player::{Notoriety, Origin},
+    },
+};
+
+// Sauvegarde de référence, remise en début de partie puis les choix du joueur sont appliqués
+// par dessus. La classe reste celle de la référence, elle dépend aussi des talents
+const ME1_LEG_TEMPLATE: &[u8] = include_bytes!("../../test/ME1Leg00_QuickSave.pcsav");
+
+pub struct NewCharacter {
+    pub first_name: String,
+    pub is_female: bool,
+    pub origin: Origin,
+    pub notoriety: Notoriety,
+}
+
+pub fn me1_leg(file_path: String, character: &NewCharacter) -> Result<SaveGame> {
+    limits::check_name(&character.first_name).context("Invalid character name")?;
+
+    let mut new_save = SaveGame::load(file_path, ME1_LEG_TEMPLATE)?;
+    if let SaveGame::MassEffect1Leg { save_game, .. } = &mut new_save {
+        let save_data = &mut save_game.save_data;
+        save_data.seconds_played = 0;
+
+        // Ni progression ni objets de la référence
+        let plot = &mut save_data.plot;
+        plot.bool_variables.set_all(false);
+        plot.int_variables.iter_mut().for_each(|int| *int = 0);
+        plot.float_variables.iter_mut().for_each(|float| *float = 0.0);
+
+        let player = &mut save_data.player;
+        player.level = 1;
+        player.current_xp = 0.0;
+        player.credits = 0;
+        player.medigel = 0;
+        player.grenades = 0.0;
+        player.omnigel = 0.0;
+        player.inventory = Inventory::default();
+
+        player.first_name = ImString::new(&character.first_name).into();
+        player.is_female = character.is_female;
+        player.origin = character.origin.clone();
+        player.notoriety = character.notoriety.clone();
+
+        // Repris par les plots comme dans l'éditeur
+        let ints = &mut save_data.plot.int_variables;
+        if let Some(origin) = ints.get_mut(1) {
+            *origin = character.origin.clone() as i32;
+        }
+        if let Some(notoriety) = ints.get_mut(2) {
+            *notoriety = character.notoriety.clone() as i32;
+        }
+    }
+    Ok(new_save)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::event_handler::GameVersion;
+
+    use super::*;
+
+    #[test]
+    fn new_me1_leg_character() -> Result<()> {
+        let character = NewCharacter {
+            first_name: "Jane".to_owned(),
+            is_female: true,
+            origin: Origin::Colonist,
+            notoriety: Notoriety::Ruthless,
+        };
+        let new_save = me1_leg("Jane00_QuickSave.pcsav".to_owned(), &character)?;
+        assert_eq!(new_save.game_version(), GameVersion::MassEffect1Leg);
+
+        // Relue telle quelle
+        let output = new_save.self_check()?;
+        let reloaded = SaveGame::load("Jane00_QuickSave.pcsav".to_owned(), &output)?;
+        match reloaded {
+            SaveGame::MassEffect1Leg { mut save_game, .. } => {
+                // Début de partie
+                let plot = &save_game.save_data.plot;
+                assert!(plot.bool_variables.not_any());
+                assert!(plot.int_variables.iter().skip(3).all(|&int| int == 0));
+                assert_eq!(save_game.save_data.player.level, 1);
+                assert_eq!(save_game.save_data.player.credits, 0);
+                let inventory = &mut save_game.save_data.player.inventory;
+                assert!(inventory.lists_mut().iter().all(|(_, list)| list.is_empty()));
+
+                let player = &save_game.save_data.player;
+                assert_eq!(player.first_name.to_str(), "Jane");
+                assert!(player.is_female);
+                assert_eq!(player.origin.clone() as usize, Origin::Colonist as usize);
+                assert_eq!(save_game.save_data.plot.int_variables[1], Origin::Colonist as i32);
+            }
+            _ => panic!("expected a ME1 LE save"),
+        }
+
+        let invalid = NewCharacter { first_name: String::new(), ..character };
+        assert!(me1_leg("Jane00_QuickSave.pcsav".to_owned(), &invalid).is_err());
+        Ok(())
+    }
+}
@@ -1,5 +1,6 @@
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
 use indexmap::IndexMap;
+use ron::ser::PrettyConfig;
 use serde::{
     de,
     ser::{Error, SerializeStruct, SerializeTupleStruct},
@@ -64,7 +65,7 @@ impl<'de> serde::Deserialize<'de> for HasHeadMorph {
             type Value = HasHeadMorph;
 
             fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-                formatter.write_str("a seq")
+                formatter.write_str("a seq or a map")
             }
 
             fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
@@ -76,8 +77,33 @@ impl<'de> serde::Deserialize<'de> for HasHeadMorph {
                     if has_head_morph { Some(seq.next_element()?.unwrap()) } else { None };
                 Ok(HasHeadMorph { has_head_morph, head_morph })
             }
+
+            // Forme sérialisée en JSON
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::MapAccess<'de>,
+            {
+                let mut has_head_morph = None;
+                let mut head_morph = None;
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "has_head_morph" => has_head_morph = Some(map.next_value()?),
+                        "head_morph" => head_morph = Some(map.next_value()?),
+                        _ => {
+                            map.next_value::<de::IgnoredAny>()?;
+                        }
+                    }
+                }
+                let has_head_morph =
+                    has_head_morph.ok_or_else(|| de::Error::missing_field("has_head_morph"))?;
+                Ok(HasHeadMorph { has_head_morph, head_morph })
+            }
         }
-        deserializer.deserialize_tuple_struct("HasHeadMorph", 2, HasHeadMorphVisitor)
+        deserializer.deserialize_struct(
+            "HasHeadMorph",
+            &["has_head_morph", "head_morph"],
+            HasHeadMorphVisitor,
+        )
     }
 }
 
@@ -113,6 +139,172 @@ pub struct HeadMorph {
     pub texture_parameters: IndexMap<ImguiString, ImguiString>,
 }
 
+impl HeadMorph {
+    // Par libellé de `BONE_LABELS`, ou par nom d'origine pour les os absents de la table
+    pub fn offset_bone_mut(&mut self, label: &str) -> Option<&mut Vector> {
+        self.offset_bones
+            .iter_mut()
+            .find(|(bone, _)| bone_label(bone.to_str()).unwrap_or_else(|| bone.to_str()) == label)
+            .map(|(_, offset)| offset)
+    }
+
+    // Format texte à éditer à la main : champs nommés, index des vertices en commentaire
+    pub fn to_ron(&self) -> Result<String> {
+        let pretty_config =
+            PrettyConfig::new().with_enumerate_arrays(true).with_new_line(String::from('\n'));
+        Ok(ron::ser::to_string_pretty(self, pretty_config)?)
+    }
+
+    // Le modèle de tête n'est pas connu ici, des LODs différents ne donnent que des
+    // `lod_warnings` à l'import dans la sauvegarde
+    pub fn from_ron(input: &str) -> Result<Self> {
+        let head_morph: HeadMorph = ron::from_str(input).context("Not a head morph")?;
+        head_morph.validate()?;
+        Ok(head_morph)
+    }
+
+    // Palette : une couleur `r g b a` par ligne, dans l'ordre des paramètres
+    pub fn export_palette(&self) -> String {
+        self.vector_parameters
+            .iter()
+            .map(|(name, LinearColor([r, g, b, a]))| {
+                format!("{} {} {} {} # {}\n", r, g, b, a, name)
+            })
+            .collect()
+    }
+
+    pub fn import_palette(&mut self, input: &str) -> Result<()> {
+        let colors = parse_palette(input)?;
+        if colors.len() != self.vector_parameters.len() {
+            bail!(
+                "The palette has {} colors, the head morph has {} color slots",
+                colors.len(),
+                self.vector_parameters.len()
+            );
+        }
+
+        for (color, LinearColor(slot)) in
+            colors.into_iter().zip(self.vector_parameters.values_mut())
+        {
+            *slot = color;
+        }
+        Ok(())
+    }
+
+    // D'un autre modèle de tête les LODs n'ont pas le même nombre de sommets, ça peut être
+    // voulu (autre espèce, autre sexe) mais le visage risque de s'afficher cassé
+    pub fn lod_warnings(&self, current: &HeadMorph) -> Vec<String> {
+        self.lods()
+            .iter()
+            .zip(current.lods().iter())
+            .enumerate()
+            .filter(|(_, (lod, current_lod))| lod.len() != current_lod.len())
+            .map(|(i, (lod, current_lod))| {
+                format!(
+                    "LOD{} has {} vertices, the current head has {}",
+                    i,
+                    lod.len(),
+                    current_lod.len()
+                )
+            })
+            .collect()
+    }
+
+    // Les valeurs infinies font planter le jeu
+    pub fn validate(&self) -> Result<()> {
+        let lods = self.lods();
+        for (i, lod) in lods.iter().enumerate() {
+            if let Some(idx) = lod.iter().position(|vertex| !vertex.is_finite()) {
+                bail!("Invalid vertex {} in LOD{}", idx, i);
+            }
+        }
+        if let Some((name, _)) = self.offset_bones.iter().find(|(_, bone)| !bone.is_finite()) {
+            bail!("Invalid offset bone `{}`", name);
+        }
+        let mut scalars = self.morph_features.iter().chain(&self.scalar_parameters);
+        if let Some((name, _)) = scalars.find(|(_, value)| !value.is_finite()) {
+            bail!("Invalid value for `{}`", name);
+        }
+        let mut colors = self.vector_parameters.iter();
+        if let Some((name, _)) =
+            colors.find(|(_, LinearColor(color))| color.iter().any(|c| !c.is_finite()))
+        {
+            bail!("Invalid color for `{}`", name);
+        }
+        Ok(())
+    }
+
+    fn lods(&self) -> [&Vec<Vector>; 4] {
+        [&self.lod0_vertices, &self.lod1_vertices, &self.lod2_vertices, &self.lod3_vertices]
+    }
+}
+
+// Os du visage communs aux têtes de ME2 et ME3
+const BONE_LABELS: [(&str, &str); 33] = [
+    ("headBase", "Head"),
+    ("brow_Left", "Brow (left)"),
+    ("brow_right", "Brow (right)"),
+    ("outBrow_left", "Outer brow (left)"),
+    ("outBrow_Right", "Outer brow (right)"),
+    ("Eye_Left", "Eye (left)"),
+    ("Eye_Right", "Eye (right)"),
+    ("eyeBlink_Left", "Upper eyelid (left)"),
+    ("eyeBlink_Right", "Upper eyelid (right)"),
+    ("lowLid_Left", "Lower eyelid (left)"),
+    ("lowLid_Right", "Lower eyelid (right)"),
+    ("underEye_left", "Under eye (left)"),
+    ("underEye_Right", "Under eye (right)"),
+    ("cheek_Left", "Cheek (left)"),
+    ("cheek_right", "Cheek (right)"),
+    ("LowerCheek_left", "Lower cheek (left)"),
+    ("lowerCheek_right", "Lower cheek (right)"),
+    ("jawBone", "Jaw"),
+    ("mouthBase", "Mouth"),
+    ("upperLip_Left", "Upper lip (left)"),
+    ("upperLip_right", "Upper lip (right)"),
+    ("innerUpperLip_Left", "Inner upper lip (left)"),
+    ("innerUpperLip_right", "Inner upper lip (right)"),
+    ("outerUpperLip_left", "Outer upper lip (left)"),
+    ("outerUpperLip_right", "Outer upper lip (right)"),
+    ("lowerLip_Left", "Lower lip (left)"),
+    ("lowerLip_right", "Lower lip (right)"),
+    ("innerLowLip_left", "Inner lower lip (left)"),
+    ("innerLowLip_right", "Inner lower lip (right)"),
+    ("LipCorner_Left", "Lip corner (left)"),
+    ("LipCorner_right", "Lip corner (right)"),
+    ("Tongue", "Tongue"),
+    ("Sneer", "Sneer"),
+];
+
+// La casse varie d'un os à l'autre dans les fichiers du jeu
+pub fn bone_label(bone: &str) -> Option<&'static str> {
+    BONE_LABELS.iter().find(|(name, _)| unicase::eq(*name, bone)).map(|(_, label)| *label)
+}
+
+// `#` pour les commentaires, valeurs séparées par des espaces ou des virgules
+fn parse_palette(input: &str) -> Result<Vec<[f32; 4]>> {
+    let mut colors = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let values = line
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|value| !value.is_empty())
+            .map(str::parse)
+            .collect::<Result<Vec<f32>, _>>()
+            .with_context(|| format!("Invalid color at line {}", i + 1))?;
+
+        match values.as_slice() {
+            [r, g, b, a] => colors.push([*r, *g, *b, *a]),
+            _ => bail!("Invalid color at line {}, expected `r g b a`", i + 1),
+        }
+    }
+    Ok(colors)
+}
+
 #[derive(Default, Clone)]
 pub struct LinearColor([f32; 4]);
 
@@ -147,3 +339,139 @@ impl serde::Serialize for LinearColor {
         linear_color.end()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use imgui::ImString;
+    use std::fs;
+
+    use crate::{save_data::mass_effect_3::Me3SaveGame, unreal};
+
+    use super::*;
+
+    fn head_morph() -> HeadMorph {
+        let mut vector_parameters = IndexMap::new();
+        vector_parameters
+            .insert(ImString::new("SkinTone").into(), LinearColor([0.5, 0.4, 0.3, 1.0]));
+        vector_parameters
+            .insert(ImString::new("HairColor").into(), LinearColor([0.1, 0.05, 0.0, 1.0]));
+        vector_parameters
+            .insert(ImString::new("EyeColor").into(), LinearColor([0.2, 0.6, 0.9, 0.5]));
+
+        HeadMorph {
+            hair_mesh: Default::default(),
+            accessory_mesh: Vec::new(),
+            morph_features: IndexMap::new(),
+            offset_bones: IndexMap::new(),
+            lod0_vertices: Vec::new(),
+            lod1_vertices: Vec::new(),
+            lod2_vertices: Vec::new(),
+            lod3_vertices: Vec::new(),
+            scalar_parameters: IndexMap::new(),
+            vector_parameters,
+            texture_parameters: IndexMap::new(),
+        }
+    }
+
+    #[test]
+    fn palette_round_trip() -> Result<()> {
+        let original = head_morph();
+        let palette = original.export_palette();
+
+        let mut head_morph = head_morph();
+        for LinearColor(color) in head_morph.vector_parameters.values_mut() {
+            *color = [0.0; 4];
+        }
+        head_morph.import_palette(&palette)?;
+
+        assert_eq!(head_morph.export_palette(), palette);
+
+        // Commentaires et virgules
+        head_morph.import_palette("# Ma palette\n1, 0, 0, 1\n\n0 1 0 1 # vert\n0,0,1,1")?;
+        assert_eq!(
+            head_morph.export_palette(),
+            "1 0 0 1 # SkinTone\n0 1 0 1 # HairColor\n0 0 1 1 # EyeColor\n"
+        );
+
+        // Mauvais nombre de couleurs
+        let err = head_morph.import_palette("1 0 0 1").unwrap_err();
+        assert_eq!(err.to_string(), "The palette has 1 colors, the head morph has 3 color slots");
+        let err = head_morph.import_palette("1 0 0\n0 1 0 1\n0 0 1 1").unwrap_err();
+        assert_eq!(err.to_string(), "Invalid color at line 1, expected `r g b a`");
+        assert!(head_morph.import_palette("a b c d\n0 1 0 1\n0 0 1 1").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn ron_round_trip() -> Result<()> {
+        let input = fs::read("test/ME3Save.pcsav")?;
+        let me3_save_game: Me3SaveGame = unreal::Deserializer::from_bytes(&input)?;
+        let original = me3_save_game.player.appearance.head_morph.head_morph.unwrap();
+        let text = original.to_ron()?;
+        assert!(text.contains("morph_features: {"));
+
+        // Mêmes octets qu'avant l'export
+        let head_morph = HeadMorph::from_ron(&text)?;
+        assert_eq!(
+            unreal::Serializer::to_byte_buf(&head_morph)?,
+            unreal::Serializer::to_byte_buf(&original)?
+        );
+
+        // Valeur modifiée à la main
+        let (feature, value) = original.morph_features.iter().next().unwrap();
+        let mut edited = original.clone();
+        edited.morph_features.insert(feature.clone(), value + 0.25);
+        let head_morph = HeadMorph::from_ron(&edited.to_ron()?)?;
+        assert_eq!(head_morph.morph_features[feature], value + 0.25);
+
+        let err = HeadMorph::from_ron("(hair_mesh: \"\")").unwrap_err();
+        assert_eq!(err.to_string(), "Not a head morph");
+        Ok(())
+    }
+
+    #[test]
+    fn named_bones() -> Result<()> {
+        let mut head_morph = head_morph();
+        for bone in &["headBase", "JAWBONE", "chin_custom"] {
+            head_morph.offset_bones.insert(ImString::new(*bone).into(), Vector::default());
+        }
+        assert_eq!(bone_label("jawBone"), Some("Jaw"));
+        assert_eq!(bone_label("chin_custom"), None);
+
+        // La casse du fichier n'empêche pas le libellé
+        head_morph.offset_bone_mut("Jaw").unwrap().y = 1.5;
+        head_morph.offset_bone_mut("chin_custom").unwrap().z = -2.0;
+        assert!(head_morph.offset_bone_mut("jawBone").is_none());
+
+        let output = unreal::Serializer::to_byte_buf(&head_morph)?;
+        let head_morph: HeadMorph = unreal::Deserializer::from_bytes(&output)?;
+        let offsets: Vec<_> =
+            head_morph.offset_bones.values().map(|Vector { x, y, z }| [*x, *y, *z]).collect();
+        assert_eq!(offsets, [[0.0, 0.0, 0.0], [0.0, 1.5, 0.0], [0.0, 0.0, -2.0]]);
+        Ok(())
+    }
+
+    #[test]
+    fn validate() -> Result<()> {
+        let mut current = head_morph();
+        current.lod0_vertices = vec![Vector::default(); 3];
+        let mut head_morph = head_morph();
+        head_morph.lod0_vertices = vec![Vector::default(); 3];
+        head_morph.validate()?;
+        assert!(head_morph.lod_warnings(&current).is_empty());
+
+        // Pas le même modèle de tête, seulement un avertissement
+        head_morph.lod0_vertices.pop();
+        assert_eq!(
+            head_morph.lod_warnings(&current),
+            ["LOD0 has 2 vertices, the current head has 3"]
+        );
+        head_morph.validate()?;
+
+        // Valeur hors limites
+        head_morph.morph_features.insert(ImString::new("JawWidth").into(), f32::NAN);
+        let err = head_morph.validate().unwrap_err();
+        assert_eq!(err.to_string(), "Invalid value for `JawWidth`");
+        Ok(())
+    }
+}
@@ -0,0 +1,71 @@
+// Comparaison octet par octet, pour les parties du format qu'on ne connaît pas encore
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ByteChange {
+    pub offset: usize,
+    pub before: Vec<u8>,
+    pub after: Vec<u8>,
+}
+
+// Les octets modifiés contigus sont regroupés, une différence de taille donne un dernier
+// changement avec un côté vide
+pub fn byte_diff(before: &[u8], after: &[u8]) -> Vec<ByteChange> {
+    let mut changes: Vec<ByteChange> = Vec::new();
+
+    let common = before.len().min(after.len());
+    for offset in 0..common {
+        if before[offset] == after[offset] {
+            continue;
+        }
+        match changes.last_mut() {
+            Some(change) if change.offset + change.before.len() == offset => {
+                change.before.push(before[offset]);
+                change.after.push(after[offset]);
+            }
+            _ => changes.push(ByteChange {
+                offset,
+                before: vec![before[offset]],
+                after: vec![after[offset]],
+            }),
+        }
+    }
+
+    if before.len() != after.len() {
+        changes.push(ByteChange {
+            offset: common,
+            before: before[common..].to_vec(),
+            after: after[common..].to_vec(),
+        });
+    }
+    changes
+}
+
+// `0A FF 12`
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02X}", byte)).collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn single_byte() {
+        let before = vec![0u8; 64];
+        let mut after = before.clone();
+        after[42] = 0xFF;
+
+        assert_eq!(
+            byte_diff(&before, &after),
+            vec![ByteChange { offset: 42, before: vec![0x00], after: vec![0xFF] }]
+        );
+        assert!(byte_diff(&before, &before).is_empty());
+
+        // Contigus puis taille différente
+        after[43] = 0x01;
+        after.push(0x12);
+        let changes = byte_diff(&before, &after);
+        assert_eq!(changes.len(), 2);
+        assert_eq!(hex(&changes[0].after), "FF 01");
+        assert_eq!(changes[1], ByteChange { offset: 64, before: vec![], after: vec![0x12] });
+    }
+}
@@ -0,0 +1,196 @@
+use anyhow::{bail, Result};
+use std::{
+    error,
+    fmt::{self, Display},
+    ops::RangeInclusive,
+};
+
+// Limites des jeux, au-delà le jeu affiche n'importe quoi ou plante
+pub const ME1_LEVEL: RangeInclusive<i32> = 1..=60;
+pub const ME2_LEVEL: RangeInclusive<i32> = 1..=30;
+pub const ME3_LEVEL: RangeInclusive<i32> = 1..=60;
+
+pub const ME1_CREDITS: RangeInclusive<i32> = 0..=9_999_999;
+pub const ME2_CREDITS: RangeInclusive<i32> = 0..=999_999_999;
+pub const ME3_CREDITS: RangeInclusive<i32> = 0..=999_999_999;
+
+// Ressources, talents, etc. : seulement positif
+pub const POSITIVE: RangeInclusive<i32> = 0..=i32::MAX;
+pub const POSITIVE_F32: RangeInclusive<f32> = 0.0..=f32::MAX;
+
+// Points de talent gagnés par niveau
+pub const ME1_TALENT_POINTS_PER_LEVEL: i32 = 2;
+pub const ME2_TALENT_POINTS_PER_LEVEL: i32 = 2;
+pub const ME3_TALENT_POINTS_PER_LEVEL: i32 = 2;
+
+// Un nom trop long déborde de l'interface du jeu
+pub const NAME_LEN: RangeInclusive<usize> = 1..=20;
+
+pub fn check_name(name: &str) -> Result<()> {
+    let len = name.chars().count();
+    if !NAME_LEN.contains(&len) {
+        bail!(
+            "The name must be {} to {} characters long, not {}",
+            NAME_LEN.start(),
+            NAME_LEN.end(),
+            len
+        );
+    }
+    Ok(())
+}
+
+// Pas de limite documentée par BioWare : les sauvegardes de `test/` font de 62 Ko (ME2) à 140 Ko
+// (ME1), 2 Mo laisse une marge de plus de 10 fois la plus grosse
+pub const SAVE_LEN_WARNING: usize = 2 * 1024 * 1024;
+
+// Taille projetée trop grande, l'UI propose d'enregistrer quand même
+#[derive(Debug)]
+pub struct SaveTooLarge {
+    pub len: usize,
+}
+
+impl fmt::Display for SaveTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        const MB: f64 = 1024.0 * 1024.0;
+        write!(
+            f,
+            "The save would be {:.1} MB, the game may not load a save larger than {} MB",
+            self.len as f64 / MB,
+            SAVE_LEN_WARNING as f64 / MB
+        )
+    }
+}
+
+impl error::Error for SaveTooLarge {}
+
+pub fn check_save_len(len: usize) -> Result<()> {
+    if len > SAVE_LEN_WARNING {
+        return Err(SaveTooLarge { len }.into());
+    }
+    Ok(())
+}
+
+// Message affiché sur un champ hors limites, `None` s'il est valide
+pub fn range_error<T>(value: &T, range: &RangeInclusive<T>) -> Option<String>
+where
+    T: PartialOrd + Display,
+{
+    if range.contains(value) {
+        None
+    } else {
+        Some(format!(
+            "Expected {} to {}, the game may not handle {}",
+            range.start(),
+            range.end(),
+            value
+        ))
+    }
+}
+
+// Ramène la valeur dans les limites, `true` si elle a été modifiée
+pub fn clamp<T>(value: &mut T, range: &RangeInclusive<T>) -> bool
+where
+    T: PartialOrd + Copy,
+{
+    let (min, max) = (*range.start(), *range.end());
+
+    if *value < min {
+        *value = min;
+        true
+    } else if *value > max {
+        *value = max;
+        true
+    } else {
+        false
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn clamp_i32() {
+        // Dans les limites
+        let mut credits = 9_999_999;
+        assert!(!clamp(&mut credits, &ME1_CREDITS));
+        assert_eq!(credits, 9_999_999);
+
+        let mut credits = 0;
+        assert!(!clamp(&mut credits, &ME1_CREDITS));
+        assert_eq!(credits, 0);
+
+        // Au-delà
+        let mut credits = 10_000_000;
+        assert!(clamp(&mut credits, &ME1_CREDITS));
+        assert_eq!(credits, 9_999_999);
+
+        let mut credits = i32::MAX;
+        assert!(clamp(&mut credits, &ME1_CREDITS));
+        assert_eq!(credits, 9_999_999);
+
+        let mut credits = -1;
+        assert!(clamp(&mut credits, &ME1_CREDITS));
+        assert_eq!(credits, 0);
+
+        let mut level = 0;
+        assert!(clamp(&mut level, &ME2_LEVEL));
+        assert_eq!(level, 1);
+
+        let mut level = 31;
+        assert!(clamp(&mut level, &ME2_LEVEL));
+        assert_eq!(level, 30);
+    }
+
+    #[test]
+    fn out_of_range() {
+        assert_eq!(range_error(&60, &ME1_LEVEL), None);
+        assert_eq!(
+            range_error(&61, &ME1_LEVEL).as_deref(),
+            Some("Expected 1 to 60, the game may not handle 61")
+        );
+        assert!(range_error(&-1, &ME2_CREDITS).is_some());
+        assert!(range_error(&-0.5, &POSITIVE_F32).is_some());
+        assert!(range_error(&f32::NAN, &POSITIVE_F32).is_some());
+    }
+
+    #[test]
+    fn name_len() {
+        assert!(check_name("Shepard").is_ok());
+        assert!(check_name(&"a".repeat(20)).is_ok());
+        assert!(check_name(&"é".repeat(20)).is_ok());
+
+        assert!(check_name("").is_err());
+        let err = check_name(&"a".repeat(21)).unwrap_err();
+        assert_eq!(err.to_string(), "The name must be 1 to 20 characters long, not 21");
+    }
+
+    #[test]
+    fn clamp_f32() {
+        let mut xp = 0.5;
+        assert!(!clamp(&mut xp, &POSITIVE_F32));
+        assert!(xp > 0.0);
+
+        let mut xp = -0.5;
+        assert!(clamp(&mut xp, &POSITIVE_F32));
+        assert!(xp >= 0.0 && xp < f32::EPSILON);
+    }
+
+    #[test]
+    fn save_len_margin() -> std::io::Result<()> {
+        // La marge annoncée par `SAVE_LEN_WARNING`
+        let files = [
+            "test/Char_01-60-3-2-2-26-6-2018-57-26.MassEffectSave",
+            "test/Clare00_AutoSave.MassEffectSave",
+            "test/ME1Leg00_QuickSave.pcsav",
+            "test/ME2LegSave.pcsav",
+            "test/ME2Save.pcsav",
+            "test/ME3Save.pcsav",
+        ];
+        for file in &files {
+            let len = std::fs::metadata(file)?.len() as usize;
+            assert!(len < SAVE_LEN_WARNING / 10, "{}", file);
+        }
+        Ok(())
+    }
+}
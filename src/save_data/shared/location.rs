@@ -0,0 +1,65 @@
+use crate::event_handler::GameVersion;
+
+// (préfixe de la map, lieu), la fin du nom change selon la zone chargée
+// ex : `BIOA_STA30` et `BIOA_STA60` sont tous les deux sur la Citadelle
+const ME1_LOCATIONS: [(&str, &str); 11] = [
+    ("BIOA_NOR", "Normandy"),
+    ("BIOA_PRO", "Eden Prime"),
+    ("BIOA_STA", "Citadel"),
+    ("BIOA_LAV", "Therum"),
+    ("BIOA_ICE", "Noveria"),
+    ("BIOA_WAR", "Feros"),
+    ("BIOA_JUG", "Virmire"),
+    ("BIOA_LOS", "Ilos"),
+    ("BIOA_CIT", "Citadel (final battle)"),
+    ("BIOA_END", "Ending"),
+    ("BIOA_UNC", "Uncharted world"),
+];
+
+const ME2_LOCATIONS: [(&str, &str); 7] = [
+    ("BioP_Nor", "Normandy"),
+    ("BioP_OmgHub", "Omega"),
+    ("BioP_CitHub", "Citadel"),
+    ("BioP_TwrHub", "Illium"),
+    ("BioP_ProCer", "Lazarus Station"),
+    ("BioP_HorCr1", "Horizon"),
+    ("BioP_EndGm", "Collector Base"),
+];
+
+const ME3_LOCATIONS: [(&str, &str); 2] = [("BioP_Nor", "Normandy"), ("BioP_CitHub", "Citadel")];
+
+// Hubs où l'on peut déménager, le nom entier de leur map persistante est connu
+const ME2_RELOCATIONS: [(&str, &str); 4] = [
+    ("BioP_Nor", "Normandy"),
+    ("BioP_OmgHub", "Omega"),
+    ("BioP_CitHub", "Citadel"),
+    ("BioP_TwrHub", "Illium"),
+];
+
+pub fn relocations(game: GameVersion) -> &'static [(&'static str, &'static str)] {
+    match game {
+        GameVersion::MassEffect1 | GameVersion::MassEffect1Leg => &[],
+        GameVersion::MassEffect2 | GameVersion::MassEffect2Leg => &ME2_RELOCATIONS,
+        GameVersion::MassEffect3 => &ME3_LOCATIONS,
+    }
+}
+
+pub fn locations(game: GameVersion) -> &'static [(&'static str, &'static str)] {
+    match game {
+        GameVersion::MassEffect1 | GameVersion::MassEffect1Leg => &ME1_LOCATIONS,
+        GameVersion::MassEffect2 | GameVersion::MassEffect2Leg => &ME2_LOCATIONS,
+        GameVersion::MassEffect3 => &ME3_LOCATIONS,
+    }
+}
+
+// Insensible à la casse, les sauvegardes écrivent aussi bien `BioP_Nor` que `Biop_Nor`
+pub fn location_name(game: GameVersion, map: &str) -> Option<&'static str> {
+    locations(game).iter().find_map(|(prefix, name)| {
+        let head = map.get(..prefix.len())?;
+        if unicase::eq(head, prefix) {
+            Some(*name)
+        } else {
+            None
+        }
+    })
+}
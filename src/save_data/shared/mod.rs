@@ -8,6 +8,10 @@ use crate::gui::Gui;
 use super::{ImguiString, List, RawUi};
 
 pub mod appearance;
+pub mod byte_diff;
+pub mod limits;
+pub mod location;
+pub mod outfits;
 pub mod player;
 pub mod plot;
 
@@ -55,9 +59,15 @@ pub struct SaveTimeStamp {
 
 #[derive(Deserialize, Serialize, RawUi, Default, Clone)]
 pub struct Vector {
-    x: f32,
-    y: f32,
-    z: f32,
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+impl Vector {
+    pub fn is_finite(&self) -> bool {
+        self.x.is_finite() && self.y.is_finite() && self.z.is_finite()
+    }
 }
 
 #[derive(Deserialize, Serialize, RawUi, Default, Clone)]
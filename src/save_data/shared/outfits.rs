@@ -0,0 +1,110 @@
+// Tenue d'un équipier : un entier de plot, l'index de la tenue dans `outfits`
+pub struct SquadOutfits {
+    pub squadmate: &'static str,
+    pub plot_id: usize,
+    pub outfits: &'static [&'static str],
+}
+
+impl SquadOutfits {
+    // `None` pour une valeur hors de la table, à laisser en numérique
+    pub fn label(&self, value: i32) -> Option<&'static str> {
+        if value < 0 {
+            return None;
+        }
+        self.outfits.get(value as usize).copied()
+    }
+}
+
+pub fn find(table: &'static [SquadOutfits], plot_id: usize) -> Option<&'static SquadOutfits> {
+    table.iter().find(|squad_outfits| squad_outfits.plot_id == plot_id)
+}
+
+const ME2_ALTERNATE: &[&str] = &["Default", "Alternate"];
+const ME2_APPEARANCE_PACK: &[&str] = &["Default", "Alternate", "Appearance Pack"];
+
+// ME2 et ME2 LE
+pub const ME2_SQUAD_OUTFITS: [SquadOutfits; 12] = [
+    SquadOutfits { squadmate: "Garrus", plot_id: 318, outfits: ME2_APPEARANCE_PACK },
+    SquadOutfits { squadmate: "Grunt", plot_id: 322, outfits: ME2_APPEARANCE_PACK },
+    SquadOutfits { squadmate: "Jack", plot_id: 314, outfits: ME2_APPEARANCE_PACK },
+    SquadOutfits { squadmate: "Jacob", plot_id: 313, outfits: ME2_ALTERNATE },
+    SquadOutfits { squadmate: "Kasumi", plot_id: 317, outfits: ME2_ALTERNATE },
+    SquadOutfits { squadmate: "Legion", plot_id: 315, outfits: ME2_ALTERNATE },
+    SquadOutfits { squadmate: "Miranda", plot_id: 312, outfits: ME2_APPEARANCE_PACK },
+    SquadOutfits { squadmate: "Mordin", plot_id: 321, outfits: ME2_ALTERNATE },
+    SquadOutfits { squadmate: "Samara", plot_id: 323, outfits: ME2_ALTERNATE },
+    SquadOutfits { squadmate: "Tali", plot_id: 320, outfits: ME2_APPEARANCE_PACK },
+    SquadOutfits { squadmate: "Thane", plot_id: 319, outfits: ME2_APPEARANCE_PACK },
+    SquadOutfits { squadmate: "Zaeed", plot_id: 324, outfits: ME2_ALTERNATE },
+];
+
+const ME3_FROM_ASHES: &[&str] = &["Default", "Alternate", "N7 Collector", "From Ashes"];
+const ME3_PACK_1: &[&str] = &["Default", "Alternate", "N7 Collector", "Appearance Pack 1"];
+
+pub const ME3_SQUAD_OUTFITS: [SquadOutfits; 8] = [
+    SquadOutfits { squadmate: "Ashley", plot_id: 10154, outfits: ME3_FROM_ASHES },
+    SquadOutfits { squadmate: "EDI", plot_id: 10156, outfits: ME3_PACK_1 },
+    SquadOutfits { squadmate: "Garrus", plot_id: 10155, outfits: ME3_PACK_1 },
+    SquadOutfits { squadmate: "James", plot_id: 10158, outfits: ME3_FROM_ASHES },
+    SquadOutfits { squadmate: "Javik", plot_id: 10157, outfits: &["Default", "Alternate"] },
+    SquadOutfits { squadmate: "Kaidan", plot_id: 10153, outfits: ME3_FROM_ASHES },
+    SquadOutfits {
+        squadmate: "Liara",
+        plot_id: 10152,
+        outfits: &["Default", "Alternate", "N7 Collector", "From Ashes", "Appearance Pack 1"],
+    },
+    SquadOutfits {
+        squadmate: "Tali",
+        plot_id: 10214,
+        outfits: &["Default", "Alternate", "From Ashes"],
+    },
+];
+
+#[cfg(test)]
+mod test {
+    use anyhow::Result;
+    use std::fs;
+
+    use crate::{
+        event_handler::{GameVersion, SaveGame},
+        plot_db::{self, PlotDb},
+        save_data::shared::plot::PlotId,
+    };
+
+    use super::*;
+
+    #[test]
+    fn squad_outfit() -> Result<()> {
+        let garrus = find(&ME2_SQUAD_OUTFITS, 318).unwrap();
+        assert_eq!(garrus.squadmate, "Garrus");
+        assert_eq!(garrus.label(-1), None);
+        assert_eq!(garrus.label(3), None);
+
+        let input = fs::read("test/ME2Save.pcsav")?;
+        let mut save_game = SaveGame::load(String::new(), &input)?;
+        save_game.set_plot(PlotId::Int(garrus.plot_id), "2")?;
+
+        let save_game = SaveGame::load(String::new(), &save_game.to_bytes()?)?;
+        let value =
+            save_game.get_plot_int("Garrus appearance (0 = Default, 1 = Alternate, 2 = DLC)")?;
+        assert_eq!(garrus.label(value), Some("Appearance Pack"));
+
+        // La table suit l'équipage de la base de plots
+        let me2_crew = match plot_db::for_game(GameVersion::MassEffect2)? {
+            PlotDb::Me2(me2_known_plot) => me2_known_plot.crew.clone(),
+            _ => unreachable!(),
+        };
+        let me3_crew = match plot_db::for_game(GameVersion::MassEffect3)? {
+            PlotDb::Me3(me3_known_plot) => me3_known_plot.crew.clone(),
+            _ => unreachable!(),
+        };
+        let tables = [(&me2_crew, &ME2_SQUAD_OUTFITS[..]), (&me3_crew, &ME3_SQUAD_OUTFITS[..])];
+        for (crew, table) in tables.iter() {
+            for squad_outfits in table.iter() {
+                let ints = &crew[squad_outfits.squadmate].ints;
+                assert!(ints.contains_key(&squad_outfits.plot_id), "{}", squad_outfits.squadmate);
+            }
+        }
+        Ok(())
+    }
+}
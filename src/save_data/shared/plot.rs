@@ -1,4 +1,4 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
 use bitvec::prelude::*;
 use derive_more::{Deref, DerefMut};
 use indexmap::IndexMap;
@@ -65,13 +65,386 @@ pub struct Me1PlotTable {
     pub float_variables: Vec<f32>,
 }
 
+impl Me1PlotTable {
+    // Complète les booléens jusqu'au prochain u32 pour que le nombre de bitfields
+    // corresponde toujours au nombre de booléens
+    pub fn normalize(&mut self) {
+        let rem = self.bool_variables.len() % 32;
+        if rem != 0 {
+            let len = self.bool_variables.len() + 32 - rem;
+            self.bool_variables.resize(len, false);
+        }
+    }
+}
+
+// Cherche les entiers égaux à `from` et les remplace par `to` s'il est donné
+// Renvoie les index concernés
+pub fn replace_ints<'a>(
+    ints: impl Iterator<Item = (usize, &'a mut i32)>, from: i32, to: Option<i32>,
+) -> Vec<usize> {
+    ints.filter(|(_, value)| **value == from)
+        .map(|(idx, value)| {
+            if let Some(to) = to {
+                *value = to;
+            }
+            idx
+        })
+        .collect()
+}
+
 #[derive(Deserialize, Serialize, RawUi, Default, Clone)]
 pub struct PlotCodex {
     pages: IndexMap<i32, bool>,
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, Clone)]
 pub struct PlotCategory {
     pub booleans: IndexMap<usize, String>,
     pub ints: IndexMap<usize, String>,
+    #[serde(default)]
+    pub wiki: PlotWiki,
+}
+
+impl PlotCategory {
+    // Recherche par description, insensible à la casse
+    pub fn find(&self, name: &str) -> Result<Option<PlotId>> {
+        find_unique(std::iter::once(self), name)
+    }
+
+    fn matches<'a>(&'a self, name: &'a str) -> impl Iterator<Item = PlotId> + 'a {
+        let booleans =
+            self.booleans.iter().filter(move |(_, desc)| unicase::eq(desc.as_str(), name));
+        let ints = self.ints.iter().filter(move |(_, desc)| unicase::eq(desc.as_str(), name));
+        booleans.map(|(id, _)| PlotId::Boolean(*id)).chain(ints.map(|(id, _)| PlotId::Int(*id)))
+    }
+
+    // Plots nommés `[Quête] Description`, les autres ne sont pas des quêtes annexes
+    pub fn side_quests(&self) -> IndexMap<&str, SideQuest> {
+        let mut side_quests: IndexMap<&str, SideQuest> = IndexMap::new();
+        for (plot_id, desc) in &self.booleans {
+            if let Some((quest, desc)) = split_quest(desc) {
+                side_quests.entry(quest).or_default().booleans.push((*plot_id, desc));
+            }
+        }
+        for (plot_id, desc) in &self.ints {
+            if let Some((quest, desc)) = split_quest(desc) {
+                side_quests.entry(quest).or_default().ints.push((*plot_id, desc));
+            }
+        }
+        side_quests
+    }
+
+    // Remplace les descriptions traduites, les autres restent en anglais
+    pub fn localize(&mut self, names: &PlotNames) {
+        localize(&mut self.booleans, &names.booleans);
+        localize(&mut self.ints, &names.ints);
+    }
+}
+
+pub fn localize(plots: &mut IndexMap<usize, String>, names: &IndexMap<usize, String>) {
+    for (plot_id, desc) in plots.iter_mut() {
+        if let Some(name) = names.get(plot_id) {
+            *desc = name.clone();
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct SideQuest<'a> {
+    pub booleans: Vec<(usize, &'a str)>,
+    pub ints: Vec<(usize, &'a str)>,
+}
+
+// Libellés du plot de fin selon les jeux
+const COMPLETION_DESCS: [&str; 4] =
+    ["Completed quest", "Completed assignment", "Completed mission", "Mission completed"];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum QuestState {
+    NotStarted,
+    InProgress,
+    Complete,
+}
+
+impl SideQuest<'_> {
+    pub fn completion(&self) -> Option<usize> {
+        self.booleans
+            .iter()
+            .find(|(_, desc)| {
+                COMPLETION_DESCS.iter().any(|completion| unicase::eq(desc, completion))
+            })
+            .map(|(plot_id, _)| *plot_id)
+    }
+
+    // En cours dès qu'une autre étape est faite, `None` sans plot de fin
+    pub fn state(&self, booleans: &BoolSlice, ints: &[i32]) -> Option<QuestState> {
+        let completion = self.completion()?;
+        if booleans.get(completion).map_or(false, |value| *value) {
+            return Some(QuestState::Complete);
+        }
+        let started = self
+            .booleans
+            .iter()
+            .any(|(plot_id, _)| booleans.get(*plot_id).map_or(false, |value| *value))
+            || self
+                .ints
+                .iter()
+                .any(|(plot_id, _)| ints.get(*plot_id).map_or(false, |value| *value != 0));
+        Some(if started { QuestState::InProgress } else { QuestState::NotStarted })
+    }
+
+    // Pas encore commencée remet toutes les étapes à zéro, en cours retire la fin et fait la
+    // première étape si aucune ne l'est
+    pub fn set_state(&self, state: QuestState, booleans: &mut BoolSlice, ints: &mut [i32]) {
+        let completion = match self.completion() {
+            Some(completion) if completion < booleans.len() => completion,
+            _ => return,
+        };
+        match state {
+            QuestState::Complete => booleans.set(completion, true),
+            QuestState::InProgress => {
+                booleans.set(completion, false);
+                if self.state(booleans, ints) == Some(QuestState::NotStarted) {
+                    let first_bool = self
+                        .booleans
+                        .iter()
+                        .map(|(plot_id, _)| *plot_id)
+                        .find(|plot_id| *plot_id != completion && *plot_id < booleans.len());
+                    let first_int = self
+                        .ints
+                        .iter()
+                        .map(|(plot_id, _)| *plot_id)
+                        .find(|plot_id| *plot_id < ints.len());
+                    match (first_bool, first_int) {
+                        (Some(plot_id), _) => booleans.set(plot_id, true),
+                        (None, Some(plot_id)) => ints[plot_id] = 1,
+                        (None, None) => (),
+                    }
+                }
+            }
+            QuestState::NotStarted => {
+                for (plot_id, _) in &self.booleans {
+                    if *plot_id < booleans.len() {
+                        booleans.set(*plot_id, false);
+                    }
+                }
+                for (plot_id, _) in &self.ints {
+                    if let Some(value) = ints.get_mut(*plot_id) {
+                        *value = 0;
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn split_quest(desc: &str) -> Option<(&str, &str)> {
+    let desc = desc.strip_prefix('[')?;
+    let end = desc.find(']')?;
+    Some((&desc[..end], desc[end + 1..].trim_start()))
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PlotId {
+    Boolean(usize),
+    Int(usize),
+}
+
+// Liens vers le wiki, seulement pour les plots documentés
+#[derive(Deserialize, Serialize, Default, Clone)]
+pub struct PlotWiki {
+    #[serde(default)]
+    pub booleans: IndexMap<usize, String>,
+    #[serde(default)]
+    pub ints: IndexMap<usize, String>,
+}
+
+// Descriptions traduites d'un jeu, indexées comme la base anglaise
+#[derive(Deserialize, Default)]
+pub struct PlotNames {
+    #[serde(default)]
+    pub booleans: IndexMap<usize, String>,
+    #[serde(default)]
+    pub ints: IndexMap<usize, String>,
+}
+
+impl PlotNames {
+    // Un plot présent dans plusieurs catégories garde sa première description
+    pub fn add(&mut self, plots: &PlotCategory) {
+        add_names(&mut self.booleans, &plots.booleans);
+        add_names(&mut self.ints, &plots.ints);
+    }
+
+    // Plots dont la description contient `query`, sans tenir compte de la casse
+    pub fn search(&self, boolean: bool, query: &str) -> Vec<(usize, &str)> {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return Vec::new();
+        }
+        let names = if boolean { &self.booleans } else { &self.ints };
+        names
+            .iter()
+            .filter(|(_, desc)| desc.to_lowercase().contains(&query))
+            .map(|(plot_id, desc)| (*plot_id, desc.as_str()))
+            .collect()
+    }
+}
+
+// Un nom porté par plusieurs plots n'en désigne aucun, le même plot peut être dans plusieurs
+// catégories
+pub fn find_unique<'a>(
+    categories: impl Iterator<Item = &'a PlotCategory>, name: &str,
+) -> Result<Option<PlotId>> {
+    let mut found: Vec<PlotId> = Vec::new();
+    for plot_id in categories.flat_map(|plots| plots.matches(name)) {
+        if !found.contains(&plot_id) {
+            found.push(plot_id);
+        }
+    }
+    match found.as_slice() {
+        [] => Ok(None),
+        [plot_id] => Ok(Some(*plot_id)),
+        _ => {
+            let found: Vec<_> = found.iter().map(|plot_id| format!("{:?}", plot_id)).collect();
+            bail!("Ambiguous plot `{}`, matches {}", name, found.join(", "))
+        }
+    }
+}
+
+pub fn add_names(names: &mut IndexMap<usize, String>, plots: &IndexMap<usize, String>) {
+    for (plot_id, desc) in plots {
+        names.entry(*plot_id).or_insert_with(|| desc.clone());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use crate::{
+        save_data::mass_effect_1::{known_plot::Me1KnownPlot, Me1SaveGame},
+        unreal,
+    };
+
+    use super::*;
+
+    #[test]
+    fn side_quests() -> Result<()> {
+        let me1_known_plot: Me1KnownPlot =
+            ron::from_str(&fs::read_to_string("plot/me1_known_plot.ron")?)?;
+        let side_quests = me1_known_plot.missions["Citadel"].side_quests();
+
+        let consort = &side_quests["Asari Consort"];
+        assert_eq!(
+            consort.booleans,
+            vec![
+                (5853, "Completed quest"),
+                (4032, "Received Trinket"),
+                (7015, "Used Trinket on Eletania")
+            ]
+        );
+        assert!(consort.ints.is_empty());
+
+        // Toggle puis round-trip
+        let input = fs::read("test/Clare00_AutoSave.MassEffectSave")?;
+        let mut me1_save_game: Me1SaveGame = unreal::Deserializer::from_bytes(&input)?;
+        let (plot_id, _) = consort.booleans[0];
+        let value = me1_save_game.state.plot.bool_variables[plot_id];
+        me1_save_game.state.plot.bool_variables.set(plot_id, !value);
+
+        let output = unreal::Serializer::to_byte_buf(&me1_save_game)?;
+        let me1_save_game: Me1SaveGame = unreal::Deserializer::from_bytes(&output)?;
+        assert_eq!(me1_save_game.state.plot.bool_variables[plot_id], !value);
+        Ok(())
+    }
+
+    #[test]
+    fn quest_state() -> Result<()> {
+        let me1_known_plot: Me1KnownPlot =
+            ron::from_str(&fs::read_to_string("plot/me1_known_plot.ron")?)?;
+        let side_quests = me1_known_plot.missions["Citadel"].side_quests();
+        let consort = &side_quests["Asari Consort"];
+        assert_eq!(consort.completion(), Some(5853));
+
+        let input = fs::read("test/Clare00_AutoSave.MassEffectSave")?;
+        let mut me1_save_game: Me1SaveGame = unreal::Deserializer::from_bytes(&input)?;
+        let plot = &mut me1_save_game.state.plot;
+        consort.set_state(QuestState::Complete, &mut plot.bool_variables, &mut plot.int_variables);
+
+        let output = unreal::Serializer::to_byte_buf(&me1_save_game)?;
+        let mut me1_save_game: Me1SaveGame = unreal::Deserializer::from_bytes(&output)?;
+        let plot = &mut me1_save_game.state.plot;
+        assert_eq!(
+            consort.state(&plot.bool_variables, &plot.int_variables),
+            Some(QuestState::Complete)
+        );
+
+        // Étape faite sans la fin
+        consort.set_state(
+            QuestState::NotStarted,
+            &mut plot.bool_variables,
+            &mut plot.int_variables,
+        );
+        plot.bool_variables.set(4032, true);
+        assert_eq!(
+            consort.state(&plot.bool_variables, &plot.int_variables),
+            Some(QuestState::InProgress)
+        );
+        consort.set_state(
+            QuestState::NotStarted,
+            &mut plot.bool_variables,
+            &mut plot.int_variables,
+        );
+        assert!(consort.booleans.iter().all(|(plot_id, _)| !plot.bool_variables[*plot_id]));
+
+        // Pas encore commencée -> en cours : la première étape est faite
+        consort.set_state(
+            QuestState::InProgress,
+            &mut plot.bool_variables,
+            &mut plot.int_variables,
+        );
+        assert_eq!(
+            consort.state(&plot.bool_variables, &plot.int_variables),
+            Some(QuestState::InProgress)
+        );
+        assert!(plot.bool_variables[4032]);
+        assert!(!plot.bool_variables[5853]);
+        Ok(())
+    }
+
+    #[test]
+    fn search_names() {
+        let mut names = PlotNames::default();
+        names.booleans.insert(10, "[Citadel] Met Emily Wong".to_owned());
+        names.booleans.insert(11, "[Feros] Saved the colony".to_owned());
+        names.ints.insert(10, "Emily Wong quest state".to_owned());
+
+        assert_eq!(names.search(true, "emily"), vec![(10, "[Citadel] Met Emily Wong")]);
+        assert_eq!(names.search(false, " WONG "), vec![(10, "Emily Wong quest state")]);
+        assert!(names.search(true, "").is_empty());
+        assert!(names.search(true, "Noveria").is_empty());
+    }
+
+    #[test]
+    fn replace() {
+        let mut ints = vec![0, 3, 1, 3, 3];
+
+        // Recherche seule
+        let found = replace_ints(ints.iter_mut().enumerate(), 3, None);
+        assert_eq!(found, vec![1, 3, 4]);
+        assert_eq!(ints, vec![0, 3, 1, 3, 3]);
+
+        let replaced = replace_ints(ints.iter_mut().enumerate(), 3, Some(7));
+        assert_eq!(replaced, vec![1, 3, 4]);
+        assert_eq!(ints, vec![0, 7, 1, 7, 7]);
+
+        assert!(replace_ints(ints.iter_mut().enumerate(), 3, Some(7)).is_empty());
+
+        let mut ints: IndexMap<i32, i32> = [(10, 3), (42, 5), (10159, 3)].iter().copied().collect();
+        let replaced =
+            replace_ints(ints.iter_mut().map(|(idx, value)| (*idx as usize, value)), 3, Some(0));
+        assert_eq!(replaced, vec![10, 10159]);
+        assert_eq!(ints[&10159], 0);
+        assert_eq!(ints[&42], 5);
+    }
 }
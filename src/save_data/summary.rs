@@ -0,0 +1,175 @@
+use super::{
+    mass_effect_1_leg::Me1LegSaveData,
+    mass_effect_2::{Me2LegSaveGame, Me2SaveGame},
+    mass_effect_3::Me3SaveGame,
+    shared::plot::BoolVec,
+};
+
+// Plots des romances, (id, nom)
+const ME1_ROMANCES: [(usize, &str); 3] = [(4281, "Ashley"), (3960, "Kaidan"), (4169, "Liara")];
+const ME2_ROMANCES: [(usize, &str); 6] = [
+    (3892, "Jacob"),
+    (3926, "Tali"),
+    (5206, "Thane"),
+    (5207, "Garrus"),
+    (5208, "Jack"),
+    (5209, "Miranda"),
+];
+const ME3_ROMANCES: [(usize, &str); 12] = [
+    (19722, "Liara"),
+    (19723, "Kaidan"),
+    (19724, "Ashley"),
+    (19725, "Garrus"),
+    (19726, "Tali"),
+    (19835, "Samantha"),
+    (19838, "Steve"),
+    (19846, "Jack"),
+    (21750, "Miranda"),
+    (19283, "Thane"),
+    (20630, "Diana Allers"),
+    (21100, "Kelly"),
+];
+
+// Résumé en lecture seule du personnage
+pub struct CharacterSummary {
+    pub name: String,
+    pub level: i32,
+    pub class_name: Option<String>,
+    pub seconds_played: f32,
+    pub paragon: i32,
+    pub renegade: i32,
+    pub credits: i32,
+    pub romances: Vec<&'static str>,
+}
+
+impl CharacterSummary {
+    pub fn me1_leg(save_data: &Me1LegSaveData) -> Self {
+        let plot = &save_data.plot;
+
+        CharacterSummary {
+            name: save_data.player.first_name.to_string(),
+            level: save_data.player.level,
+            class_name: None,
+            seconds_played: save_data.seconds_played as f32,
+            paragon: plot.int_variables.get(47).copied().unwrap_or_default(),
+            renegade: plot.int_variables.get(46).copied().unwrap_or_default(),
+            credits: save_data.player.credits,
+            romances: romances(&plot.bool_variables, &ME1_ROMANCES),
+        }
+    }
+
+    pub fn me2(save_game: &Me2SaveGame) -> Self {
+        let Me2SaveGame { player, plot, seconds_played, .. } = save_game;
+
+        CharacterSummary {
+            name: player.first_name.to_string(),
+            level: player.level,
+            class_name: Some(class_name(player.class_name.to_str())),
+            seconds_played: *seconds_played,
+            paragon: plot.int_variables.get(2).copied().unwrap_or_default(),
+            renegade: plot.int_variables.get(3).copied().unwrap_or_default(),
+            credits: player.credits,
+            romances: romances(&plot.bool_variables, &ME2_ROMANCES),
+        }
+    }
+
+    pub fn me2_leg(save_game: &Me2LegSaveGame) -> Self {
+        let Me2LegSaveGame { player, plot, seconds_played, .. } = save_game;
+
+        CharacterSummary {
+            name: player.first_name.to_string(),
+            level: player.level,
+            class_name: Some(class_name(player.class_name.to_str())),
+            seconds_played: *seconds_played,
+            paragon: plot.int_variables.get(2).copied().unwrap_or_default(),
+            renegade: plot.int_variables.get(3).copied().unwrap_or_default(),
+            credits: player.credits,
+            romances: romances(&plot.bool_variables, &ME2_ROMANCES),
+        }
+    }
+
+    pub fn me3(save_game: &Me3SaveGame) -> Self {
+        let Me3SaveGame { player, plot, seconds_played, .. } = save_game;
+
+        CharacterSummary {
+            name: player.first_name.to_string(),
+            level: player.level,
+            class_name: Some(class_name(player.class_name.to_str())),
+            seconds_played: *seconds_played,
+            paragon: plot.int_variables.get(&10159).copied().unwrap_or_default(),
+            renegade: plot.int_variables.get(&10160).copied().unwrap_or_default(),
+            credits: player.credits,
+            romances: romances(&plot.bool_variables, &ME3_ROMANCES),
+        }
+    }
+
+    pub fn playtime(&self) -> String {
+        let minutes = (self.seconds_played.max(0.0) / 60.0) as u32;
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    }
+}
+
+// `SFXGame.SFXPawn_PlayerAdept` => `Adept`
+fn class_name(class_name: &str) -> String {
+    let class_name = class_name.rsplit('.').next().unwrap_or(class_name);
+    class_name.trim_start_matches("SFXPawn_Player").to_owned()
+}
+
+fn romances(booleans: &BoolVec, romances: &[(usize, &'static str)]) -> Vec<&'static str> {
+    romances
+        .iter()
+        .filter(|(plot_id, _)| *plot_id < booleans.len() && booleans[*plot_id])
+        .map(|(_, name)| *name)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::Result;
+    use std::{fs::File, io::Read};
+
+    use crate::unreal;
+
+    use super::*;
+
+    #[test]
+    fn me2_summary() -> Result<()> {
+        let mut input = Vec::new();
+        {
+            let mut file = File::open("test/ME2Save.pcsav")?;
+            file.read_to_end(&mut input)?;
+        }
+
+        let save_game: Me2SaveGame = unreal::Deserializer::from_bytes(&input)?;
+        let summary = CharacterSummary::me2(&save_game);
+
+        assert_eq!(summary.name, save_game.player.first_name.to_string());
+        assert_eq!(summary.level, save_game.player.level);
+        assert_eq!(summary.credits, save_game.player.credits);
+        assert_eq!(summary.paragon, save_game.plot.int_variables[2]);
+        assert_eq!(summary.renegade, save_game.plot.int_variables[3]);
+        for (plot_id, name) in &ME2_ROMANCES {
+            assert_eq!(summary.romances.contains(name), save_game.plot.bool_variables[*plot_id]);
+        }
+        assert!(save_game.player.class_name.to_str().ends_with(&summary.class_name.unwrap()));
+        Ok(())
+    }
+
+    #[test]
+    fn helpers() {
+        assert_eq!(class_name("SFXGame.SFXPawn_PlayerAdept"), "Adept");
+        assert_eq!(class_name("Vanguard"), "Vanguard");
+
+        let summary = CharacterSummary {
+            name: String::new(),
+            level: 1,
+            class_name: None,
+            seconds_played: 7_530.0,
+            paragon: 0,
+            renegade: 0,
+            credits: 0,
+            romances: Vec::new(),
+        };
+        assert_eq!(summary.playtime(), "2h 05m");
+    }
+}
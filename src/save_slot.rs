@@ -0,0 +1,310 @@
+use anyhow::{bail, Result};
+use std::path::{Path, PathBuf};
+
+use crate::event_handler::GameVersion;
+
+// Les jeux ne listent que les sauvegardes nommées `Save_0001`, `AutoSave`, `QuickSave`
+// ou `ChapterSave`, avec un éventuel préfixe (personnage de ME1)
+const SPECIAL_SLOTS: [&str; 3] = ["AutoSave", "QuickSave", "ChapterSave"];
+
+// (préfixe, numéro) ; pas de numéro pour les slots spéciaux
+fn parse_slot(stem: &str) -> Option<(&str, Option<u32>)> {
+    let (prefix, number) = match SPECIAL_SLOTS.iter().find_map(|slot| stem.strip_suffix(slot)) {
+        Some(prefix) => (prefix, None),
+        None => {
+            let idx = stem.rfind("Save_")?;
+            let digits = &stem[idx + 5..];
+            if digits.len() != 4 || !digits.chars().all(|c| c.is_ascii_digit()) {
+                return None;
+            }
+            (&stem[..idx], Some(digits.parse().ok()?))
+        }
+    };
+
+    if prefix.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        Some((prefix, number))
+    } else {
+        None
+    }
+}
+
+pub fn validate_name(stem: &str) -> Result<()> {
+    if parse_slot(stem).is_none() {
+        bail!(
+            "`{}` isn't a valid save name, the game expects `Save_0001`, `AutoSave`, `QuickSave` or `ChapterSave`",
+            stem
+        );
+    }
+    Ok(())
+}
+
+// Même dossier, même extension
+pub fn rename_target(path: &Path, new_stem: &str) -> Result<PathBuf> {
+    validate_name(new_stem)?;
+
+    let mut target = path.with_file_name(new_stem);
+    if let Some(ext) = path.extension() {
+        target.set_extension(ext);
+    }
+    Ok(target)
+}
+
+// Extensions des sauvegardes des 3 jeux, LE compris
+pub fn is_save(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| {
+            let ext = ext.to_string_lossy();
+            unicase::eq(ext.as_ref(), "pcsav") || unicase::eq(ext.as_ref(), "MassEffectSave")
+        })
+        .unwrap_or(false)
+}
+
+// Dossiers créés par les jeux : `Mass Effect 2/Save/<perso>` pour les originaux,
+// `Mass Effect Legendary Edition/Save/ME2/<perso>` pour la LE
+pub fn game_from_path(path: &Path) -> Option<GameVersion> {
+    let mut game = None;
+    for component in path.components() {
+        let name = component.as_os_str().to_string_lossy().to_lowercase();
+        let folder_game = match name.as_str() {
+            "mass effect" => Some(GameVersion::MassEffect1),
+            "mass effect 2" => Some(GameVersion::MassEffect2),
+            "mass effect 3" => Some(GameVersion::MassEffect3),
+            "me1" => Some(GameVersion::MassEffect1Leg),
+            "me2" => Some(GameVersion::MassEffect2Leg),
+            "me3" => Some(GameVersion::MassEffect3),
+            _ => None,
+        };
+        // Le dossier le plus profond l'emporte
+        game = folder_game.or(game);
+    }
+    game
+}
+
+// Chaque personnage a son dossier, `None` pour une sauvegarde posée à la racine
+// ou directement dans le dossier d'un jeu
+pub fn character_folder(root: &Path, path: &Path) -> Option<String> {
+    let parent = path.parent().filter(|parent| *parent != root)?;
+    let name = parent.file_name()?.to_string_lossy();
+    let is_game_folder =
+        unicase::eq(name.as_ref(), "Save") || game_from_path(Path::new(name.as_ref())).is_some();
+    (!is_game_folder).then(|| name.into_owned())
+}
+
+// Les en-têtes des sauvegardes automatiques et manuelles sont identiques,
+// seul le nom du fichier fait la différence
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SlotKind {
+    Manual,
+    AutoSave,
+    QuickSave,
+    ChapterSave,
+}
+
+pub fn slot_kind(path: &Path) -> Option<SlotKind> {
+    let stem = path.file_stem()?.to_string_lossy();
+    let kind = match parse_slot(&stem)? {
+        (_, Some(_)) => SlotKind::Manual,
+        _ if stem.ends_with("AutoSave") => SlotKind::AutoSave,
+        _ if stem.ends_with("QuickSave") => SlotKind::QuickSave,
+        _ => SlotKind::ChapterSave,
+    };
+    Some(kind)
+}
+
+// Une sauvegarde automatique devient le prochain `Save_XXXX` libre, une manuelle devient
+// l'`AutoSave` du même personnage
+pub fn convert_target(path: &Path, existing: &[String]) -> Result<PathBuf> {
+    match slot_kind(path) {
+        Some(SlotKind::Manual) => {
+            let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+            let prefix = parse_slot(&stem).map(|(prefix, _)| prefix).unwrap_or_default();
+            rename_target(path, &format!("{}AutoSave", prefix))
+        }
+        Some(_) => Ok(duplicate_target(path, existing)),
+        None => bail!("`{}` isn't a save slot the game lists", path.display()),
+    }
+}
+
+// Numéro du slot, `None` pour les slots spéciaux ou un nom invalide
+pub fn slot_number(path: &Path) -> Option<u32> {
+    let stem = path.file_stem()?.to_string_lossy();
+    parse_slot(&stem).and_then(|(_, number)| number)
+}
+
+// `Save_0003`, `QuickSave`... sans le préfixe du personnage
+pub fn slot_label(path: &Path) -> Option<String> {
+    let stem = path.file_stem()?.to_string_lossy();
+    let (prefix, _) = parse_slot(&stem)?;
+    Some(stem[prefix.len()..].to_owned())
+}
+
+// Même préfixe et même extension, seul le numéro change
+pub fn slot_target(path: &Path, number: u32) -> Result<PathBuf> {
+    if !(1..=9999).contains(&number) {
+        bail!("The slot number must be between 1 and 9999, got {}", number);
+    }
+    let prefix = path
+        .file_stem()
+        .and_then(|stem| parse_slot(&stem.to_string_lossy()).map(|(prefix, _)| prefix.to_owned()))
+        .unwrap_or_default();
+    rename_target(path, &format!("{}Save_{:04}", prefix, number))
+}
+
+// Prochain `Save_XXXX` libre parmi les fichiers du dossier
+pub fn duplicate_target(path: &Path, existing: &[String]) -> PathBuf {
+    let ext = path.extension().map(|ext| ext.to_string_lossy().into_owned()).unwrap_or_default();
+    let prefix = path
+        .file_stem()
+        .and_then(|stem| parse_slot(&stem.to_string_lossy()).map(|(prefix, _)| prefix.to_owned()))
+        .unwrap_or_default();
+
+    let last = existing
+        .iter()
+        .filter_map(|file_name| {
+            let file_name = Path::new(file_name);
+            if file_name.extension().and_then(|ext| ext.to_str()) != Some(ext.as_str()) {
+                return None;
+            }
+            // Les emplacements des autres personnages de ME1 ne comptent pas
+            let stem = file_name.file_stem()?.to_string_lossy();
+            parse_slot(&stem).filter(|(slot_prefix, _)| *slot_prefix == prefix)?.1
+        })
+        .max()
+        .unwrap_or(0);
+
+    let mut target = path.with_file_name(format!("{}Save_{:04}", prefix, last + 1));
+    if !ext.is_empty() {
+        target.set_extension(ext);
+    }
+    target
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn validate() {
+        assert!(validate_name("Save_0001").is_ok());
+        assert!(validate_name("AutoSave").is_ok());
+        assert!(validate_name("QuickSave").is_ok());
+        assert!(validate_name("ChapterSave").is_ok());
+        assert!(validate_name("Clare00_AutoSave").is_ok());
+        assert!(validate_name("ME1Leg00_Save_0042").is_ok());
+
+        assert!(validate_name("").is_err());
+        assert!(validate_name("Save_1").is_err());
+        assert!(validate_name("Save_00001").is_err());
+        assert!(validate_name("Save_abcd").is_err());
+        assert!(validate_name("My save").is_err());
+        assert!(validate_name("../Save_0001").is_err());
+    }
+
+    #[test]
+    fn rename() -> Result<()> {
+        let path = Path::new("saves/Shepard/Save_0003.pcsav");
+        assert_eq!(rename_target(path, "QuickSave")?, Path::new("saves/Shepard/QuickSave.pcsav"));
+        assert!(rename_target(path, "QuickSave.pcsav").is_err());
+
+        let path = Path::new("Clare00_AutoSave.MassEffectSave");
+        assert_eq!(
+            rename_target(path, "Clare00_Save_0002")?,
+            Path::new("Clare00_Save_0002.MassEffectSave")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn duplicate() {
+        let existing = vec![
+            "Save_0001.pcsav".to_owned(),
+            "Save_0007.pcsav".to_owned(),
+            "Save_0009.pcsav.bak".to_owned(),
+            "QuickSave.pcsav".to_owned(),
+        ];
+
+        let path = Path::new("saves/QuickSave.pcsav");
+        assert_eq!(duplicate_target(path, &existing), Path::new("saves/Save_0008.pcsav"));
+
+        let path = Path::new("Clare00_QuickSave.MassEffectSave");
+        assert_eq!(
+            duplicate_target(path, &existing),
+            Path::new("Clare00_Save_0001.MassEffectSave")
+        );
+
+        // Chaque personnage de ME1 a ses propres numéros
+        let existing = vec![
+            "Clare00_Save_0002.MassEffectSave".to_owned(),
+            "John01_Save_0005.MassEffectSave".to_owned(),
+        ];
+        assert_eq!(
+            duplicate_target(path, &existing),
+            Path::new("Clare00_Save_0003.MassEffectSave")
+        );
+    }
+
+    #[test]
+    fn slot() -> Result<()> {
+        let path = Path::new("saves/Clare00_Save_0003.MassEffectSave");
+        assert_eq!(slot_number(path), Some(3));
+        assert_eq!(slot_label(path).as_deref(), Some("Save_0003"));
+        assert_eq!(slot_number(Path::new("QuickSave.pcsav")), None);
+        assert_eq!(slot_label(Path::new("QuickSave.pcsav")).as_deref(), Some("QuickSave"));
+        assert_eq!(slot_label(Path::new("My save.pcsav")), None);
+
+        // Le numéro change, pas le préfixe ni l'extension
+        let target = slot_target(path, 12)?;
+        assert_eq!(target, Path::new("saves/Clare00_Save_0012.MassEffectSave"));
+        assert_eq!(slot_number(&target), Some(12));
+        assert_eq!(
+            slot_target(Path::new("Clare00_QuickSave.MassEffectSave"), 1)?,
+            Path::new("Clare00_Save_0001.MassEffectSave")
+        );
+
+        assert!(slot_target(path, 0).is_err());
+        assert!(slot_target(path, 10000).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn convert() -> Result<()> {
+        let path = Path::new("test/Clare00_AutoSave.MassEffectSave");
+        assert_eq!(slot_kind(path), Some(SlotKind::AutoSave));
+        assert_eq!(slot_kind(Path::new("QuickSave.pcsav")), Some(SlotKind::QuickSave));
+        assert_eq!(slot_kind(Path::new("ChapterSave.pcsav")), Some(SlotKind::ChapterSave));
+        assert_eq!(slot_kind(Path::new("My save.pcsav")), None);
+
+        // Automatique vers manuelle, et retour
+        let existing = vec!["Clare00_Save_0001.MassEffectSave".to_owned()];
+        let manual = convert_target(path, &existing)?;
+        assert_eq!(manual, Path::new("test/Clare00_Save_0002.MassEffectSave"));
+        assert_eq!(slot_kind(&manual), Some(SlotKind::Manual));
+        assert_eq!(convert_target(&manual, &existing)?, path);
+
+        // Même en-tête, rien d'autre à changer dans le fichier
+        let manual = std::fs::read("test/Char_01-60-3-2-2-26-6-2018-57-26.MassEffectSave")?;
+        let auto = std::fs::read(path)?;
+        assert_eq!(manual[..0x48], auto[..0x48]);
+
+        assert!(convert_target(Path::new("My save.pcsav"), &existing).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn trilogy_folders() {
+        let root = Path::new("BioWare");
+        let me2 = Path::new("BioWare/Mass Effect 2/Save/Shepard_01/Save_0001.pcsav");
+        assert_eq!(game_from_path(me2), Some(GameVersion::MassEffect2));
+        assert_eq!(character_folder(root, me2).as_deref(), Some("Shepard_01"));
+
+        let me1_leg =
+            Path::new("BioWare/Mass Effect Legendary Edition/Save/ME1/Jane_01/Save_0001.pcsav");
+        assert_eq!(game_from_path(me1_leg), Some(GameVersion::MassEffect1Leg));
+        assert_eq!(character_folder(root, me1_leg).as_deref(), Some("Jane_01"));
+
+        let loose = Path::new("BioWare/Mass Effect 3/Save/AutoSave.pcsav");
+        assert_eq!(character_folder(root, loose), None);
+        assert_eq!(character_folder(root, Path::new("BioWare/Save_0001.pcsav")), None);
+        assert_eq!(game_from_path(Path::new("Downloads/Save_0001.pcsav")), None);
+    }
+}
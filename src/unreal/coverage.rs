@@ -0,0 +1,383 @@
+use serde::{ser, Serialize};
+use std::ops::Add;
+
+use super::{Result, Serializer};
+
+// Champs dont on ne connaît pas le contenu, gardés tels quels
+const OPAQUE_FIELDS: [&str; 5] =
+    ["_unknown", "_osef", "_no_mans_land", "_remaining_bytes", "_world_save_package"];
+
+// Octets associés à un champ nommé, les autres sont recopiés sans être compris
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct Coverage {
+    pub known: usize,
+    pub opaque: usize,
+}
+
+impl Coverage {
+    pub fn opaque(len: usize) -> Self {
+        Coverage { known: 0, opaque: len }
+    }
+
+    pub fn total(&self) -> usize {
+        self.known + self.opaque
+    }
+
+    pub fn known_ratio(&self) -> f32 {
+        match self.total() {
+            0 => 1.0,
+            total => self.known as f32 / total as f32,
+        }
+    }
+}
+
+impl Add for Coverage {
+    type Output = Coverage;
+
+    fn add(self, other: Coverage) -> Coverage {
+        Coverage { known: self.known + other.known, opaque: self.opaque + other.opaque }
+    }
+}
+
+// Octets opaques et le champ qui les contient, pour les `FieldDecoder`
+#[derive(Clone, Debug)]
+pub struct OpaqueRegion {
+    pub field: String,
+    pub bytes: Vec<u8>,
+}
+
+// Les dummies et les champs de `OPAQUE_FIELDS` sont opaques
+pub fn to_coverage<T>(value: &T) -> Result<Coverage>
+where
+    T: ?Sized + Serialize,
+{
+    let total = Serializer::to_byte_buf(value)?.len();
+    let mut counter = OpaqueCounter::default();
+    value.serialize(&mut counter)?;
+    Ok(Coverage { known: total - counter.opaque, opaque: counter.opaque })
+}
+
+// Dans l'ordre de sérialisation, un dummy porte le nom du champ qui le contient
+pub fn to_opaque_regions<T>(value: &T) -> Result<Vec<OpaqueRegion>>
+where
+    T: ?Sized + Serialize,
+{
+    let mut counter = OpaqueCounter::default();
+    value.serialize(&mut counter)?;
+    Ok(counter.regions)
+}
+
+fn is_opaque(field: &str) -> bool {
+    OPAQUE_FIELDS.iter().any(|prefix| field.starts_with(prefix))
+}
+
+#[derive(Default)]
+struct OpaqueCounter {
+    opaque: usize,
+    field: &'static str,
+    regions: Vec<OpaqueRegion>,
+}
+
+impl OpaqueCounter {
+    fn field<T>(&mut self, field: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        if is_opaque(field) {
+            self.push_region(field, Serializer::to_byte_buf(value)?);
+            Ok(())
+        } else {
+            self.field = field;
+            value.serialize(self)
+        }
+    }
+
+    fn push_region(&mut self, field: &str, bytes: Vec<u8>) {
+        self.opaque += bytes.len();
+        if !bytes.is_empty() {
+            self.regions.push(OpaqueRegion { field: field.to_owned(), bytes });
+        }
+    }
+}
+
+macro_rules! impl_primitive {
+    ($ser_method:ident($type:ty)) => {
+        fn $ser_method(self, _: $type) -> Result<()> {
+            Ok(())
+        }
+    };
+}
+
+impl<'a> ser::Serializer for &'a mut OpaqueCounter {
+    type Ok = ();
+    type Error = super::Error;
+
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    impl_primitive!(serialize_bool(bool));
+    impl_primitive!(serialize_i8(i8));
+    impl_primitive!(serialize_i16(i16));
+    impl_primitive!(serialize_i32(i32));
+    impl_primitive!(serialize_i64(i64));
+    impl_primitive!(serialize_u8(u8));
+    impl_primitive!(serialize_u16(u16));
+    impl_primitive!(serialize_u32(u32));
+    impl_primitive!(serialize_u64(u64));
+    impl_primitive!(serialize_f32(f32));
+    impl_primitive!(serialize_f64(f64));
+    impl_primitive!(serialize_char(char));
+    impl_primitive!(serialize_str(&str));
+
+    // Dummy
+    fn serialize_bytes(self, value: &[u8]) -> Result<()> {
+        let field = self.field;
+        self.push_region(field, value.to_vec());
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn serialize_some<T: ?Sized>(self, value: &T) -> Result<()>
+    where
+        T: Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<()> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(self, _: &'static str, _: u32, _: &'static str) -> Result<()> {
+        Ok(())
+    }
+
+    fn serialize_newtype_struct<T: ?Sized>(self, _: &'static str, value: &T) -> Result<()>
+    where
+        T: Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized>(
+        self, _: &'static str, _: u32, _: &'static str, value: &T,
+    ) -> Result<()>
+    where
+        T: Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self> {
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(self, _: &'static str, _: usize) -> Result<Self> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_variant(
+        self, _: &'static str, _: u32, _: &'static str, _: usize,
+    ) -> Result<Self> {
+        Ok(self)
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self> {
+        Ok(self)
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self> {
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self, _: &'static str, _: u32, _: &'static str, _: usize,
+    ) -> Result<Self> {
+        Ok(self)
+    }
+
+    // Même représentation que le `Serializer` binaire
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+impl<'a> ser::SerializeSeq for &'a mut OpaqueCounter {
+    type Ok = ();
+    type Error = super::Error;
+
+    fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize,
+    {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> ser::SerializeTuple for &'a mut OpaqueCounter {
+    type Ok = ();
+    type Error = super::Error;
+
+    fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize,
+    {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> ser::SerializeTupleStruct for &'a mut OpaqueCounter {
+    type Ok = ();
+    type Error = super::Error;
+
+    fn serialize_field<T: ?Sized>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize,
+    {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> ser::SerializeTupleVariant for &'a mut OpaqueCounter {
+    type Ok = ();
+    type Error = super::Error;
+
+    fn serialize_field<T: ?Sized>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize,
+    {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> ser::SerializeMap for &'a mut OpaqueCounter {
+    type Ok = ();
+    type Error = super::Error;
+
+    fn serialize_key<T: ?Sized>(&mut self, key: &T) -> Result<()>
+    where
+        T: Serialize,
+    {
+        key.serialize(&mut **self)
+    }
+
+    fn serialize_value<T: ?Sized>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize,
+    {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> ser::SerializeStruct for &'a mut OpaqueCounter {
+    type Ok = ();
+    type Error = super::Error;
+
+    fn serialize_field<T: ?Sized>(&mut self, field: &'static str, value: &T) -> Result<()>
+    where
+        T: Serialize,
+    {
+        self.field(field, value)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> ser::SerializeStructVariant for &'a mut OpaqueCounter {
+    type Ok = ();
+    type Error = super::Error;
+
+    fn serialize_field<T: ?Sized>(&mut self, field: &'static str, value: &T) -> Result<()>
+    where
+        T: Serialize,
+    {
+        self.field(field, value)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use crate::{
+        save_data::{
+            mass_effect_1::Me1SaveGame,
+            mass_effect_2::{Me2LegSaveGame, Me2SaveGame},
+        },
+        unreal,
+    };
+
+    use super::*;
+
+    #[test]
+    fn coverage() -> anyhow::Result<()> {
+        // Entièrement décrite
+        let input = fs::read("test/ME2Save.pcsav")?;
+        let me2_save_game: Me2SaveGame = unreal::Deserializer::from_bytes(&input)?;
+        let coverage = to_coverage(&me2_save_game)?;
+        assert_eq!(coverage.opaque, 0);
+        assert_eq!(coverage.total(), input.len() - 4);
+
+        // Seul `_unknown` reste
+        let input = fs::read("test/ME2LegSave.pcsav")?;
+        let me2_leg_save_game: Me2LegSaveGame = unreal::Deserializer::from_bytes(&input)?;
+        let coverage = to_coverage(&me2_leg_save_game)?;
+        assert_eq!(coverage.opaque, 28);
+        assert!(coverage.known_ratio() > 0.99);
+
+        let regions = to_opaque_regions(&me2_leg_save_game)?;
+        assert_eq!(regions.len(), 1);
+        assert_eq!(regions[0].field, "_unknown");
+        assert_eq!(regions[0].bytes.len(), 28);
+
+        // No man's land et WorldSavePackage
+        let input = fs::read("test/Clare00_AutoSave.MassEffectSave")?;
+        let me1_save_game: Me1SaveGame = unreal::Deserializer::from_bytes(&input)?;
+        let coverage = me1_save_game.coverage()?;
+        assert!(coverage.opaque > me1_save_game.no_mans_land().len());
+        assert!(coverage.known_ratio() > 0.0 && coverage.known_ratio() < 1.0);
+        Ok(())
+    }
+}
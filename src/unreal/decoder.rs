@@ -0,0 +1,157 @@
+use super::OpaqueRegion;
+
+// Au-delà, plus probablement des octets quelconques qu'une chaîne
+const MAX_STRING_LEN: i32 = 0x100;
+
+// Lecture d'une région opaque, à implémenter pour avancer le reverse sans toucher au reste
+pub trait FieldDecoder: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    // `field` : `_unknown`, `_no_mans_land`, `WorldSavePackage.sav`...
+    fn accepts(&self, field: &str) -> bool;
+
+    // Une ligne par valeur trouvée, vide si rien n'est reconnu
+    fn decode(&self, bytes: &[u8]) -> Vec<String>;
+}
+
+pub struct DecodedRegion {
+    pub field: String,
+    pub len: usize,
+    pub outputs: Vec<(&'static str, Vec<String>)>,
+}
+
+pub struct Decoders(Vec<Box<dyn FieldDecoder>>);
+
+// Décodeurs intégrés, les nouveaux s'ajoutent ici
+impl Default for Decoders {
+    fn default() -> Self {
+        Decoders(vec![Box::new(StringDecoder)])
+    }
+}
+
+impl Decoders {
+    pub fn register(&mut self, decoder: Box<dyn FieldDecoder>) {
+        self.0.push(decoder);
+    }
+
+    pub fn decode(&self, regions: &[OpaqueRegion]) -> Vec<DecodedRegion> {
+        regions
+            .iter()
+            .map(|OpaqueRegion { field, bytes }| {
+                let outputs = self
+                    .0
+                    .iter()
+                    .filter(|decoder| decoder.accepts(field))
+                    .map(|decoder| (decoder.name(), decoder.decode(bytes)))
+                    .filter(|(_, lines)| !lines.is_empty())
+                    .collect();
+                DecodedRegion { field: field.clone(), len: bytes.len(), outputs }
+            })
+            .collect()
+    }
+}
+
+// FString ASCII : longueur i32 (`\0` compris) puis les caractères
+pub struct StringDecoder;
+
+impl FieldDecoder for StringDecoder {
+    fn name(&self) -> &'static str {
+        "Strings"
+    }
+
+    fn accepts(&self, _: &str) -> bool {
+        true
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Vec<String> {
+        let mut lines = Vec::new();
+        let mut offset = 0;
+        while offset + 4 < bytes.len() {
+            let mut len_bytes = [0u8; 4];
+            len_bytes.copy_from_slice(&bytes[offset..offset + 4]);
+            let len = i32::from_le_bytes(len_bytes);
+
+            let string = match len {
+                2..=MAX_STRING_LEN if offset + 4 + len as usize <= bytes.len() => {
+                    let chars = &bytes[offset + 4..offset + 4 + len as usize];
+                    match chars.split_last() {
+                        Some((0, chars))
+                            if chars.iter().all(|c| c.is_ascii_graphic() || *c == b' ') =>
+                        {
+                            Some(String::from_utf8_lossy(chars).into_owned())
+                        }
+                        _ => None,
+                    }
+                }
+                _ => None,
+            };
+
+            match string {
+                Some(string) => {
+                    lines.push(format!("0x{:X} : \"{}\"", offset, string));
+                    offset += 4 + len as usize;
+                }
+                None => offset += 1,
+            }
+        }
+        lines
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn fstring(string: &str) -> Vec<u8> {
+        let mut bytes = (string.len() as i32 + 1).to_le_bytes().to_vec();
+        bytes.extend(string.as_bytes());
+        bytes.push(0);
+        bytes
+    }
+
+    struct LenDecoder;
+
+    impl FieldDecoder for LenDecoder {
+        fn name(&self) -> &'static str {
+            "Length"
+        }
+
+        fn accepts(&self, field: &str) -> bool {
+            field == "_unknown"
+        }
+
+        fn decode(&self, bytes: &[u8]) -> Vec<String> {
+            vec![format!("{} bytes", bytes.len())]
+        }
+    }
+
+    #[test]
+    fn registered_decoders() {
+        let mut bytes = vec![0xFF, 0x01];
+        bytes.extend(fstring("BIOA_NOR"));
+        bytes.extend(&[0; 3]);
+        bytes.extend(fstring("Shepard"));
+        let regions = [
+            OpaqueRegion { field: "_unknown".to_owned(), bytes: bytes.clone() },
+            OpaqueRegion { field: "_osef".to_owned(), bytes: vec![0; 8] },
+        ];
+
+        let mut decoders = Decoders::default();
+        decoders.register(Box::new(LenDecoder));
+        let decoded = decoders.decode(&regions);
+
+        // Les deux décodeurs sur `_unknown`
+        assert_eq!(decoded[0].len, bytes.len());
+        assert_eq!(
+            decoded[0].outputs,
+            [
+                ("Strings", vec!["0x2 : \"BIOA_NOR\"".to_owned(), "0x12 : \"Shepard\"".to_owned()]),
+                ("Length", vec![format!("{} bytes", bytes.len())]),
+            ]
+        );
+
+        // Rien de reconnu, `LenDecoder` ne s'applique pas
+        assert_eq!(decoded[1].field, "_osef");
+        assert!(decoded[1].outputs.is_empty());
+    }
+}
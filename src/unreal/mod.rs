@@ -7,6 +7,15 @@ pub use serializer::*;
 mod deserializer;
 pub use deserializer::*;
 
+mod schema;
+pub use schema::*;
+
+mod coverage;
+pub use coverage::*;
+
+mod decoder;
+pub use decoder::*;
+
 pub type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Clone, Debug, PartialEq)]
@@ -0,0 +1,378 @@
+use serde::{ser, Serialize};
+use serde_json::{Map, Value};
+
+use super::{Result, Serializer};
+
+// Structure d'une sauvegarde : noms et types des champs, avec leur position dans le fichier
+// quand le format n'est pas compressé. Les listes ne décrivent que leur premier élément.
+pub fn to_schema<T>(value: &T, offsets: bool) -> Result<Value>
+where
+    T: ?Sized + Serialize,
+{
+    value.serialize(SchemaSerializer { offset: if offsets { Some(0) } else { None } })
+}
+
+fn node(kind: &str, offset: Option<usize>) -> Map<String, Value> {
+    let mut node = Map::new();
+    node.insert("type".to_owned(), Value::from(kind));
+    if let Some(offset) = offset {
+        node.insert("offset".to_owned(), Value::from(offset));
+    }
+    node
+}
+
+// Taille une fois sérialisé, pour placer le champ suivant
+fn advance<T>(offset: Option<usize>, value: &T) -> Result<Option<usize>>
+where
+    T: ?Sized + Serialize,
+{
+    match offset {
+        Some(offset) => Ok(Some(offset + Serializer::to_byte_buf(value)?.len())),
+        None => Ok(None),
+    }
+}
+
+pub struct SchemaSerializer {
+    offset: Option<usize>,
+}
+
+macro_rules! impl_primitive {
+    ($ser_method:ident($type:ty)) => {
+        fn $ser_method(self, _: $type) -> Result<Value> {
+            Ok(Value::Object(node(stringify!($type), self.offset)))
+        }
+    };
+}
+
+impl ser::Serializer for SchemaSerializer {
+    type Ok = Value;
+    type Error = super::Error;
+
+    type SerializeSeq = SchemaSeq;
+    type SerializeTuple = SchemaFields;
+    type SerializeTupleStruct = SchemaFields;
+    type SerializeTupleVariant = SchemaFields;
+    type SerializeMap = SchemaMap;
+    type SerializeStruct = SchemaFields;
+    type SerializeStructVariant = SchemaFields;
+
+    impl_primitive!(serialize_bool(bool));
+    impl_primitive!(serialize_i8(i8));
+    impl_primitive!(serialize_i16(i16));
+    impl_primitive!(serialize_i32(i32));
+    impl_primitive!(serialize_i64(i64));
+    impl_primitive!(serialize_u8(u8));
+    impl_primitive!(serialize_u16(u16));
+    impl_primitive!(serialize_u32(u32));
+    impl_primitive!(serialize_u64(u64));
+    impl_primitive!(serialize_f32(f32));
+    impl_primitive!(serialize_f64(f64));
+    impl_primitive!(serialize_char(char));
+
+    fn serialize_str(self, _: &str) -> Result<Value> {
+        Ok(Value::Object(node("string", self.offset)))
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<Value> {
+        let mut node = node("bytes", self.offset);
+        node.insert("len".to_owned(), Value::from(value.len()));
+        Ok(Value::Object(node))
+    }
+
+    fn serialize_none(self) -> Result<Value> {
+        Ok(Value::Object(node("none", self.offset)))
+    }
+
+    fn serialize_some<T: ?Sized>(self, value: &T) -> Result<Value>
+    where
+        T: Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Value> {
+        Ok(Value::Object(node("unit", self.offset)))
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Value> {
+        let mut node = node("unit", self.offset);
+        node.insert("name".to_owned(), Value::from(name));
+        Ok(Value::Object(node))
+    }
+
+    fn serialize_unit_variant(
+        self, name: &'static str, _: u32, variant: &'static str,
+    ) -> Result<Value> {
+        let mut node = node("enum", self.offset);
+        node.insert("name".to_owned(), Value::from(name));
+        node.insert("variant".to_owned(), Value::from(variant));
+        Ok(Value::Object(node))
+    }
+
+    fn serialize_newtype_struct<T: ?Sized>(self, name: &'static str, value: &T) -> Result<Value>
+    where
+        T: Serialize,
+    {
+        let mut inner = value.serialize(self)?;
+        if let Value::Object(node) = &mut inner {
+            node.entry("name").or_insert_with(|| Value::from(name));
+        }
+        Ok(inner)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized>(
+        self, name: &'static str, _: u32, variant: &'static str, value: &T,
+    ) -> Result<Value>
+    where
+        T: Serialize,
+    {
+        let mut inner = value.serialize(self)?;
+        if let Value::Object(node) = &mut inner {
+            node.entry("name").or_insert_with(|| Value::from(name));
+            node.insert("variant".to_owned(), Value::from(variant));
+        }
+        Ok(inner)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SchemaSeq> {
+        // La taille est préfixée quand elle est connue
+        let items_offset = match len {
+            Some(_) => self.offset.map(|offset| offset + 4),
+            None => self.offset,
+        };
+        Ok(SchemaSeq { offset: self.offset, items_offset, len: 0, items: None })
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<SchemaFields> {
+        Ok(SchemaFields::new("tuple", None, self.offset))
+    }
+
+    fn serialize_tuple_struct(self, name: &'static str, _: usize) -> Result<SchemaFields> {
+        Ok(SchemaFields::new("tuple", Some(name.to_owned()), self.offset))
+    }
+
+    fn serialize_tuple_variant(
+        self, name: &'static str, _: u32, variant: &'static str, _: usize,
+    ) -> Result<SchemaFields> {
+        Ok(SchemaFields::new("tuple", Some(format!("{}::{}", name, variant)), self.offset))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<SchemaMap> {
+        let entries_offset = match len {
+            Some(_) => self.offset.map(|offset| offset + 4),
+            None => self.offset,
+        };
+        Ok(SchemaMap {
+            offset: self.offset,
+            entries_offset,
+            len: 0,
+            keys: None,
+            values: None,
+            values_offset: None,
+        })
+    }
+
+    fn serialize_struct(self, name: &'static str, _: usize) -> Result<SchemaFields> {
+        Ok(SchemaFields::new("struct", Some(name.to_owned()), self.offset))
+    }
+
+    fn serialize_struct_variant(
+        self, name: &'static str, _: u32, variant: &'static str, _: usize,
+    ) -> Result<SchemaFields> {
+        Ok(SchemaFields::new("struct", Some(format!("{}::{}", name, variant)), self.offset))
+    }
+
+    // Même représentation que le `Serializer` binaire
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+pub struct SchemaSeq {
+    offset: Option<usize>,
+    items_offset: Option<usize>,
+    len: usize,
+    items: Option<Value>,
+}
+
+impl ser::SerializeSeq for SchemaSeq {
+    type Ok = Value;
+    type Error = super::Error;
+
+    fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize,
+    {
+        if self.items.is_none() {
+            self.items = Some(value.serialize(SchemaSerializer { offset: self.items_offset })?);
+        }
+        self.len += 1;
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value> {
+        let mut node = node("array", self.offset);
+        node.insert("len".to_owned(), Value::from(self.len));
+        node.insert("items".to_owned(), self.items.unwrap_or(Value::Null));
+        Ok(Value::Object(node))
+    }
+}
+
+pub struct SchemaMap {
+    offset: Option<usize>,
+    entries_offset: Option<usize>,
+    len: usize,
+    keys: Option<Value>,
+    values: Option<Value>,
+    values_offset: Option<usize>,
+}
+
+impl ser::SerializeMap for SchemaMap {
+    type Ok = Value;
+    type Error = super::Error;
+
+    fn serialize_key<T: ?Sized>(&mut self, key: &T) -> Result<()>
+    where
+        T: Serialize,
+    {
+        if self.keys.is_none() {
+            self.keys = Some(key.serialize(SchemaSerializer { offset: self.entries_offset })?);
+            self.values_offset = advance(self.entries_offset, key)?;
+        }
+        self.len += 1;
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize,
+    {
+        if self.values.is_none() {
+            self.values = Some(value.serialize(SchemaSerializer { offset: self.values_offset })?);
+        }
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value> {
+        let mut node = node("map", self.offset);
+        node.insert("len".to_owned(), Value::from(self.len));
+        node.insert("keys".to_owned(), self.keys.unwrap_or(Value::Null));
+        node.insert("values".to_owned(), self.values.unwrap_or(Value::Null));
+        Ok(Value::Object(node))
+    }
+}
+
+// Structs et tuples, les champs sont dans l'ordre du fichier
+pub struct SchemaFields {
+    node: Map<String, Value>,
+    next_offset: Option<usize>,
+    fields: Vec<Value>,
+}
+
+impl SchemaFields {
+    fn new(kind: &str, name: Option<String>, offset: Option<usize>) -> Self {
+        let mut node = node(kind, offset);
+        if let Some(name) = name {
+            node.insert("name".to_owned(), Value::from(name));
+        }
+        SchemaFields { node, next_offset: offset, fields: Vec::new() }
+    }
+
+    fn push<T: ?Sized>(&mut self, field: Option<&'static str>, value: &T) -> Result<()>
+    where
+        T: Serialize,
+    {
+        let mut schema = value.serialize(SchemaSerializer { offset: self.next_offset })?;
+        if let (Some(field), Value::Object(node)) = (field, &mut schema) {
+            node.insert("field".to_owned(), Value::from(field));
+        }
+        self.fields.push(schema);
+        self.next_offset = advance(self.next_offset, value)?;
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<Value> {
+        self.node.insert("fields".to_owned(), Value::Array(self.fields));
+        Ok(Value::Object(self.node))
+    }
+}
+
+impl ser::SerializeStruct for SchemaFields {
+    type Ok = Value;
+    type Error = super::Error;
+
+    fn serialize_field<T: ?Sized>(&mut self, field: &'static str, value: &T) -> Result<()>
+    where
+        T: Serialize,
+    {
+        self.push(Some(field), value)
+    }
+
+    fn end(self) -> Result<Value> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for SchemaFields {
+    type Ok = Value;
+    type Error = super::Error;
+
+    fn serialize_field<T: ?Sized>(&mut self, field: &'static str, value: &T) -> Result<()>
+    where
+        T: Serialize,
+    {
+        self.push(Some(field), value)
+    }
+
+    fn end(self) -> Result<Value> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for SchemaFields {
+    type Ok = Value;
+    type Error = super::Error;
+
+    fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize,
+    {
+        self.push(None, value)
+    }
+
+    fn end(self) -> Result<Value> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for SchemaFields {
+    type Ok = Value;
+    type Error = super::Error;
+
+    fn serialize_field<T: ?Sized>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize,
+    {
+        self.push(None, value)
+    }
+
+    fn end(self) -> Result<Value> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for SchemaFields {
+    type Ok = Value;
+    type Error = super::Error;
+
+    fn serialize_field<T: ?Sized>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize,
+    {
+        self.push(None, value)
+    }
+
+    fn end(self) -> Result<Value> {
+        self.finish()
+    }
+}
@@ -14,7 +14,7 @@ impl Serializer {
     #[allow(clippy::wrong_self_convention)]
     pub fn to_byte_buf<T>(value: &T) -> Result<Vec<u8>>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         let mut serializer = Serializer { output: Vec::new() };
         value.serialize(&mut serializer)?;